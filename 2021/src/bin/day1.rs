@@ -1,7 +1,10 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming, Unsolved},
+};
 use std::collections::VecDeque;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -10,32 +13,24 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-fn count_increments_with_sliding_window(reader: impl BufRead) -> Result<i32, std::io::Error> {
+fn depth(line: &str) -> Result<i32, ParseError> {
+    line.parse()
+        .map_err(|_| ParseError::new(line, line, "a depth"))
+}
+
+fn count_increments_with_sliding_window(depths: impl IntoIterator<Item = i32>) -> i32 {
     let window_size = 3;
     let mut increments = 0;
     let mut last_number: i32;
     let mut window = VecDeque::new();
-    let mut lines = reader.lines();
-    for line in &mut lines {
-        let next_number = line
-            .as_ref()
-            .expect("Could not read next line due to IO error")
-            .parse::<i32>()
-            .expect("Could not parse number");
+    let mut depths = depths.into_iter();
+    for next_number in &mut depths {
         window.push_front(next_number);
         if window.len() == window_size {
             break;
         }
     }
-    for line in lines {
-        let next_line = line
-            .as_ref()
-            .expect("Could not read next line due to IO error");
-        if next_line == "" {
-            // handle last line
-            break;
-        }
-        let next_number = next_line.parse::<i32>().expect("Could not parse number");
+    for next_number in depths {
         last_number = window.iter().fold(0, |a, x| a + x);
         window.push_front(next_number);
         let _ = window.pop_back();
@@ -43,17 +38,40 @@ fn count_increments_with_sliding_window(reader: impl BufRead) -> Result<i32, std
         if window_sum > last_number {
             increments += 1;
         }
-        println!("last : {}", last_number);
-        println!("window : {}", window_sum);
     }
-    Ok(increments)
+    increments
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<i32>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), depth).collect()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        count_increments_with_sliding_window(input.iter().copied())
+    }
 }
+
+impl Streaming for Day1 {
+    type Line = i32;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        depth(line)
+    }
+
+    fn answers(depths: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        (Unsolved, count_increments_with_sliding_window(depths))
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
-    let file = File::open(args.path.as_path()).unwrap();
-    let buf_reader = BufReader::new(file);
-    println!(
-        "{}",
-        count_increments_with_sliding_window(buf_reader).unwrap()
-    );
+    let buf_reader = input::open(args.path.as_path()).unwrap();
+    // the depths are streamed, stopping at the first bad line
+    let depths = parse_lines(buf_reader, depth).map(or_exit);
+    println!("{}", count_increments_with_sliding_window(depths));
 }
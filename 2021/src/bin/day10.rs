@@ -1,4 +1,5 @@
-use std::fs;
+use advent_of_code::{input, parse::ParseError, solution::Solution};
+use std::io::BufRead;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -7,7 +8,7 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-enum Line {
+pub enum Line {
     Corrupt(char),
     Incomplete(Vec<char>),
 }
@@ -69,39 +70,78 @@ fn parse_corrupt(source: &str) -> Line {
     )
 }
 
-fn main() {
-    let args = Cli::from_args();
-    let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let illegal_score = source
-        .trim()
-        .split('\n')
-        .filter_map(|line| match parse_corrupt(line) {
-            Line::Corrupt(c) => Some(c),
-            Line::Incomplete(_) => None,
-        })
-        .map(|x| match x {
-            ')' => 3,
-            ']' => 57,
-            '}' => 1197,
-            '>' => 25137,
-            _ => panic!("Unexpected"),
-        })
-        .fold(0, |a, x| a + x);
-    println!("illegal score = {}", illegal_score);
-
-    let mut incomplete_scores = source
-        .trim()
-        .split('\n')
-        .filter_map(|line| match parse_corrupt(line) {
-            Line::Corrupt(_) => None,
-            Line::Incomplete(c) => Some(score_incomplete_sequence(c)),
-        })
-        .collect::<Vec<u64>>();
+fn corrupt_score(c: &char) -> u64 {
+    match c {
+        ')' => 3,
+        ']' => 57,
+        '}' => 1197,
+        '>' => 25137,
+        _ => panic!("Unexpected"),
+    }
+}
+
+fn middle_score(mut incomplete_scores: Vec<u64>) -> u64 {
     incomplete_scores.sort();
-    let total_incomplete_score = incomplete_scores
+    *incomplete_scores
         .get((incomplete_scores.len() - 1) / 2)
-        .unwrap();
-    println!("total incomplete score: {}", total_incomplete_score);
+        .unwrap()
+}
+
+// Scores the lines as they are read, only the completion scores are kept for the middle one
+fn scores(input: impl BufRead) -> (u64, u64) {
+    let mut corrupt = 0;
+    let mut incomplete_scores = Vec::new();
+    for line in input.lines() {
+        let line = line.expect("Could not read next line due to IO error");
+        if line.is_empty() {
+            continue;
+        }
+        match parse_corrupt(&line) {
+            Line::Corrupt(c) => corrupt += corrupt_score(&c),
+            Line::Incomplete(c) => incomplete_scores.push(score_incomplete_sequence(c)),
+        }
+    }
+    (corrupt, middle_score(incomplete_scores))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(input.trim().split('\n').map(parse_corrupt).collect())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        input
+            .iter()
+            .filter_map(|line| match line {
+                Line::Corrupt(c) => Some(c),
+                Line::Incomplete(_) => None,
+            })
+            .map(corrupt_score)
+            .sum::<u64>()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        middle_score(
+            input
+                .iter()
+                .filter_map(|line| match line {
+                    Line::Corrupt(_) => None,
+                    Line::Incomplete(c) => Some(score_incomplete_sequence(c.clone())),
+                })
+                .collect(),
+        )
+    }
+}
+
+fn main() {
+    let args = Cli::from_args();
+    let (illegal, incomplete) = scores(input::open(args.path.as_path()).unwrap());
+    println!("illegal score = {}", illegal);
+    println!("total incomplete score: {}", incomplete);
 }
 
 #[cfg(test)]
@@ -193,4 +233,19 @@ mod tests {
         let total = scores.get((scores.len() - 1) / 2).unwrap();
         assert_eq!(*total, 288957);
     }
+
+    #[test]
+    fn test_scores() {
+        let example = "[({(<(())[]>[[{[]{<()<>>\n\
+                       [(()[<>])]({[<{<<[]>>(\n\
+                       {([(<{}[<>[]}>{[]{[(<()>\n\
+                       (((({<>}<{<{<>}{[]{[]{}\n\
+                       [[<[([]))<([[{}[[()]]]\n\
+                       [{[{({}]{}}([{[{{{}}([]\n\
+                       {<[[]]>}<{[{[{[]{()[[[]\n\
+                       [<(<(<(<{}))><([]([]()\n\
+                       <{([([[(<>()){}]>(<<{{\n\
+                       <{([{{}}[<[[[<>{}]]]>[]]\n";
+        assert_eq!(scores(example.as_bytes()), (26397, 288957));
+    }
 }
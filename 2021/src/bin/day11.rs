@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::collections::BTreeMap;
 use std::fs;
 use structopt::StructOpt;
@@ -80,16 +84,34 @@ fn search_light_up(mut grid: Grid) -> u32 {
     }
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input<'a> = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (_, flashes) = step_rounds(input.clone(), 100);
+        flashes
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search_light_up(input.clone())
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(Day11::parse(&source));
     let grid = parse_input(&source);
     let (_, flashes) = step_rounds(grid, args.steps);
     println!("flashes: {}", flashes);
 
-    let grid = parse_input(&source);
-    let steps = search_light_up(grid);
-    println!("steps taken: {}", steps);
+    println!("steps taken: {}", Day11::part2(&input));
 }
 
 #[cfg(test)]
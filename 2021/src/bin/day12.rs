@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use regex::Regex;
 use std::collections::BTreeSet;
 use std::fs;
@@ -9,7 +13,7 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-struct Network<'a> {
+pub struct Network<'a> {
     edges: BTreeSet<(Cave<'a>, Cave<'a>)>,
 }
 
@@ -140,11 +144,6 @@ fn search_network_with_revisit<'a>(
         }
         // Loop detection
         if paths_since_small_room(visited_paths.as_ref()).contains(target) {
-            println!(
-                "paths_since {:?}",
-                paths_since_small_room(visited_paths.as_ref())
-            );
-            println!("{:?}   {:?}", visited_paths, target);
             continue;
         }
         if let Cave::Small(_) = target {
@@ -194,16 +193,30 @@ fn search_network<'a>(visited_paths: &[Cave<'a>], network: &'a Network, final_ta
     valid_paths
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input<'a> = Network<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_network(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search_network(&[START], input, END)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search_network_with_revisit(&vec![START].as_slice().into(), input, END)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let network = parse_network(&source);
-    let start = Cave::new("start");
-    let end = Cave::new("end");
-    let valid_paths = search_network(&vec![start], &network, end);
-    println!("Possible paths: {}", valid_paths);
-    let valid_paths = search_network_with_revisit(&vec![start].as_slice().into(), &network, end);
-    println!("Paths with revisit: {}", valid_paths);
+    let input = or_exit(Day12::parse(&source));
+    println!("Possible paths: {}", Day12::part1(&input));
+    println!("Paths with revisit: {}", Day12::part2(&input));
 }
 
 #[cfg(test)]
@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use regex::Regex;
 use std::cmp::max;
 use std::collections::BTreeSet;
@@ -11,13 +15,13 @@ struct Cli {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
-enum FoldInstruction {
+pub enum FoldInstruction {
     Left(i32),
     Up(i32),
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct Dot(i32, i32);
+pub struct Dot(i32, i32);
 
 fn parse_dots(source: &str) -> BTreeSet<Dot> {
     let mut v = BTreeSet::new();
@@ -80,17 +84,36 @@ fn visualize_dots(dots: &BTreeSet<Dot>) -> String {
     s
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input<'a> = (BTreeSet<Dot>, Vec<FoldInstruction>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (dots, fi) = input;
+        fold(fi[0], dots).len()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (dots, fi) = input;
+        let mut dots = dots.clone();
+        for f in fi {
+            dots = fold(*f, &dots);
+        }
+        visualize_dots(&dots)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let (mut dots, fi) = parse_input(&source);
-    let first_fold = fold(fi[0], &dots);
-    println!("dots after first fold: {}", first_fold.len());
-
-    for f in fi {
-        dots = fold(f, &dots);
-    }
-    println!("{}", visualize_dots(&dots));
+    let input = or_exit(Day13::parse(&source));
+    println!("dots after first fold: {}", Day13::part1(&input));
+    println!("{}", Day13::part2(&input));
 }
 
 #[cfg(test)]
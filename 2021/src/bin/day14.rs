@@ -1,3 +1,4 @@
+use advent_of_code::{parse::ParseError, solution::Solution};
 use regex::Regex;
 use std::collections::BTreeMap;
 use std::fs;
@@ -10,7 +11,7 @@ struct Cli {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-struct Rule {
+pub struct Rule {
     doublet: [char; 2],
     out: char,
 }
@@ -117,6 +118,32 @@ fn apply_rules(rules: &[Rule], state: &mut Polymer) {
     }
 }
 
+fn polymerise((s, rules): &(&str, Vec<Rule>), steps: usize) -> u64 {
+    let mut state = Polymer::new(s);
+    for _ in 0..steps {
+        apply_rules(rules, &mut state);
+    }
+    state.most_common().1 - state.least_common().1
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input<'a> = (&'a str, Vec<Rule>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        polymerise(input, 10)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        polymerise(input, 40)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
@@ -196,14 +223,12 @@ mod tests {
             out: 'B',
         };
         apply_rules(&[rule], &mut state);
-        let doublet_exp = BTreeMap::from_iter(
-            [
-                (['N', 'N'], 1),
-                (['N', 'B'], 1),
-                (['B', 'C'], 1),
-                (['C', 'B'], 1),
-            ],
-        );
+        let doublet_exp = BTreeMap::from_iter([
+            (['N', 'N'], 1),
+            (['N', 'B'], 1),
+            (['B', 'C'], 1),
+            (['C', 'B'], 1),
+        ]);
         let element_exp = BTreeMap::from_iter([('N', 2), ('B', 2), ('C', 1)]);
         assert_eq!(state.doublets, doublet_exp);
         assert_eq!(state.elements, element_exp);
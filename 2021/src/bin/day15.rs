@@ -1,10 +1,10 @@
-
-use std::cmp::Reverse;
+use advent_of_code::search::{dijkstra, Path};
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::collections::BTreeMap;
-use std::collections::BTreeSet;
-use std::collections::BinaryHeap;
 use std::fs;
-use std::rc::Rc;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -20,12 +20,7 @@ fn corner(grid: &Grid) -> (i32, i32) {
     *corner.0
 }
 
-#[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Clone)]
-struct SearchState {
-    total_risk: u32,
-    pos: (i32, i32),
-    prev: Option<Rc<SearchState>>,
-}
+const DIRECTIONS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
 
 fn parse_input(source: &str) -> Grid {
     let mut result = BTreeMap::new();
@@ -37,24 +32,6 @@ fn parse_input(source: &str) -> Grid {
     result
 }
 
-fn flatten_search_state(state: Rc<SearchState>) -> Vec<((i32, i32), u32)> {
-    let mut result = Vec::new();
-    let mut current_state = &Some(state);
-    loop {
-        match current_state {
-            Some(s) => {
-                result.push((s.pos, s.total_risk));
-                current_state = &s.prev;
-            }
-            None => {
-                break;
-            }
-        }
-    }
-    result.reverse();
-    result
-}
-
 #[allow(dead_code)]
 fn visualize(grid: &Grid) -> String {
     let (max_x, max_y) = corner(grid);
@@ -87,56 +64,45 @@ fn expand_map(grid: &Grid) -> Grid {
     result
 }
 
-fn risk_search(grid: &Grid) -> Vec<((i32, i32), u32)> {
-    let mut heap = BinaryHeap::new();
-    let mut visited = BTreeSet::new();
-    let start = Rc::new(SearchState {
-        total_risk: 0,
-        pos: (0, 0),
-        prev: None,
-    });
-    let mut path = Vec::new();
-    let corner = corner(&grid);
-    let mut k = 0;
-    heap.push(Reverse(start));
-    'outer: while let Some(Reverse(state)) = heap.pop() {
-        if visited.contains(&state.pos) {
-            continue;
-        }
-        for (i, j) in [(0, -1), (0, 1), (-1, 0), (1, 0)]{
-            let x = &state.pos.0 + &i;
-            let y = &state.pos.1 + &j;
-            if visited.contains(&(x, y)) {
-                continue;
-            }
-            if let Some(risk) = grid.get(&(x, y)) {
-                let probe = Rc::new(SearchState {
-                    total_risk: state.total_risk + *risk,
-                    pos: (x, y),
-                    prev: Some(state.clone()),
-                });
-                if (x, y) == corner {
-                    path = flatten_search_state(probe);
-                    break 'outer;
-                }
-                heap.push(Reverse(probe));
-                visited.insert(state.pos);
-            }
-        }
-        k = k + 1;
+fn risk_search(grid: &Grid) -> Path<(i32, i32), u32> {
+    let corner = corner(grid);
+    dijkstra(
+        (0, 0),
+        |&(x, y)| {
+            DIRECTIONS.iter().filter_map(move |(i, j)| {
+                let pos = (x + i, y + j);
+                grid.get(&pos).map(|risk| (pos, *risk))
+            })
+        },
+        |&pos| pos == corner,
+    )
+    .expect("no path to the corner")
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input<'a> = Grid;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        risk_search(input).cost
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        risk_search(&expand_map(input)).cost
     }
-    path
 }
 
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let grid = parse_input(&source);
-    let path = risk_search(&grid);
-    println!("minimum risk: {}", path[path.len() - 1].1);
-    let expanded_grid = expand_map(&grid);
-    let path = risk_search(&expanded_grid);
-    println!("minimum risk on expanded: {}", path[path.len() - 1].1);
+    let input = or_exit(Day15::parse(&source));
+    println!("minimum risk: {}", Day15::part1(&input));
+    println!("minimum risk on expanded: {}", Day15::part2(&input));
 }
 
 #[cfg(test)]
@@ -157,8 +123,10 @@ mod tests {
              1293138521\n\
              2311944581\n",
         );
-        let mut path = risk_search(&input);
-        assert_eq!(path.pop().unwrap().1, 40);
+        let path = risk_search(&input);
+        assert_eq!(path.cost, 40);
+        assert_eq!(path.nodes[0], (0, 0));
+        assert_eq!(path.goal(), &(9, 9));
     }
 
     #[test]
@@ -244,20 +212,4 @@ mod tests {
         let output = expand_map(&input);
         assert_eq!(output, exp);
     }
-
-    #[test]
-    fn test_search_state() {
-        let input = SearchState {
-            total_risk: 20,
-            pos: (0, 1),
-            prev: Some(Rc::new(SearchState {
-                total_risk: 10,
-                pos: (0, 0),
-                prev: None,
-            })),
-        };
-        let output = flatten_search_state(Rc::new(input));
-        assert_eq!(output[0], ((0, 0), 10));
-        assert_eq!(output[1], ((0, 1), 20));
-    }
 }
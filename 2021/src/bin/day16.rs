@@ -1,17 +1,41 @@
+use advent_of_code::{
+    arith::{self, ArithError, Arithmetic, BigInt, Integer},
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use byteorder::{BigEndian, ByteOrder};
-use hex::FromHex;
+use hex::{FromHex, FromHexError};
 use std::cmp;
 use std::convert::TryInto;
-use std::fmt::{Display, Error, Formatter};
+use std::fmt::{Display, Error, Formatter, Write};
 use std::fs;
+use std::process;
+use std::slice;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
 struct Cli {
-    #[structopt(parse(from_os_str))]
-    path: std::path::PathBuf,
+    #[structopt(parse(from_os_str), required_unless = "compile")]
+    path: Option<std::path::PathBuf>,
+    /// print the transmission re-encoded with length type 0 or 1
+    #[structopt(long)]
+    encode: Option<u8>,
+    /// print the transmission for an expression like `max(3, 4*5) > 7` instead
+    #[structopt(long)]
+    compile: Option<String>,
+    /// evaluate with `checked` u64s that report overflow or `big` integers
+    #[structopt(long, default_value = "checked")]
+    arithmetic: Arithmetic,
+    /// print the packets decoded before the first error instead of failing
+    #[structopt(long)]
+    lenient: bool,
+    /// print the decoded packets as an indented tree or an S-expression
+    #[structopt(long, possible_values = &["tree", "sexp"])]
+    disassemble: Option<String>,
 }
 
+// every variant hands out its operands as the same slice of boxes
+#[allow(clippy::vec_box)]
 #[derive(PartialEq, Eq, Debug, Clone)]
 enum Operation {
     Sum(Vec<Box<Packet>>),
@@ -21,6 +45,26 @@ enum Operation {
     GreaterThan([Box<Packet>; 2]),
     LessThan([Box<Packet>; 2]),
     EqualTo([Box<Packet>; 2]),
+    /// A comparison kept by the lenient decoder without exactly two operands,
+    /// with its type id and the operands it did read.
+    Unpaired(u8, Vec<Box<Packet>>),
+}
+
+impl Operation {
+    /// The same operators the expression compiler accepts.
+    fn symbol(&self) -> &'static str {
+        match self {
+            Operation::Sum(_) => "+",
+            Operation::Product(_) => "*",
+            Operation::Minimum(_) => "min",
+            Operation::Maximum(_) => "max",
+            Operation::GreaterThan(_) => ">",
+            Operation::LessThan(_) => "<",
+            Operation::EqualTo(_) | Operation::Unpaired(7, _) => "==",
+            Operation::Unpaired(5, _) => ">",
+            Operation::Unpaired(_, _) => "<",
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone)]
@@ -29,13 +73,45 @@ enum Payload {
     Operator(Operation),
 }
 
+impl Payload {
+    /// The type id this payload is written with.
+    fn type_id(&self) -> u8 {
+        match self {
+            Payload::Literal(_) => 4,
+            Payload::Operator(Operation::Sum(_)) => 0,
+            Payload::Operator(Operation::Product(_)) => 1,
+            Payload::Operator(Operation::Minimum(_)) => 2,
+            Payload::Operator(Operation::Maximum(_)) => 3,
+            Payload::Operator(Operation::GreaterThan(_)) => 5,
+            Payload::Operator(Operation::LessThan(_)) => 6,
+            Payload::Operator(Operation::EqualTo(_)) => 7,
+            Payload::Operator(Operation::Unpaired(type_id, _)) => *type_id,
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone)]
-struct Packet {
+pub struct Packet {
     version: u8,
     type_id: u8,
     payload: Payload,
 }
 
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        match &self.payload {
+            Payload::Literal(literal) => write!(f, "{}", literal),
+            Payload::Operator(operation) => {
+                write!(f, "({}", operation.symbol())?;
+                for packet in sub_packets(operation) {
+                    write!(f, " {}", packet)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
 struct BitSlice<'a> {
     bytes: &'a [u8],
@@ -124,7 +200,7 @@ fn parse_literal(mut stream: BitSlice) -> (u64, BitSlice) {
     (total, stream)
 }
 
-fn parse_length_type_0_packets(mut stream: BitSlice) -> (Vec<Box<Packet>>, BitSlice) {
+fn parse_length_type(stream: BitSlice) -> (usize, BitSlice) {
     let mut buffer = [0, 0, 0, 0];
     buffer[0] = stream.bytes[0];
     buffer[1] = *stream.bytes.get(1).unwrap_or(&0);
@@ -132,140 +208,222 @@ fn parse_length_type_0_packets(mut stream: BitSlice) -> (Vec<Box<Packet>>, BitSl
     buffer[3] = *stream.bytes.get(3).unwrap_or(&0);
 
     let extract = BigEndian::read_u32(&buffer);
-    let masked = (0b1111_1111_1111_1110_0000_0000_0000_0000 >> stream.offset) & extract;
-    let length = (masked >> ((32 - 15) - stream.offset)) as usize;
-    stream = stream.advance(15);
-    let mut packets = Vec::new();
-    assert!(stream.len() >= length);
-    let old_stream_len = stream.len();
-    while old_stream_len - stream.len() != length {
-        let result = parse_packet(stream);
-        packets.push(Box::new(result.0));
-        stream = result.1;
+    let masked = (0b1000_0000_0000_0000_0000_0000_0000_0000 >> stream.offset) & extract;
+    let length_type = (masked >> ((32 - 1) - stream.offset)) as usize;
+    let stream = stream.advance(1);
+    (length_type, stream)
+}
+
+/// The part of a packet being read when a transmission ran out of bits or
+/// didn't add up.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum Field {
+    Version,
+    TypeId,
+    LiteralGroup,
+    LiteralSize,
+    LengthType,
+    Length,
+    Count,
+    SubPackets,
+    Operands,
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        f.write_str(match self {
+            Field::Version => "a version",
+            Field::TypeId => "a type id",
+            Field::LiteralGroup => "a literal group",
+            Field::LiteralSize => "a literal of at most 16 groups",
+            Field::LengthType => "a length type",
+            Field::Length => "a sub-packet length",
+            Field::Count => "a sub-packet count",
+            Field::SubPackets => "sub-packets ending at their length",
+            Field::Operands => "two operands for a comparison",
+        })
     }
-    (packets, stream)
 }
 
-fn parse_length_type_1_packets(mut stream: BitSlice) -> (Vec<Box<Packet>>, BitSlice) {
-    let mut buffer = [0, 0, 0, 0];
-    buffer[0] = stream.bytes[0];
-    buffer[1] = *stream.bytes.get(1).unwrap_or(&0);
-    buffer[2] = *stream.bytes.get(2).unwrap_or(&0);
-    buffer[3] = *stream.bytes.get(3).unwrap_or(&0);
+#[derive(PartialEq, Eq, Debug, Clone)]
+struct DecodeError {
+    /// bit offset from the start of the transmission
+    offset: usize,
+    field: Field,
+}
 
-    let extract = BigEndian::read_u32(&buffer);
-    let masked = (0b1111_1111_1110_0000_0000_0000_0000_0000 >> stream.offset) & extract;
-    let count = (masked >> ((32 - 11) - stream.offset)) as usize;
-    let mut packets = Vec::new();
-    stream = stream.advance(11);
-    for _ in 0..count {
-        let result = parse_packet(stream);
-        packets.push(Box::new(result.0));
-        stream = result.1;
+impl DecodeError {
+    /// Points at the hex digit holding the offending bit.
+    fn in_hex(&self, input: &str) -> ParseError {
+        let hex = input.trim();
+        let at = cmp::min(self.offset / 4, hex.len());
+        ParseError::new(input, &hex[at..], self.field.to_string())
     }
-    (packets, stream)
 }
 
-fn parse_length_type(stream: BitSlice) -> (usize, BitSlice) {
-    let mut buffer = [0, 0, 0, 0];
-    buffer[0] = stream.bytes[0];
-    buffer[1] = *stream.bytes.get(1).unwrap_or(&0);
-    buffer[2] = *stream.bytes.get(2).unwrap_or(&0);
-    buffer[3] = *stream.bytes.get(3).unwrap_or(&0);
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(f, "bit {}: expected {}", self.offset, self.field)
+    }
+}
 
-    let extract = BigEndian::read_u32(&buffer);
-    let masked = (0b1000_0000_0000_0000_0000_0000_0000_0000 >> stream.offset) & extract;
-    let length_type = (masked >> ((32 - 1) - stream.offset)) as usize;
-    let stream = stream.advance(1);
-    (length_type, stream)
+fn read_bits(mut stream: BitSlice, bits: usize) -> (u64, BitSlice) {
+    let mut number = 0;
+    for _ in 0..bits {
+        let bit = (stream.bytes[0] >> (7 - stream.offset)) & 1;
+        number = (number << 1) | bit as u64;
+        stream = stream.advance(1);
+    }
+    (number, stream)
 }
 
-fn parse_operator_packets(mut stream: BitSlice) -> (Vec<Box<Packet>>, BitSlice) {
-    let result = parse_length_type(stream);
-    let length_type = result.0;
-    stream = result.1;
-    let packets = match length_type {
-        0 => {
-            let result = parse_length_type_0_packets(stream);
-            stream = result.1;
-            result.0
+/// Checks there are enough bits left before every read. A lenient decoder
+/// keeps the sub-packets read before the first error instead of failing.
+struct Decoder {
+    total: usize,
+    lenient: bool,
+    error: Option<DecodeError>,
+}
+
+impl Decoder {
+    fn new(stream: BitSlice, lenient: bool) -> Self {
+        Decoder {
+            total: stream.len(),
+            lenient,
+            error: None,
         }
-        1 => {
-            let result = parse_length_type_1_packets(stream);
-            stream = result.1;
-            result.0
+    }
+    fn error(&self, stream: BitSlice, field: Field) -> DecodeError {
+        DecodeError {
+            offset: self.total - stream.len(),
+            field,
         }
-        _ => {
-            panic!("unexpected length type");
+    }
+    fn need(&self, stream: BitSlice, bits: usize, field: Field) -> Result<(), DecodeError> {
+        if stream.len() < bits {
+            return Err(self.error(stream, field));
         }
-    };
-    (packets, stream)
-}
-
-fn parse_packet(stream: BitSlice) -> (Packet, BitSlice) {
-    let (version, stream) = parse_version(stream);
-    let (type_id, mut stream) = parse_type_id(stream);
-    let payload = match type_id {
-        0 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::Sum(packets))
-        }
-        1 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::Product(packets))
-        }
-        2 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::Minimum(packets))
-        }
-        3 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::Maximum(packets))
-        }
-        4 => {
-            let result = parse_literal(stream);
-            let literal = result.0;
-            stream = result.1;
-            Payload::Literal(literal)
-        }
-        5 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::GreaterThan(packets.try_into().unwrap()))
-        }
-        6 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::LessThan(packets.try_into().unwrap()))
-        }
-        7 => {
-            let result = parse_operator_packets(stream);
-            let packets = result.0;
-            stream = result.1;
-            Payload::Operator(Operation::EqualTo(packets.try_into().unwrap()))
-        }
-        _ => {
-            panic!("Unexpected TypeID");
+        Ok(())
+    }
+    fn recover(&mut self, err: DecodeError) -> Result<(), DecodeError> {
+        if !self.lenient {
+            return Err(err);
         }
-    };
-    (
-        Packet {
-            version,
-            type_id,
-            payload,
-        },
-        stream,
-    )
+        self.error.get_or_insert(err);
+        Ok(())
+    }
+    fn literal<'a>(&self, mut stream: BitSlice<'a>) -> Result<(u64, BitSlice<'a>), DecodeError> {
+        let mut total: u64 = 0;
+        loop {
+            self.need(stream, 5, Field::LiteralGroup)?;
+            if total >> 60 != 0 {
+                return Err(self.error(stream, Field::LiteralSize));
+            }
+            let (group, rest) = read_bits(stream, 5);
+            total = (total << 4) | (group & 0b1111);
+            stream = rest;
+            if group & 0b1_0000 == 0 {
+                return Ok((total, stream));
+            }
+        }
+    }
+    fn operation<'a>(
+        &mut self,
+        type_id: u8,
+        start: BitSlice,
+        stream: BitSlice<'a>,
+    ) -> Result<(Operation, BitSlice<'a>), DecodeError> {
+        self.need(stream, 1, Field::LengthType)?;
+        let (length_type, stream) = parse_length_type(stream);
+        let mut packets = Vec::new();
+        let (packets, stream) = if length_type == 0 {
+            self.need(stream, 15, Field::Length)?;
+            let (length, mut stream) = read_bits(stream, 15);
+            let before = stream.len();
+            while before - stream.len() < length as usize && self.error.is_none() {
+                match self.packet(stream) {
+                    Ok((packet, rest)) => {
+                        packets.push(Box::new(packet));
+                        stream = rest;
+                    }
+                    Err(err) => self.recover(err)?,
+                }
+            }
+            if before - stream.len() > length as usize {
+                self.recover(self.error(stream, Field::SubPackets))?;
+            }
+            (packets, stream)
+        } else {
+            self.need(stream, 11, Field::Count)?;
+            let (count, mut stream) = read_bits(stream, 11);
+            for _ in 0..count {
+                if self.error.is_some() {
+                    break;
+                }
+                match self.packet(stream) {
+                    Ok((packet, rest)) => {
+                        packets.push(Box::new(packet));
+                        stream = rest;
+                    }
+                    Err(err) => self.recover(err)?,
+                }
+            }
+            (packets, stream)
+        };
+        let operation = match type_id {
+            0 => Operation::Sum(packets),
+            1 => Operation::Product(packets),
+            2 => Operation::Minimum(packets),
+            3 => Operation::Maximum(packets),
+            _ => match packets.try_into() {
+                Ok(operands) => match type_id {
+                    5 => Operation::GreaterThan(operands),
+                    6 => Operation::LessThan(operands),
+                    _ => Operation::EqualTo(operands),
+                },
+                Err(packets) => {
+                    self.recover(self.error(start, Field::Operands))?;
+                    Operation::Unpaired(type_id, packets)
+                }
+            },
+        };
+        Ok((operation, stream))
+    }
+    fn packet<'a>(&mut self, stream: BitSlice<'a>) -> Result<(Packet, BitSlice<'a>), DecodeError> {
+        let start = stream;
+        self.need(stream, 3, Field::Version)?;
+        let (version, stream) = parse_version(stream);
+        self.need(stream, 3, Field::TypeId)?;
+        let (type_id, stream) = parse_type_id(stream);
+        let (payload, stream) = if type_id == 4 {
+            let (literal, stream) = self.literal(stream)?;
+            (Payload::Literal(literal), stream)
+        } else {
+            let (operation, stream) = self.operation(type_id, start, stream)?;
+            (Payload::Operator(operation), stream)
+        };
+        Ok((
+            Packet {
+                version,
+                type_id,
+                payload,
+            },
+            stream,
+        ))
+    }
+}
+
+fn parse_packet(stream: BitSlice) -> Result<(Packet, BitSlice), DecodeError> {
+    Decoder::new(stream, false).packet(stream)
+}
+
+/// Whatever decodes before the first error, along with that error.
+fn parse_packet_lenient(stream: BitSlice) -> (Option<Packet>, Option<DecodeError>) {
+    let mut decoder = Decoder::new(stream, true);
+    match decoder.packet(stream) {
+        Ok((packet, _)) => (Some(packet), decoder.error),
+        Err(err) => (None, Some(decoder.error.unwrap_or(err))),
+    }
 }
 
 fn sum_version_numbers(packet: &Packet) -> u64 {
@@ -280,52 +438,480 @@ fn sum_version_numbers(packet: &Packet) -> u64 {
                 Operation::GreaterThan(v) => v.iter().fold(0, |a, x| a + sum_version_numbers(&x)),
                 Operation::LessThan(v) => v.iter().fold(0, |a, x| a + sum_version_numbers(&x)),
                 Operation::EqualTo(v) => v.iter().fold(0, |a, x| a + sum_version_numbers(&x)),
+                Operation::Unpaired(_, v) => v.iter().fold(0, |a, x| a + sum_version_numbers(x)),
             },
         }
 }
 
-fn eval(packet: &Packet) -> u64 {
-    match &packet.payload {
-        Payload::Literal(u) => *u,
-        Payload::Operator(u) => match u {
-            Operation::Sum(v) => v.iter().fold(0, |a, x| a + eval(&x)),
-            Operation::Product(v) => v.iter().fold(1, |a, x| a * eval(&x)),
-            Operation::Minimum(v) => v.iter().fold(u64::MAX, |a, x| cmp::min(a, eval(&x))),
-            Operation::Maximum(v) => v.iter().fold(0, |a, x| cmp::max(a, eval(&x))),
-            Operation::GreaterThan(v) => {
-                if eval(&v[0]) > eval(&v[1]) {
-                    1
-                } else {
-                    0
+fn eval<N: Integer + From<u64>>(packet: &Packet) -> Result<N, ArithError> {
+    eval_all(packet, &mut Vec::new())
+}
+
+/// Evaluates `packet` once, recording the value of it and of every packet
+/// inside it in pre-order.
+fn eval_all<N: Integer + From<u64>>(packet: &Packet, all: &mut Vec<N>) -> Result<N, ArithError> {
+    let at = all.len();
+    all.push(N::zero());
+    let value = match &packet.payload {
+        Payload::Literal(u) => N::from(*u),
+        Payload::Operator(u) => {
+            let values = sub_packets(u)
+                .iter()
+                .map(|x| eval_all(x, all))
+                .collect::<Result<Vec<N>, _>>()?;
+            let truth = |b: bool| if b { N::one() } else { N::zero() };
+            match u {
+                Operation::Sum(_) => values
+                    .iter()
+                    .try_fold(N::zero(), |a, x| arith::add(&a, x))?,
+                Operation::Product(_) => {
+                    values.iter().try_fold(N::one(), |a, x| arith::mul(&a, x))?
                 }
+                Operation::Minimum(_) => values.into_iter().min().unwrap_or_else(N::zero),
+                Operation::Maximum(_) => values.into_iter().max().unwrap_or_else(N::zero),
+                Operation::GreaterThan(_) => truth(values[0] > values[1]),
+                Operation::LessThan(_) => truth(values[0] < values[1]),
+                Operation::EqualTo(_) => truth(values[0] == values[1]),
+                Operation::Unpaired(_, _) => return Err(ArithError::Arity),
+            }
+        }
+    };
+    all[at] = value.clone();
+    Ok(value)
+}
+
+fn evaluate(packet: &Packet, arithmetic: Arithmetic) -> Result<String, ArithError> {
+    match arithmetic {
+        Arithmetic::Checked => eval::<u64>(packet).map(|value| value.to_string()),
+        Arithmetic::Big => eval::<BigInt>(packet).map(|value| value.to_string()),
+    }
+}
+
+fn from_hex(input: &str) -> Result<Vec<u8>, ParseError> {
+    let hex = input.trim();
+    FromHex::from_hex(hex).map_err(|err| match err {
+        FromHexError::InvalidHexCharacter { index, .. } => {
+            let at = hex.char_indices().nth(index).map_or(hex.len(), |(i, _)| i);
+            ParseError::new(input, &hex[at..], "a hex digit")
+        }
+        _ => ParseError::new(input, &hex[hex.len()..], "an even number of hex digits"),
+    })
+}
+
+fn decode(input: &str) -> Result<Packet, ParseError> {
+    let stream = from_hex(input)?;
+    parse_packet(BitSlice::new(&stream, 0))
+        .map(|(packet, _)| packet)
+        .map_err(|err| err.in_hex(input))
+}
+
+/// Writes `packet` and the packets inside it, taking their values in
+/// pre-order from `values`.
+fn disassemble_packet<'a>(
+    out: &mut String,
+    packet: &Packet,
+    values: &mut slice::Iter<BigInt>,
+    stream: BitSlice<'a>,
+    total: usize,
+    depth: usize,
+) -> BitSlice<'a> {
+    let value = values.next().unwrap();
+    let offset = total - stream.len();
+    let stream = stream.advance(6);
+    let indent = depth * 2;
+    match &packet.payload {
+        Payload::Literal(literal) => {
+            writeln!(
+                out,
+                "{:indent$}{}  [v{} t{} @{}]",
+                "",
+                literal,
+                packet.version,
+                packet.type_id,
+                offset,
+                indent = indent
+            )
+            .unwrap();
+            parse_literal(stream).1
+        }
+        Payload::Operator(operation) => {
+            let (length_type, stream) = parse_length_type(stream);
+            writeln!(
+                out,
+                "{:indent$}{} = {}  [v{} t{} l{} @{}]",
+                "",
+                operation.symbol(),
+                value,
+                packet.version,
+                packet.type_id,
+                length_type,
+                offset,
+                indent = indent
+            )
+            .unwrap();
+            let mut stream = stream.advance(if length_type == 0 { 15 } else { 11 });
+            for packet in sub_packets(operation) {
+                stream = disassemble_packet(out, packet, values, stream, total, depth + 1);
+            }
+            stream
+        }
+    }
+}
+
+/// One line per packet, with its value, version, type id, length type and
+/// bit offset into the transmission.
+fn disassemble(bytes: &[u8]) -> Result<String, DecodeError> {
+    let stream = BitSlice::new(bytes, 0);
+    let (packet, _) = parse_packet(stream)?;
+    let mut values = Vec::new();
+    eval_all::<BigInt>(&packet, &mut values).unwrap();
+    let mut out = String::new();
+    disassemble_packet(
+        &mut out,
+        &packet,
+        &mut values.iter(),
+        stream,
+        stream.len(),
+        0,
+    );
+    Ok(out)
+}
+
+/// Which of the two operator headers to write, the other is used when the
+/// sub-packets don't fit in it.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+enum LengthType {
+    /// length type 0, the total length of the sub-packets in bits
+    Bits,
+    /// length type 1, the number of sub-packets
+    Count,
+}
+
+/// An operator whose sub-packets are too many to count and too long to
+/// measure in either header.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+struct EncodeError {
+    sub_packets: usize,
+    bits: usize,
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        write!(
+            f,
+            "{} sub-packets in {} bits fit in neither length type",
+            self.sub_packets, self.bits
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    /// number of bits written so far
+    len: usize,
+}
+
+impl BitWriter {
+    fn new() -> Self {
+        Self::default()
+    }
+    fn push(&mut self, value: u64, bits: usize) {
+        for i in (0..bits).rev() {
+            self.push_bit((value >> i) & 1 == 1);
+        }
+    }
+    fn push_bit(&mut self, bit: bool) {
+        if self.len.is_multiple_of(8) {
+            self.bytes.push(0);
+        }
+        if bit {
+            self.bytes[self.len / 8] |= 0b1000_0000 >> (self.len % 8);
+        }
+        self.len += 1;
+    }
+    fn append(&mut self, other: &BitWriter) {
+        for i in 0..other.len {
+            self.push_bit(other.bytes[i / 8] & (0b1000_0000 >> (i % 8)) != 0);
+        }
+    }
+}
+
+fn sub_packets(operation: &Operation) -> &[Box<Packet>] {
+    match operation {
+        Operation::Sum(v)
+        | Operation::Product(v)
+        | Operation::Minimum(v)
+        | Operation::Maximum(v) => v,
+        Operation::GreaterThan(v) | Operation::LessThan(v) | Operation::EqualTo(v) => v,
+        Operation::Unpaired(_, v) => v,
+    }
+}
+
+fn write_literal(writer: &mut BitWriter, literal: u64) {
+    let nibbles = cmp::max(1, (64 - literal.leading_zeros() as usize).div_ceil(4));
+    for i in (0..nibbles).rev() {
+        writer.push_bit(i != 0);
+        writer.push(literal >> (i * 4), 4);
+    }
+}
+
+fn write_packet(
+    writer: &mut BitWriter,
+    packet: &Packet,
+    length_type: LengthType,
+) -> Result<(), EncodeError> {
+    writer.push(packet.version as u64, 3);
+    writer.push(packet.payload.type_id() as u64, 3);
+    match &packet.payload {
+        Payload::Literal(literal) => write_literal(writer, *literal),
+        Payload::Operator(operation) => {
+            let packets = sub_packets(operation);
+            let mut contents = BitWriter::new();
+            for packet in packets {
+                write_packet(&mut contents, packet, length_type)?;
             }
-            Operation::LessThan(v) => {
-                if eval(&v[0]) < eval(&v[1]) {
-                    1
-                } else {
-                    0
+            let fits_bits = contents.len < 1 << 15;
+            let fits_count = packets.len() < 1 << 11;
+            match length_type {
+                LengthType::Bits if fits_bits => {
+                    writer.push(0, 1);
+                    writer.push(contents.len as u64, 15);
+                }
+                _ if fits_count => {
+                    writer.push(1, 1);
+                    writer.push(packets.len() as u64, 11);
+                }
+                _ if fits_bits => {
+                    writer.push(0, 1);
+                    writer.push(contents.len as u64, 15);
+                }
+                _ => {
+                    return Err(EncodeError {
+                        sub_packets: packets.len(),
+                        bits: contents.len,
+                    })
                 }
             }
-            Operation::EqualTo(v) => {
-                if eval(&v[0]) == eval(&v[1]) {
-                    1
-                } else {
-                    0
+            writer.append(&contents);
+        }
+    }
+    Ok(())
+}
+
+fn encode_bits(packet: &Packet, length_type: LengthType) -> Result<BitWriter, EncodeError> {
+    let mut writer = BitWriter::new();
+    write_packet(&mut writer, packet, length_type)?;
+    Ok(writer)
+}
+
+/// The hex transmission for `packet`, zero padded to whole bytes.
+fn encode(packet: &Packet, length_type: LengthType) -> Result<String, EncodeError> {
+    encode_bits(packet, length_type).map(|bits| hex::encode_upper(bits.bytes))
+}
+
+fn operator(type_id: u8, packets: Vec<Packet>) -> Packet {
+    let packets: Vec<Box<Packet>> = packets.into_iter().map(Box::new).collect();
+    let operation = match type_id {
+        0 => Operation::Sum(packets),
+        1 => Operation::Product(packets),
+        2 => Operation::Minimum(packets),
+        3 => Operation::Maximum(packets),
+        5 => Operation::GreaterThan(packets.try_into().unwrap()),
+        6 => Operation::LessThan(packets.try_into().unwrap()),
+        7 => Operation::EqualTo(packets.try_into().unwrap()),
+        _ => panic!("Unexpected TypeID"),
+    };
+    Packet {
+        version: 0,
+        type_id,
+        payload: Payload::Operator(operation),
+    }
+}
+
+/// Recursive descent over `comparison := sum (("<" | ">" | "==") sum)?`,
+/// `sum := product ("+" product)*`, `product := atom ("*" atom)*` and
+/// `atom := number | "(" comparison ")" | name "(" comparison ("," comparison)* ")"`.
+struct Compiler<'a> {
+    text: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> Compiler<'a> {
+    fn error(&self, expected: &str) -> ParseError {
+        ParseError::new(self.text, self.rest, expected)
+    }
+    fn eat(&mut self, token: &str) -> bool {
+        self.rest = self.rest.trim_start();
+        match self.rest.strip_prefix(token) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+    fn expect(&mut self, token: &str) -> Result<(), ParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{}`", token)))
+        }
+    }
+    fn comparison(&mut self) -> Result<Packet, ParseError> {
+        let left = self.sum()?;
+        let type_id = if self.eat(">") {
+            5
+        } else if self.eat("<") {
+            6
+        } else if self.eat("==") {
+            7
+        } else {
+            return Ok(left);
+        };
+        let right = self.sum()?;
+        Ok(operator(type_id, vec![left, right]))
+    }
+    fn sum(&mut self) -> Result<Packet, ParseError> {
+        let mut terms = vec![self.product()?];
+        while self.eat("+") {
+            terms.push(self.product()?);
+        }
+        Ok(match terms.len() {
+            1 => terms.pop().unwrap(),
+            _ => operator(0, terms),
+        })
+    }
+    fn product(&mut self) -> Result<Packet, ParseError> {
+        let mut factors = vec![self.atom()?];
+        while self.eat("*") {
+            factors.push(self.atom()?);
+        }
+        Ok(match factors.len() {
+            1 => factors.pop().unwrap(),
+            _ => operator(1, factors),
+        })
+    }
+    fn atom(&mut self) -> Result<Packet, ParseError> {
+        if self.eat("(") {
+            let inner = self.comparison()?;
+            self.expect(")")?;
+            return Ok(inner);
+        }
+        for &(name, type_id) in &[("sum", 0), ("product", 1), ("min", 2), ("max", 3)] {
+            if self.eat(name) {
+                self.expect("(")?;
+                let mut arguments = vec![self.comparison()?];
+                while self.eat(",") {
+                    arguments.push(self.comparison()?);
                 }
+                self.expect(")")?;
+                return Ok(operator(type_id, arguments));
             }
-        },
+        }
+        let digits = self.rest.len()
+            - self
+                .rest
+                .trim_start_matches(|c: char| c.is_ascii_digit())
+                .len();
+        let literal = self.rest[..digits]
+            .parse::<u64>()
+            .map_err(|_| self.error("a number, `(` or sum, product, min, max"))?;
+        self.rest = &self.rest[digits..];
+        Ok(Packet {
+            version: 0,
+            type_id: 4,
+            payload: Payload::Literal(literal),
+        })
+    }
+}
+
+/// Builds the packet tree for an infix expression, all packets have version 0.
+fn compile(expression: &str) -> Result<Packet, ParseError> {
+    let mut compiler = Compiler {
+        text: expression,
+        rest: expression,
+    };
+    let packet = compiler.comparison()?;
+    if !compiler.rest.trim().is_empty() {
+        compiler.rest = compiler.rest.trim_start();
+        return Err(compiler.error("an operator or end of expression"));
+    }
+    Ok(packet)
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input<'a> = Packet;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        decode(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_version_numbers(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        evaluate(input, Arithmetic::Big).unwrap()
     }
 }
 
 fn main() {
     let args = Cli::from_args();
-    let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let stream: Vec<u8> = FromHex::from_hex(source.trim()).unwrap();
-    let (packet, _) = parse_packet(BitSlice::new(&stream, 0));
-    let sum = sum_version_numbers(&packet);
-    println!("sum of version numbers: {}", sum);
-    let eval = eval(&packet);
-    println!("Eval: {}", eval);
+    let length_type = match args.encode {
+        None | Some(0) => LengthType::Bits,
+        Some(1) => LengthType::Count,
+        Some(_) => {
+            eprintln!("error: length type must be 0 or 1");
+            process::exit(1)
+        }
+    };
+    let encoded = |packet: &Packet| match encode(packet, length_type) {
+        Ok(hex) => println!("{}", hex),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    };
+    if let Some(expression) = &args.compile {
+        encoded(&or_exit(compile(expression)));
+        return;
+    }
+    let source = fs::read_to_string(args.path.unwrap()).unwrap();
+    if args.lenient {
+        let bytes = or_exit(from_hex(&source));
+        let (packet, error) = parse_packet_lenient(BitSlice::new(&bytes, 0));
+        if let Some(packet) = packet {
+            println!("{}", packet);
+        }
+        if let Some(err) = error {
+            eprintln!("error: {}", err.in_hex(&source));
+            process::exit(1);
+        }
+        return;
+    }
+    let input = or_exit(Day16::parse(&source));
+    println!("sum of version numbers: {}", Day16::part1(&input));
+    match evaluate(&input, args.arithmetic) {
+        Ok(value) => println!("Eval: {}", value),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+    if args.encode.is_some() {
+        encoded(&input);
+    }
+    match args.disassemble.as_deref() {
+        Some("tree") => {
+            let bytes = or_exit(from_hex(&source));
+            print!("{}", disassemble(&bytes).unwrap());
+        }
+        Some(_) => println!("{}", input),
+        None => {}
+    }
 }
 
 #[cfg(test)]
@@ -399,20 +985,20 @@ mod tests {
     #[test]
     fn test_parse_literal_packet() {
         let slice = Vec::from([0b1101_0010, 0b1111_1110, 0b0010_1000]);
-        let (packet, _) = parse_packet(BitSlice::new(&slice, 0));
+        let (packet, _) = parse_packet(BitSlice::new(&slice, 0)).unwrap();
         assert_eq!(packet.version, 6);
         assert_eq!(packet.type_id, 4);
         assert_eq!(packet.payload, Payload::Literal(2021));
 
         // handle case single nibble
         let slice = Vec::from([0b1101_0001, 0b0100_0000]);
-        let (packet, _) = parse_packet(BitSlice::new(&slice, 0));
+        let (packet, _) = parse_packet(BitSlice::new(&slice, 0)).unwrap();
         assert_eq!(packet.version, 6);
         assert_eq!(packet.type_id, 4);
         assert_eq!(packet.payload, Payload::Literal(10));
 
         let slice = Vec::from([0b1111_0100, 0b0111_1000]);
-        let (packet, _) = parse_packet(BitSlice::new(&slice, 2));
+        let (packet, _) = parse_packet(BitSlice::new(&slice, 2)).unwrap();
         assert_eq!(packet.version, 6);
         assert_eq!(packet.type_id, 4);
         assert_eq!(packet.payload, Payload::Literal(15));
@@ -422,7 +1008,7 @@ mod tests {
     fn test_sum_packet_versions() {
         let stream: Vec<u8> = FromHex::from_hex("8A004A801A8002F478".trim()).unwrap();
         let bits = BitSlice::new(&stream, 0);
-        let (packet, _) = parse_packet(bits);
+        let (packet, _) = parse_packet(bits).unwrap();
         let sum = sum_version_numbers(&packet);
         assert_eq!(sum, 16);
     }
@@ -430,8 +1016,8 @@ mod tests {
     fn eval_helper(s: &str) -> u64 {
         let stream: Vec<u8> = FromHex::from_hex(s.trim()).unwrap();
         let bits = BitSlice::new(&stream, 0);
-        let (packet, _) = parse_packet(bits);
-        eval(&packet)
+        let (packet, _) = parse_packet(bits).unwrap();
+        eval(&packet).unwrap()
     }
 
     #[test]
@@ -445,4 +1031,364 @@ mod tests {
         assert_eq!(eval_helper("9C005AC2F8F0"), 0);
         assert_eq!(eval_helper("9C0141080250320F1802104A08"), 1);
     }
+
+    fn literal(version: u8, value: u64) -> Box<Packet> {
+        Box::new(Packet {
+            version,
+            type_id: 4,
+            payload: Payload::Literal(value),
+        })
+    }
+
+    #[test]
+    fn test_encode_examples() {
+        for (hex, length_type) in [
+            ("D2FE28", LengthType::Bits),
+            ("38006F45291200", LengthType::Bits),
+            ("EE00D40C823060", LengthType::Count),
+        ] {
+            assert_eq!(encode(&decode(hex).unwrap(), length_type).unwrap(), hex);
+        }
+        let packet = Packet {
+            version: 1,
+            type_id: 6,
+            payload: Payload::Operator(Operation::LessThan([literal(6, 10), literal(2, 20)])),
+        };
+        assert_eq!(encode(&packet, LengthType::Bits).unwrap(), "38006F45291200");
+        assert_eq!(
+            eval::<u64>(&decode(&encode(&packet, LengthType::Count).unwrap()).unwrap()).unwrap(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_encode_fallback() {
+        let packets = (0..2048).map(|i| literal(0, i % 16)).collect();
+        let packet = Packet {
+            version: 0,
+            type_id: 0,
+            payload: Payload::Operator(Operation::Sum(packets)),
+        };
+        let bits = encode_bits(&packet, LengthType::Count).unwrap();
+        assert_eq!(
+            parse_length_type(BitSlice::new(&bits.bytes, 0).advance(6)).0,
+            0
+        );
+        assert_eq!(
+            parse_packet(BitSlice::new(&bits.bytes, 0)).unwrap().0,
+            packet
+        );
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        // 2048 literals of 86 bits need more than either header can hold
+        let packets = (0..2048).map(|_| literal(0, u64::MAX)).collect();
+        let packet = Packet {
+            version: 0,
+            type_id: 0,
+            payload: Payload::Operator(Operation::Sum(packets)),
+        };
+        assert_eq!(
+            encode(&packet, LengthType::Count).unwrap_err().to_string(),
+            "2048 sub-packets in 176128 bits fit in neither length type"
+        );
+        // the type id written is the payload's, not the field's
+        let packet = Packet {
+            version: 6,
+            type_id: 0,
+            payload: Payload::Literal(2021),
+        };
+        assert_eq!(encode(&packet, LengthType::Bits).unwrap(), "D2FE28");
+    }
+
+    /// xorshift, so the generated packets are the same on every run
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn random_packet(rng: &mut Rng, depth: usize) -> Packet {
+        let version = rng.below(8) as u8;
+        let type_id = if depth == 0 { 4 } else { rng.below(8) as u8 };
+        let payload = match type_id {
+            4 => Payload::Literal(rng.next() >> rng.below(64)),
+            0..=3 => {
+                let n = 1 + rng.below(4);
+                let v = random_packets(rng, depth, n).collect();
+                Payload::Operator(match type_id {
+                    0 => Operation::Sum(v),
+                    1 => Operation::Product(v),
+                    2 => Operation::Minimum(v),
+                    _ => Operation::Maximum(v),
+                })
+            }
+            _ => {
+                let v = random_packets(rng, depth, 2)
+                    .collect::<Vec<_>>()
+                    .try_into()
+                    .unwrap();
+                Payload::Operator(match type_id {
+                    5 => Operation::GreaterThan(v),
+                    6 => Operation::LessThan(v),
+                    _ => Operation::EqualTo(v),
+                })
+            }
+        };
+        Packet {
+            version,
+            type_id,
+            payload,
+        }
+    }
+
+    fn random_packets(
+        rng: &mut Rng,
+        depth: usize,
+        n: u64,
+    ) -> impl Iterator<Item = Box<Packet>> + '_ {
+        (0..n).map(move |_| Box::new(random_packet(rng, depth - 1)))
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut rng = Rng(0x2021_0016);
+        for _ in 0..500 {
+            let packet = random_packet(&mut rng, 4);
+            for length_type in [LengthType::Bits, LengthType::Count] {
+                let bits = encode_bits(&packet, length_type).unwrap();
+                let (parsed, rest) = parse_packet(BitSlice::new(&bits.bytes, 0)).unwrap();
+                assert_eq!(parsed, packet);
+                assert_eq!(bits.bytes.len() * 8 - rest.len(), bits.len);
+                assert_eq!(
+                    decode(&encode(&packet, length_type).unwrap()).unwrap(),
+                    packet
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_compile() {
+        let packet = compile("max(3, 4*5) > 7").unwrap();
+        assert_eq!(eval::<u64>(&packet).unwrap(), 1);
+        assert_eq!(
+            packet,
+            operator(
+                5,
+                vec![
+                    operator(3, vec![compile("3").unwrap(), compile("4 * 5").unwrap()]),
+                    compile("7").unwrap()
+                ]
+            )
+        );
+        for (expression, hex) in [
+            ("1 + 2", "C200B40A82"),
+            ("6 * 9", "04005AC33890"),
+            ("min(7, 8, 9)", "880086C3E88112"),
+            ("max(7, 8, 9)", "CE00C43D881120"),
+            ("5 < 15", "D8005AC2A8F0"),
+            ("5 > 15", "F600BC2D8F"),
+            ("5 == 15", "9C005AC2F8F0"),
+            ("1 + 3 == 2 * 2", "9C0141080250320F1802104A08"),
+        ] {
+            let packet = compile(expression).unwrap();
+            assert_eq!(
+                eval::<u64>(&packet).unwrap(),
+                eval_helper(hex),
+                "{}",
+                expression
+            );
+            assert_eq!(
+                decode(&encode(&packet, LengthType::Count).unwrap()).unwrap(),
+                packet
+            );
+        }
+        assert_eq!(
+            eval::<u64>(&compile("(2 + 3) * (1 < 2) + product(2, 2)").unwrap()).unwrap(),
+            9
+        );
+    }
+
+    #[test]
+    fn test_compile_errors() {
+        assert_eq!(
+            compile("max(3, 4*) > 7").unwrap_err().to_string(),
+            "input line 1, column 10: expected a number, `(` or sum, product, min, max, got ) > 7"
+        );
+        assert_eq!(
+            compile("max(3 4)").unwrap_err().to_string(),
+            "input line 1, column 7: expected `)`, got 4)"
+        );
+        assert_eq!(
+            compile("1 < 2 < 3").unwrap_err().to_string(),
+            "input line 1, column 7: expected an operator or end of expression, got < 3"
+        );
+        assert_eq!(compile("99999999999999999999").unwrap_err().column, 1);
+    }
+
+    #[test]
+    fn test_compile_large() {
+        let nested = format!("{}1{}", "max(2 * ".repeat(300), ")".repeat(300));
+        let terms = vec!["0"; 2500].join(" + ");
+        for expression in [nested, terms] {
+            let packet = compile(&expression).unwrap();
+            for length_type in [LengthType::Bits, LengthType::Count] {
+                assert_eq!(
+                    decode(&encode(&packet, length_type).unwrap()).unwrap(),
+                    packet
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_disassemble() {
+        let bytes: Vec<u8> = FromHex::from_hex("38006F45291200").unwrap();
+        assert_eq!(
+            disassemble(&bytes).unwrap(),
+            "< = 1  [v1 t6 l0 @0]\n  10  [v6 t4 @22]\n  20  [v2 t4 @33]\n"
+        );
+        let bytes: Vec<u8> = FromHex::from_hex("9C0141080250320F1802104A08").unwrap();
+        assert_eq!(
+            disassemble(&bytes).unwrap(),
+            [
+                "== = 1  [v4 t7 l0 @0]",
+                "  + = 4  [v2 t0 l1 @22]",
+                "    1  [v2 t4 @40]",
+                "    3  [v4 t4 @51]",
+                "  * = 4  [v6 t1 l1 @62]",
+                "    2  [v0 t4 @80]",
+                "    2  [v2 t4 @91]",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_sexp() {
+        assert_eq!(
+            decode("9C0141080250320F1802104A08").unwrap().to_string(),
+            "(== (+ 1 3) (* 2 2))"
+        );
+        assert_eq!(
+            compile("max(3, 4*5) > 7").unwrap().to_string(),
+            "(> (max 3 (* 4 5)) 7)"
+        );
+        assert_eq!(decode("D2FE28").unwrap().to_string(), "2021");
+    }
+
+    #[test]
+    fn test_truncated() {
+        assert_eq!(
+            parse_packet(BitSlice::new(&[0xD2, 0xFE], 0)),
+            Err(DecodeError {
+                offset: 16,
+                field: Field::LiteralGroup
+            })
+        );
+        assert_eq!(
+            decode("EE00D40C82").unwrap_err().to_string(),
+            "input line 1, column 11: expected a version, got end of input"
+        );
+        assert_eq!(
+            decode("EE").unwrap_err().to_string(),
+            "input line 1, column 2: expected a sub-packet count, got E"
+        );
+        assert_eq!(
+            parse_packet(BitSlice::new(&[0x38, 0x00], 0))
+                .unwrap_err()
+                .field,
+            Field::Length
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        // the length says 26 bits but the sub-packets take 27
+        assert_eq!(
+            parse_packet(BitSlice::new(&Vec::from_hex("38006B45291200").unwrap(), 0)),
+            Err(DecodeError {
+                offset: 49,
+                field: Field::SubPackets
+            })
+        );
+        // max(1, 2, 3) retyped as a comparison
+        assert_eq!(
+            decode("F600D40C823060").unwrap_err().to_string(),
+            "input line 1, column 1: expected two operands for a comparison, got F600D40C823060"
+        );
+        assert_eq!(
+            decode("D2FG28").unwrap_err().to_string(),
+            "input line 1, column 4: expected a hex digit, got G28"
+        );
+        assert_eq!(
+            decode("D2F").unwrap_err().expected,
+            "an even number of hex digits"
+        );
+        let mut long = BitWriter::new();
+        long.push(4, 6);
+        for _ in 0..17 {
+            long.push(0b1_1111, 5);
+        }
+        long.push(0, 5);
+        assert_eq!(
+            parse_packet(BitSlice::new(&long.bytes, 0)),
+            Err(DecodeError {
+                offset: 86,
+                field: Field::LiteralSize
+            })
+        );
+    }
+
+    #[test]
+    fn test_lenient() {
+        let bytes: Vec<u8> = FromHex::from_hex("EE00D40C82").unwrap();
+        let (packet, error) = parse_packet_lenient(BitSlice::new(&bytes, 0));
+        assert_eq!(packet.unwrap().to_string(), "(max 1 2)");
+        assert_eq!(error.unwrap().offset, 40);
+
+        let bytes: Vec<u8> = FromHex::from_hex("9C0141080250320F1802104A08").unwrap();
+        let (packet, error) = parse_packet_lenient(BitSlice::new(&bytes[..9], 0));
+        let packet = packet.unwrap();
+        assert_eq!(packet.to_string(), "(== (+ 1 3))");
+        assert_eq!(sum_version_numbers(&packet), 12);
+        assert_eq!(eval::<u64>(&packet), Err(ArithError::Arity));
+        assert_eq!(error.unwrap().field, Field::Count);
+
+        let bytes: Vec<u8> = FromHex::from_hex("8A004A801A8002F478").unwrap();
+        assert_eq!(
+            parse_packet_lenient(BitSlice::new(&bytes, 0)),
+            (
+                Some(parse_packet(BitSlice::new(&bytes, 0)).unwrap().0),
+                None
+            )
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let packet = compile("product(4294967296, 4294967296) > 1").unwrap();
+        assert_eq!(eval::<u64>(&packet), Err(ArithError::Overflow));
+        assert_eq!(evaluate(&packet, Arithmetic::Big), Ok("1".to_string()));
+        let packet = compile("18446744073709551615 * 18446744073709551615 + 1").unwrap();
+        assert_eq!(
+            evaluate(&packet, Arithmetic::Big).unwrap(),
+            "340282366920938463426481119284349108226"
+        );
+        assert_eq!(
+            evaluate(&compile("min(5, 3 + 4) == 5").unwrap(), Arithmetic::Checked),
+            Ok("1".to_string())
+        );
+    }
 }
@@ -1,10 +1,16 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
+use regex::Regex;
 use std::cmp;
+use std::fs;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
 struct Cli {
-    //#[structopt(parse(from_os_str))]
-    //path: std::path::PathBuf,
+    #[structopt(parse(from_os_str))]
+    path: std::path::PathBuf,
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
@@ -16,7 +22,7 @@ struct State {
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, Copy)]
-struct Bounds {
+pub struct Bounds {
     min_x: i32,
     max_x: i32,
     min_y: i32,
@@ -181,18 +187,42 @@ fn sum_all_possible(bounds: Bounds) -> i32 {
     count
 }
 
+fn parse_bounds(source: &str) -> Bounds {
+    let re = Regex::new(r"x=(-?\d+)\.\.(-?\d+), y=(-?\d+)\.\.(-?\d+)").unwrap();
+    let cap = re.captures(source).expect("Could not find target area");
+    let n = |i| cap.get(i).unwrap().as_str().parse::<i32>().unwrap();
+    Bounds {
+        min_x: n(1),
+        max_x: n(2),
+        min_y: n(3),
+        max_y: n(4),
+    }
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input<'a> = Bounds;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_bounds(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sim_until_highest(*input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_all_possible(*input)
+    }
+}
+
 fn main() {
-    let _ = Cli::from_args();
-    let bounds = Bounds {
-        min_x: 155,
-        max_x: 182,
-        min_y: -117,
-        max_y: -67,
-    };
-    let high = sim_until_highest(bounds);
-    println!("{}", high);
-    let count = sum_all_possible(bounds);
-    println!("{}", count);
+    let args = Cli::from_args();
+    let source = fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(Day17::parse(&source));
+    println!("{}", Day17::part1(&input));
+    println!("{}", Day17::part2(&input));
 }
 
 #[cfg(test)]
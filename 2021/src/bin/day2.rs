@@ -1,6 +1,9 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming, Unsolved},
+};
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -8,7 +11,9 @@ struct Cli {
     #[structopt(parse(from_os_str))]
     path: std::path::PathBuf,
 }
-enum Direction {
+
+#[derive(Clone, Copy)]
+pub enum Direction {
     Forward,
     Up,
     Down,
@@ -34,47 +39,66 @@ fn parse_line(line: &str) -> Option<(Direction, i32)> {
     }
 }
 
-fn final_position(movement_stream: impl BufRead) -> (i32, i32) {
+fn movement(line: &str) -> Result<(Direction, i32), ParseError> {
+    parse_line(line).ok_or_else(|| ParseError::new(line, line, "a direction and a distance"))
+}
+
+fn final_position(movements: impl IntoIterator<Item = (Direction, i32)>) -> (i32, i32) {
     let mut horizontal_position = 0;
     let mut depth = 0;
     let mut aim = 0;
-    let lines = movement_stream.lines();
-    for line in lines {
-        println!("x={} y={}", horizontal_position, depth);
-        let next_line = line
-            .as_ref()
-            .expect("Could not read next line due to IO error");
-        if let Some((direction, count)) = parse_line(next_line) {
-            aim = aim
-                + match direction {
-                    Direction::Up => -count,
-                    Direction::Down => count,
-                    Direction::Forward => 0,
-                };
-            horizontal_position = horizontal_position
-                + match direction {
-                    Direction::Up => 0,
-                    Direction::Down => 0,
-                    Direction::Forward => count,
-                };
-            depth = depth
-                + match direction {
-                    Direction::Up => 0,
-                    Direction::Down => 0,
-                    Direction::Forward => aim * count,
-                };
-        } else {
-            break;
-        }
+    for (direction, count) in movements {
+        aim += match direction {
+            Direction::Up => -count,
+            Direction::Down => count,
+            Direction::Forward => 0,
+        };
+        horizontal_position += match direction {
+            Direction::Up => 0,
+            Direction::Down => 0,
+            Direction::Forward => count,
+        };
+        depth += match direction {
+            Direction::Up => 0,
+            Direction::Down => 0,
+            Direction::Forward => aim * count,
+        };
     }
     return (horizontal_position, depth);
 }
 
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<(Direction, i32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), movement).collect()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (x, y) = final_position(input.iter().copied());
+        x * y
+    }
+}
+
+impl Streaming for Day2 {
+    type Line = (Direction, i32);
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        movement(line)
+    }
+
+    fn answers(movements: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let (x, y) = final_position(movements);
+        (Unsolved, x * y)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
-    let file = File::open(args.path.as_path()).unwrap();
-    let buf_reader = BufReader::new(file);
-    let (x, y) = final_position(buf_reader);
+    let buf_reader = input::open(args.path.as_path()).unwrap();
+    let (x, y) = final_position(parse_lines(buf_reader, movement).map(or_exit));
     println!("x: {} y: {}", x, y);
     println!("{}", x * y);
 }
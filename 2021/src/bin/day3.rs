@@ -1,6 +1,9 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::Solution,
+};
 use std::collections::BTreeMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -9,41 +12,28 @@ struct Cli {
     path: std::path::PathBuf,
 }
 #[derive(PartialEq, Clone, Debug)]
-enum Binary {
+pub enum Binary {
     One,
     Zero,
 }
-#[derive(Debug, Clone)]
-enum Error {
-    ParsedNoneBinaryCharacter,
-    IOError,
-}
 
-impl From<std::io::Error> for Error {
-    fn from(_: std::io::Error) -> Self {
-        Error::IOError
-    }
-}
-
-fn parse_line(line: &str) -> Result<Vec<Binary>, Error> {
-    Ok(line
-        .chars()
-        .map(|c| match c {
+fn parse_line(line: &str) -> Result<Vec<Binary>, ParseError> {
+    line.char_indices()
+        .map(|(i, c)| match c {
             '0' => Ok(Binary::Zero),
             '1' => Ok(Binary::One),
-            _ => Err(Error::ParsedNoneBinaryCharacter),
+            _ => Err(ParseError::new(line, &line[i..], "0 or 1")),
         })
-        .collect::<Result<Vec<Binary>, Error>>()?)
+        .collect()
 }
 
-fn get_values(stream: impl BufRead) -> Result<(usize, usize, usize, usize), Error> {
-    let mut sanitised_stream: Vec<Vec<Binary>> = stream
-        .lines()
-        .collect::<Result<Vec<String>, std::io::Error>>()?
-        .iter()
-        .map(|x| parse_line(x.as_str()))
-        .collect::<Result<Vec<Vec<Binary>>, Error>>()?;
-    sanitised_stream.retain(|x| x.len() > 0);
+fn parse(stream: impl std::io::BufRead) -> Result<Vec<Vec<Binary>>, ParseError> {
+    let mut rows = parse_lines(stream, parse_line).collect::<Result<Vec<_>, _>>()?;
+    rows.retain(|x| !x.is_empty());
+    Ok(rows)
+}
+
+fn get_values(sanitised_stream: &[Vec<Binary>]) -> (usize, usize, usize, usize) {
     let mut counters = BTreeMap::new();
     for row in sanitised_stream.iter() {
         for (i, c) in row.iter().enumerate() {
@@ -72,7 +62,7 @@ fn get_values(stream: impl BufRead) -> Result<(usize, usize, usize, usize), Erro
         .map(|(i, x)| usize::pow(x, i as u32 + 1) / 2)
         .fold(0, |a, x| a + x);
 
-    let oxygen = bit_criteria(sanitised_stream.clone(), 0, |x| {
+    let oxygen = bit_criteria(sanitised_stream.to_vec(), 0, |x| {
         if x >= 0 {
             Binary::One
         } else {
@@ -89,7 +79,7 @@ fn get_values(stream: impl BufRead) -> Result<(usize, usize, usize, usize), Erro
     .map(|(i, x)| usize::pow(x, i as u32 + 1) / 2)
     .fold(0, |a, x| a + x);
 
-    let co2 = bit_criteria(sanitised_stream, 0, |x| {
+    let co2 = bit_criteria(sanitised_stream.to_vec(), 0, |x| {
         if x < 0 {
             Binary::One
         } else {
@@ -106,7 +96,7 @@ fn get_values(stream: impl BufRead) -> Result<(usize, usize, usize, usize), Erro
     .map(|(i, x)| usize::pow(x, i as u32 + 1) / 2)
     .fold(0, |a, x| a + x);
 
-    return Ok((gamma, epsilon, oxygen, co2));
+    (gamma, epsilon, oxygen, co2)
 }
 
 fn bit_criteria(bits: Vec<Vec<Binary>>, level: usize, search: fn(i32) -> Binary) -> Vec<Binary> {
@@ -117,25 +107,49 @@ fn bit_criteria(bits: Vec<Vec<Binary>>, level: usize, search: fn(i32) -> Binary)
             Binary::Zero => -1,
         })
         .fold(0, |a, x| a + x);
-    println!("{} === {:?}", level, bits);
-    println!("sum:  {}", summation);
     let firstpass = bits
         .iter()
         .filter(|x| x.get(level).unwrap() == &search(summation))
         .map(|x| x.clone())
         .collect::<Vec<Vec<Binary>>>();
     if firstpass.len() == 1 {
-        println!("{:?}", firstpass);
         return firstpass.get(0).unwrap().to_vec();
     }
     bit_criteria(firstpass, level + 1, search)
 }
 
+fn part1(input: &[Vec<Binary>]) -> usize {
+    let (gamma, epsilon, _, _) = get_values(input);
+    gamma * epsilon
+}
+
+fn part2(input: &[Vec<Binary>]) -> usize {
+    let (_, _, oxygen, co2) = get_values(input);
+    oxygen * co2
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Vec<Vec<Binary>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input.as_bytes())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
-    let file = File::open(args.path.as_path()).unwrap();
-    let buf_reader = BufReader::new(file);
-    let (gamma, epsilon, oxygen, co2) = get_values(buf_reader).unwrap();
+    let buf_reader = input::open(args.path.as_path()).unwrap();
+    let (gamma, epsilon, oxygen, co2) = get_values(&or_exit(parse(buf_reader)));
 
     println!(
         "gamma={} epsilon={} oxygen={} co2={}",
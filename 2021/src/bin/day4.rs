@@ -1,3 +1,4 @@
+use advent_of_code::{parse::ParseError, solution::Solution};
 use regex::Regex;
 use std::fs;
 use structopt::StructOpt;
@@ -9,7 +10,7 @@ struct Cli {
 }
 
 #[derive(PartialEq, Clone, Debug)]
-struct Board {
+pub struct Board {
     entries: Vec<Cell>,
 }
 #[derive(PartialEq, Clone, Debug)]
@@ -69,7 +70,6 @@ fn is_completed(board: &Board) -> bool {
     false
 }
 
-#[allow(dead_code)]
 fn fill_in(inputs: Vec<usize>, mut boards: Vec<Board>) -> Option<(usize, Board)> {
     for input in inputs.iter() {
         for board in &mut boards {
@@ -86,7 +86,6 @@ fn fill_in(inputs: Vec<usize>, mut boards: Vec<Board>) -> Option<(usize, Board)>
 }
 fn fill_in_last(inputs: Vec<usize>, mut boards: Vec<Board>) -> Option<(usize, Board)> {
     let mut completed_boards = Vec::new();
-    let mut j = 0;
     for input in inputs.iter() {
         for board in &mut boards {
             if is_completed(board) {
@@ -97,14 +96,10 @@ fn fill_in_last(inputs: Vec<usize>, mut boards: Vec<Board>) -> Option<(usize, Bo
                 *mark = true;
             }
             if is_completed(board) {
-                j += 1;
-                println!("{}", j);
                 completed_boards.push((input.clone(), board.clone()));
             }
         }
     }
-    println!("board list count: {}", boards.len());
-    println!("Completed board count: {}", completed_boards.len());
     let last_element = completed_boards.last().unwrap();
     Some(last_element.clone())
 }
@@ -154,12 +149,13 @@ fn parse_double_newlines(source: &str) -> (Option<()>, &str) {
 fn parse_bingo_board(source: &str) -> (Board, &str) {
     let newline_regex = Regex::new("\n\n").unwrap();
     let space_regex = Regex::new(" +").unwrap();
+    // the last board may run to the end of the input without a blank line after it
     let split_index = newline_regex
         .find(source)
-        .expect("Could not find double new line")
-        .start();
+        .map_or(source.len(), |m| m.start());
     let (text_board, remaining_input) = source.split_at(split_index);
     let board_entries = text_board
+        .trim_end()
         .split('\n')
         .map(|row| row.trim())
         .map(|row| space_regex.split(row))
@@ -178,6 +174,38 @@ fn parse_bingo_board(source: &str) -> (Board, &str) {
     )
 }
 
+fn score(last_call: usize, board: &Board) -> usize {
+    board
+        .entries
+        .iter()
+        .filter(|e| e.mark == false)
+        .map(|e| e.value)
+        .fold(0, |a, e| a + e)
+        * last_call
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = (Vec<usize>, Vec<Board>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_bingo(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (inputs, boards) = input.clone();
+        let (last_call, board) = fill_in(inputs, boards).expect("no winners found");
+        score(last_call, &board)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (inputs, boards) = input.clone();
+        let (last_call, board) = fill_in_last(inputs, boards).expect("no winners found");
+        score(last_call, &board)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
@@ -185,14 +213,7 @@ fn main() {
     let (inputs, boards) = parse_bingo(source.as_ref());
     if let Some((last_call, filled_board)) = fill_in_last(inputs, boards) {
         println!("{:?}", &filled_board);
-        let score = filled_board
-            .entries
-            .iter()
-            .filter(|e| e.mark == false)
-            .map(|e| e.value)
-            .fold(0, |a, e| a + e)
-            * last_call;
-        println!("Score: {}", score);
+        println!("Score: {}", score(last_call, &filled_board));
     } else {
         println!("No winners found");
     }
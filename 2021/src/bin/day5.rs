@@ -1,6 +1,12 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
 use regex::Regex;
 use std::cmp;
-use std::fs;
+use std::fmt::Display;
+use std::sync::OnceLock;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -10,7 +16,7 @@ struct Cli {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq)]
-struct Coord {
+pub struct Coord {
     x: usize,
     y: usize,
 }
@@ -22,8 +28,10 @@ struct Grid {
     height: usize,
 }
 
+static LINE: OnceLock<Regex> = OnceLock::new();
+
 fn parse_line(source: &str) -> (Option<(Coord, Coord)>, &str) {
-    let regex = Regex::new(r"^(\d+),(\d+) -> (\d+),(\d+)\n?").unwrap();
+    let regex = LINE.get_or_init(|| Regex::new(r"^(\d+),(\d+) -> (\d+),(\d+)\n?").unwrap());
     if let Some(captures) = regex.captures(source) {
         let mut matches = captures.iter();
 
@@ -66,6 +74,14 @@ fn parse_line(source: &str) -> (Option<(Coord, Coord)>, &str) {
     }
 }
 
+fn segment(line: &str) -> Result<(Coord, Coord), ParseError> {
+    match parse_line(line) {
+        (Some(coords), "") => Ok(coords),
+        (Some(_), rest) => Err(ParseError::new(line, rest, "end of line")),
+        (None, _) => Err(ParseError::new(line, line, "two coordinates")),
+    }
+}
+
 fn parse_coordinates(source: &str) -> (Vec<(Coord, Coord)>, &str) {
     let mut source = source;
     let mut coords_list = Vec::new();
@@ -141,16 +157,58 @@ impl Grid {
     }
 }
 
-fn main() {
-    let args = Cli::from_args();
-    let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let (coord_pairs, _) = parse_coordinates(&source);
+fn overlaps(coord_pairs: Vec<(Coord, Coord)>) -> usize {
     let mut grid = make_big_grid(&coord_pairs);
     for c in coord_pairs {
         draw_line(&mut grid, c);
     }
-    let count = count_overlap(&grid);
-    println!("overlaps: {}", count);
+    count_overlap(&grid)
+}
+
+/// The horizontal and vertical lines, leaving out the diagonals.
+fn straight(coord_pairs: &[(Coord, Coord)]) -> Vec<(Coord, Coord)> {
+    let mut coord_pairs = coord_pairs.to_vec();
+    coord_pairs.retain(|(a, b)| a.x == b.x || a.y == b.y);
+    coord_pairs
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input<'a> = Vec<(Coord, Coord)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_coordinates(input).0)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        overlaps(straight(input))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        overlaps(input.clone())
+    }
+}
+
+impl Streaming for Day5 {
+    type Line = (Coord, Coord);
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        segment(line)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        // only the text is streamed, the grid is sized from every segment
+        let coord_pairs: Vec<_> = lines.collect();
+        (overlaps(straight(&coord_pairs)), overlaps(coord_pairs))
+    }
+}
+
+fn main() {
+    let args = Cli::from_args();
+    let input = input::open(args.path.as_path()).unwrap();
+    let coord_pairs = parse_lines(input, segment).map(or_exit).collect();
+    println!("overlaps: {}", overlaps(coord_pairs));
 }
 
 #[cfg(test)]
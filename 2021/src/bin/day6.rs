@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::collections::BTreeMap;
 use std::fs;
 use std::num::ParseIntError;
@@ -39,17 +43,39 @@ fn step_timer(old_counter: BTreeMap<u64, u64>) -> BTreeMap<u64, u64> {
     new_counter
 }
 
-fn main() {
-    let args = Cli::from_args();
-    let days = args.days;
-    let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let inputs = parse_input(&source).unwrap();
-    let mut counter = group_by_timer(inputs);
+fn fish_after(inputs: &[u64], days: u32) -> u64 {
+    let mut counter = group_by_timer(inputs.to_vec());
     for _ in 0..days {
         counter = step_timer(counter);
     }
-    let number_of_fish = counter.values().fold(0, |a, x| a + x);
-    println!("Fishes: {}", number_of_fish);
+    counter.values().fold(0, |a, x| a + x)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input).unwrap())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        fish_after(input, 80)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        fish_after(input, 256)
+    }
+}
+
+fn main() {
+    let args = Cli::from_args();
+    let source = fs::read_to_string(args.path.as_path()).unwrap();
+    println!(
+        "Fishes: {}",
+        fish_after(&or_exit(Day6::parse(&source)), args.days)
+    );
 }
 
 #[cfg(test)]
@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::cmp;
 use std::fs;
 use std::num::ParseIntError;
@@ -30,15 +34,26 @@ fn crab_game(inputs: Vec<i64>) -> i64 {
         .fold(i64::MAX, |b, attempts| cmp::min(b, attempts))
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = Vec<u64>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input).unwrap())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let inputs = input.iter().map(|x| *x as i64).collect::<Vec<i64>>();
+        crab_game(inputs)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let inputs = parse_input(&source)
-        .unwrap()
-        .iter()
-        .map(|x| x.clone() as i64)
-        .collect::<Vec<i64>>();
-    let minimised_total_distance = crab_game(inputs);
+    let input = or_exit(Day7::parse(&source));
+    let minimised_total_distance = Day7::part2(&input);
     println!("total distance: {}", minimised_total_distance);
 }
 
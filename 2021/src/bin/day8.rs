@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::collections::{BTreeSet, HashMap};
 use std::fs;
 use structopt::StructOpt;
@@ -113,11 +117,25 @@ fn solve_number_to_set(digits: Vec<BTreeSet<usize>>) -> HashMap<usize, BTreeSet<
     result
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input<'a> = (Vec<Vec<BTreeSet<usize>>>, Vec<Vec<BTreeSet<usize>>>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        count_easy_digits(input.1.clone())
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let (_, f) = parse_input(&source);
-    let count = count_easy_digits(f);
+    let input = or_exit(Day8::parse(&source));
+    let count = Day8::part1(&input);
     println!("easy digit count: {}", count);
 }
 
@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::cmp;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -106,22 +110,38 @@ fn basin_size(grid: &HashMap<(i32, i32), u32>, i: i32, j: i32) -> u32 {
     basin_count
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input<'a> = HashMap<(i32, i32), u32>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_input(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        stream_lowpoints(input)
+            .iter()
+            .map(|x| x + 1)
+            .fold(0, |a, x| a + x)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let mut basins = lowpoints_coords(input)
+            .iter()
+            .map(|(i, j)| basin_size(input, *i, *j))
+            .collect::<Vec<u32>>();
+        basins.sort();
+        basins[basins.len() - 3..].iter().fold(1, |a, x| a * x)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let source = fs::read_to_string(args.path.as_path()).unwrap();
-    let grid = parse_input(&source);
-    let sum_risk_level = stream_lowpoints(&grid)
-        .iter()
-        .map(|x| x + 1)
-        .fold(0, |a, x| a + x);
-    println!("sum of risk level: {}", sum_risk_level);
-    let mut basins = lowpoints_coords(&grid)
-        .iter()
-        .map(|(i, j)| basin_size(&grid, *i, *j))
-        .collect::<Vec<u32>>();
-    basins.sort();
-    let x = basins[basins.len() - 3..].iter().fold(1, |a, x| a * x);
-    println!("{}", x);
+    let input = or_exit(Day9::parse(&source));
+    println!("sum of risk level: {}", Day9::part1(&input));
+    println!("{}", Day9::part2(&input));
 }
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,15 @@
+use advent_of_code::answers;
+
+fn check(args: &[&str]) {
+    answers::check(env!("CARGO_BIN_EXE_aoc"), env!("CARGO_MANIFEST_DIR"), args);
+}
+
+#[test]
+fn examples() {
+    check(&["--examples"]);
+}
+
+#[test]
+fn real_inputs() {
+    check(&[]);
+}
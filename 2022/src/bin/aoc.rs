@@ -0,0 +1,116 @@
+use std::process::ExitCode;
+
+use advent_of_code::{runner, solution::Day};
+
+#[path = "day1.rs"]
+#[allow(dead_code)]
+mod day1;
+#[path = "day10.rs"]
+#[allow(dead_code)]
+mod day10;
+#[path = "day11.rs"]
+#[allow(dead_code)]
+mod day11;
+#[path = "day12.rs"]
+#[allow(dead_code)]
+mod day12;
+#[path = "day13.rs"]
+#[allow(dead_code)]
+mod day13;
+#[path = "day14.rs"]
+#[allow(dead_code)]
+mod day14;
+#[path = "day15.rs"]
+#[allow(dead_code)]
+mod day15;
+#[path = "day16.rs"]
+#[allow(dead_code)]
+mod day16;
+#[path = "day17.rs"]
+#[allow(dead_code)]
+mod day17;
+#[path = "day18.rs"]
+#[allow(dead_code)]
+mod day18;
+#[path = "day19.rs"]
+#[allow(dead_code)]
+mod day19;
+#[path = "day2.rs"]
+#[allow(dead_code)]
+mod day2;
+#[path = "day20.rs"]
+#[allow(dead_code)]
+mod day20;
+#[path = "day21.rs"]
+#[allow(dead_code)]
+mod day21;
+#[path = "day22.rs"]
+#[allow(dead_code)]
+mod day22;
+#[path = "day23.rs"]
+#[allow(dead_code)]
+mod day23;
+#[path = "day24.rs"]
+#[allow(dead_code)]
+mod day24;
+#[path = "day25.rs"]
+#[allow(dead_code)]
+mod day25;
+#[path = "day3.rs"]
+#[allow(dead_code)]
+mod day3;
+#[path = "day4.rs"]
+#[allow(dead_code)]
+mod day4;
+#[path = "day5.rs"]
+#[allow(dead_code)]
+mod day5;
+#[path = "day6.rs"]
+#[allow(dead_code)]
+mod day6;
+#[path = "day6-fast.rs"]
+#[allow(dead_code)]
+mod day6_fast;
+#[path = "day7.rs"]
+#[allow(dead_code)]
+mod day7;
+#[path = "day8.rs"]
+#[allow(dead_code)]
+mod day8;
+#[path = "day9.rs"]
+#[allow(dead_code)]
+mod day9;
+
+fn main() -> ExitCode {
+    runner::main(
+        2022,
+        &[
+            Day::streaming::<day1::Day1>(1),
+            Day::streaming::<day2::Day2>(2),
+            Day::streaming::<day3::Day3>(3),
+            Day::streaming::<day4::Day4>(4),
+            Day::new::<day5::Day5>(5),
+            Day::new::<day6::Day6>(6),
+            Day::new::<day6_fast::Day6>(6).variant("fast"),
+            Day::new::<day7::Day7>(7),
+            Day::new::<day8::Day8>(8),
+            Day::streaming::<day9::Day9>(9),
+            Day::streaming::<day10::Day10>(10),
+            Day::new::<day11::Day11>(11),
+            Day::new::<day12::Day12>(12),
+            Day::streaming::<day13::Day13>(13),
+            Day::new::<day14::Day14>(14),
+            Day::new::<day15::Day15>(15),
+            Day::new::<day16::Day16>(16),
+            Day::new::<day17::Day17>(17),
+            Day::new::<day18::Day18>(18),
+            Day::new::<day19::Day19>(19),
+            Day::new::<day20::Day20>(20),
+            Day::new::<day21::Day21>(21),
+            Day::new::<day22::Day22>(22),
+            Day::new::<day23::Day23>(23),
+            Day::new::<day24::Day24>(24),
+            Day::streaming::<day25::Day25>(25),
+        ],
+    )
+}
@@ -1,8 +1,11 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
 use std::cmp::Reverse;
 use std::collections::BinaryHeap;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -11,17 +14,19 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Line {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line {
     Number(i32),
     Empty,
 }
 
-fn parse(line: &str) -> Line {
+fn parse(line: &str) -> Result<Line, ParseError> {
     if line.is_empty() {
-        return Line::Empty;
+        return Ok(Line::Empty);
     }
-    Line::Number(line.parse().expect("Could not parse"))
+    line.parse()
+        .map(Line::Number)
+        .map_err(|_| ParseError::new(line, line, "calories or an empty line"))
 }
 
 #[derive(Debug, Clone)]
@@ -63,14 +68,70 @@ fn ranked_chomp(state: RankedTracker, next: Line) -> RankedTracker {
     }
 }
 
+// The trailing empty line closes off the last elf
+fn top_carried(lines: impl IntoIterator<Item = Line>) -> Vec<i32> {
+    lines
+        .into_iter()
+        .chain(std::iter::once(Line::Empty))
+        .fold(RankedTracker::init(), ranked_chomp)
+        .top_carried
+        .into_iter()
+        .map(|Reverse(x)| x)
+        .collect()
+}
+
+fn part1(lines: &[Line]) -> i32 {
+    top_carried(lines.iter().copied())
+        .into_iter()
+        .max()
+        .unwrap_or(0)
+}
+
+fn part2(lines: &[Line]) -> i32 {
+    top_carried(lines.iter().copied()).into_iter().sum()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), parse).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day1 {
+    type Line = Line;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        parse(line)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let top = top_carried(lines);
+        (
+            top.iter().copied().max().unwrap_or(0),
+            top.iter().sum::<i32>(),
+        )
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
-    let input = File::open(args.path.as_path()).unwrap();
-    let lines = BufReader::new(input).lines();
-    let ranked_state = lines
-        .map(|x| parse(&x.unwrap()))
-        .fold(RankedTracker::init(), ranked_chomp);
-    println!("{:?}", ranked_state);
+    // the lines are streamed, stopping at the first bad one
+    let lines = parse_lines(input::open(args.path.as_path()).unwrap(), parse).map(or_exit);
+    let (most, top_three) = Day1::answers(lines);
+    println!("{}", most);
+    println!("{}", top_three);
 }
 
 #[cfg(test)]
@@ -79,10 +140,11 @@ mod tests {
 
     #[test]
     fn test_parse_empty() {
-        assert_eq!(parse(""), Line::Empty);
+        assert_eq!(parse(""), Ok(Line::Empty));
     }
     #[test]
     fn test_parse_number() {
-        assert_eq!(parse("0100"), Line::Number(100));
+        assert_eq!(parse("0100"), Ok(Line::Number(100)));
+        assert!(parse("10a").is_err());
     }
 }
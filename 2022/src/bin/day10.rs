@@ -1,5 +1,9 @@
-use std::time::Instant;
-
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
+use std::{fmt::Display, io::BufRead};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -52,78 +56,137 @@ impl State {
     }
 }
 
-fn parse_line(line: &str) -> (i32, u32) {
+fn parse_line(line: &str) -> Result<(i32, u32), ParseError> {
     let parts = line.split(' ').collect::<Vec<&str>>();
     match parts.as_slice() {
-        ["noop"] => (0, 1),
-        ["addx", addition] => (addition.parse::<i32>().unwrap(), 2),
-        _ => panic!("unexpected"),
+        ["noop"] => Ok((0, 1)),
+        ["addx", addition] => addition
+            .parse::<i32>()
+            .map(|addition| (addition, 2))
+            .map_err(|_| ParseError::new(line, addition, "a number")),
+        _ => Err(ParseError::new(line, line, "noop or addx")),
     }
 }
 
-fn run(mut state: State, instructions: &str) -> i32 {
-    let mut sum = 0;
-    for line in instructions.lines() {
-        let ins = parse_line(line);
-        state = state.next(ins);
+fn parse_program(input: impl BufRead) -> Result<Vec<(i32, u32)>, ParseError> {
+    parse_lines(input, parse_line).collect()
+}
+
+/// The state during every cycle, before the instruction finishing in it is applied.
+fn cycles(
+    mut state: State,
+    instructions: impl IntoIterator<Item = (i32, u32)>,
+) -> impl Iterator<Item = State> {
+    instructions.into_iter().flat_map(move |ins| {
+        state = state.clone().next(ins);
+        let mut during = Vec::with_capacity(2);
         while state.processing != 0 {
-            state = state.tick();
-            sum = sum
-                + if state.cycle >= 20 && (state.cycle - 20) % 40 == 0 {
-                    (state.cycle as i32) * state.register
-                } else {
-                    0
-                };
+            state = state.clone().tick();
+            during.push(state.clone());
             if state.processing == 0 {
-                state = state.process();
+                state = state.clone().process();
             }
         }
+        during
+    })
+}
+
+fn signal_strength(state: &State) -> i32 {
+    if state.cycle >= 20 && (state.cycle - 20) % 40 == 0 {
+        (state.cycle as i32) * state.register
+    } else {
+        0
     }
-    sum
 }
 
-fn draw(mut state: State, instructions: &str) -> Vec<Vec<u8>> {
+/// Lights the pixel drawn in this cycle if the sprite covers it, starting a new row when
+/// the last one is full.
+fn draw_pixel(crt: &mut Vec<Vec<u8>>, state: &State) {
+    let pos = (state.cycle as i32 - 1) % 40;
+    if pos == 0 {
+        crt.push(vec![0; 40]);
+    }
+    if pos == state.register || pos - 1 == state.register || pos + 1 == state.register {
+        crt.last_mut().unwrap()[pos as usize] = 1;
+    }
+}
+
+fn run(state: State, instructions: &[(i32, u32)]) -> i32 {
+    cycles(state, instructions.iter().copied())
+        .map(|state| signal_strength(&state))
+        .sum()
+}
+
+fn draw(state: State, instructions: &[(i32, u32)]) -> Vec<Vec<u8>> {
     let mut crt = Vec::new();
-    let mut row = vec![0; 40];
-    for line in instructions.lines() {
-        let ins = parse_line(line);
-        state = state.next(ins);
-        while state.processing != 0 {
-            state = state.tick();
-            let pos = (state.cycle as i32 - 1) % 40;
-            if pos == state.register || pos - 1 == state.register || pos + 1 == state.register {
-                row[pos as usize] = 1;
-            }
-            if state.cycle % 40 == 0 {
-                crt.push(row);
-                row = vec![0; 40];
-            }
-            if state.processing == 0 {
-                state = state.process();
-            }
-        }
+    for state in cycles(state, instructions.iter().copied()) {
+        draw_pixel(&mut crt, &state);
     }
-    print!("\n");
-    print!("\n");
-    for row in crt.iter() {
-        for c in row.iter() {
-            print!("{}", if c == &1 { "1" } else { " " });
+    crt
+}
+
+fn render(crt: &[Vec<u8>]) -> String {
+    crt.iter()
+        .map(|row| {
+            row.iter()
+                .map(|c| if c == &1 { '1' } else { ' ' })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn part1(input: &[(i32, u32)]) -> i32 {
+    run(State::new(), input)
+}
+
+fn part2(input: &[(i32, u32)]) -> String {
+    render(&draw(State::new(), input))
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = Vec<(i32, u32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_program(input.as_bytes())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day10 {
+    type Line = (i32, u32);
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        parse_line(line)
+    }
+
+    fn answers(instructions: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let mut sum = 0;
+        let mut crt = Vec::new();
+        for state in cycles(State::new(), instructions) {
+            sum += signal_strength(&state);
+            draw_pixel(&mut crt, &state);
         }
-        print!("\n");
+        (sum, render(&crt))
     }
-    print!("\n");
-    print!("\n");
-    crt
 }
 
 fn main() {
-    let start = Instant::now();
     let args = Cli::from_args();
-    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    println!("sum: {}", run(State::new(), &input));
+    let program = input::open(args.path.as_path()).unwrap();
+    let (sum, screen) = Day10::answers(parse_lines(program, parse_line).map(or_exit));
+    println!("sum: {}", sum);
 
-    draw(State::new(), &input);
-    println!("time: {}", start.elapsed().as_micros());
+    print!("\n\n{}\n\n\n", screen);
 }
 
 #[cfg(test)]
@@ -132,15 +195,24 @@ mod tests {
 
     #[test]
     fn test_parse_line() {
-        assert_eq!(parse_line("noop"), (0, 1));
-        assert_eq!(parse_line("addx 5"), (5, 2));
-        assert_eq!(parse_line("addx -5"), (-5, 2));
+        assert_eq!(parse_line("noop"), Ok((0, 1)));
+        assert_eq!(parse_line("addx 5"), Ok((5, 2)));
+        assert_eq!(parse_line("addx -5"), Ok((-5, 2)));
+        assert_eq!(
+            parse_program("noop\naddx x\n".as_bytes())
+                .unwrap_err()
+                .to_string(),
+            "input line 2, column 6: expected a number, got x"
+        );
     }
 
     #[test]
     fn test_run() {
         assert_eq!(
-            run(State::new(), include_str!("../../input/day10-test")),
+            run(
+                State::new(),
+                &parse_program(include_str!("../../input/day10-test").as_bytes()).unwrap()
+            ),
             13140
         );
     }
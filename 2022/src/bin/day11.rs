@@ -1,16 +1,71 @@
+use advent_of_code::{
+    cycle,
+    numtheory::lcm_all,
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
+use nom::{
+    branch::alt,
+    character::complete::{i64, line_ending, multispace0, u64},
+    combinator::{all_consuming, consumed, map, value},
+    multi::{separated_list0, separated_list1},
+    sequence::{delimited, pair, preceded, terminated, tuple},
+};
 use std::iter::zip;
-use std::time::Instant;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
 struct Cli {
     #[structopt(parse(from_os_str))]
     path: std::path::PathBuf,
+    /// also count the worried monkey business after this many rounds
+    #[structopt(long)]
+    rounds: Option<usize>,
 }
 
-struct Monkey {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operand {
+    Old,
+    Constant(i64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operation {
+    Add(Operand),
+    Mul(Operand),
+}
+
+impl Operation {
+    fn apply(&self, old: i64) -> i64 {
+        let operand = |operand: &Operand| match operand {
+            Operand::Old => old,
+            Operand::Constant(c) => *c,
+        };
+        match self {
+            Operation::Add(x) => old + operand(x),
+            Operation::Mul(x) => old * operand(x),
+        }
+    }
+    /// `apply` on a residue, staying below `modulus`. It works in `i128`, so even the square of a
+    /// residue of the largest `i64` modulus fits.
+    fn apply_mod(&self, old: i64, modulus: i64) -> i64 {
+        let (old, modulus) = (i128::from(old), i128::from(modulus));
+        let operand = |operand: &Operand| match operand {
+            Operand::Old => old,
+            Operand::Constant(c) => i128::from(*c).rem_euclid(modulus),
+        };
+        let new = match self {
+            Operation::Add(x) => old + operand(x),
+            Operation::Mul(x) => old * operand(x),
+        };
+        (new % modulus) as i64
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monkey {
     items: Vec<i64>,
-    operation: Box<dyn Fn(i64) -> i64>,
+    operation: Operation,
     division_number: i64,
     true_monkey_index: usize,
     false_monkey_index: usize,
@@ -24,7 +79,7 @@ impl Monkey {
         self.items = self
             .items
             .iter()
-            .map(|item| (self.operation)(*item))
+            .map(|item| self.operation.apply(*item))
             .collect();
     }
     fn calm(&mut self) {
@@ -50,6 +105,86 @@ impl Monkey {
     }
 }
 
+fn operand(input: &str) -> IResult<&str, Operand> {
+    alt((value(Operand::Old, tag("old")), map(i64, Operand::Constant)))(input)
+}
+
+fn operation(input: &str) -> IResult<&str, Operation> {
+    preceded(
+        tag("new = old "),
+        alt((
+            map(preceded(tag("+ "), operand), Operation::Add),
+            map(preceded(tag("* "), operand), Operation::Mul),
+        )),
+    )(input)
+}
+
+/// A monkey, with the text of its divisor and of the monkeys it throws to for [`parse`] to check.
+fn monkey(input: &str) -> IResult<&str, (Monkey, [&str; 3])> {
+    map(
+        tuple((
+            delimited(tag("Monkey "), u64, pair(tag(":"), line_ending)),
+            delimited(
+                tag("  Starting items: "),
+                separated_list0(tag(", "), i64),
+                line_ending,
+            ),
+            delimited(tag("  Operation: "), operation, line_ending),
+            delimited(tag("  Test: divisible by "), consumed(i64), line_ending),
+            delimited(
+                tag("    If true: throw to monkey "),
+                consumed(u64),
+                line_ending,
+            ),
+            preceded(tag("    If false: throw to monkey "), consumed(u64)),
+        )),
+        |(_, items, operation, (divisor, division_number), if_true, if_false)| {
+            let monkey = Monkey {
+                items,
+                operation,
+                division_number,
+                true_monkey_index: if_true.1 as usize,
+                false_monkey_index: if_false.1 as usize,
+            };
+            (monkey, [divisor, if_true.0, if_false.0])
+        },
+    )(input)
+}
+
+fn monkeys(input: &str) -> IResult<&str, Vec<(Monkey, [&str; 3])>> {
+    all_consuming(terminated(
+        separated_list1(pair(line_ending, line_ending), monkey),
+        multispace0,
+    ))(input)
+}
+
+/// Parses the monkeys, making sure every test divides by something other than 0 and every monkey
+/// throws to another monkey that exists.
+fn parse(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let monkeys = from_nom(input, monkeys(input))?;
+    let count = monkeys.len();
+    monkeys
+        .into_iter()
+        .enumerate()
+        .map(|(index, (monkey, [divisor, if_true, if_false]))| {
+            if monkey.division_number == 0 {
+                return Err(ParseError::new(input, divisor, "a divisor other than 0"));
+            }
+            let targets = [
+                (monkey.true_monkey_index, if_true),
+                (monkey.false_monkey_index, if_false),
+            ];
+            for (target, text) in targets {
+                if target >= count || target == index {
+                    let expected = format!("a monkey other than {} below {}", index, count);
+                    return Err(ParseError::new(input, text, expected));
+                }
+            }
+            Ok(monkey)
+        })
+        .collect()
+}
+
 fn monkey_round(mut monkeys: Vec<Monkey>) -> (Vec<Monkey>, Vec<u64>) {
     let mut inspection_count = vec![0; monkeys.len()];
     for i in 0..monkeys.len() {
@@ -65,7 +200,7 @@ fn monkey_round(mut monkeys: Vec<Monkey>) -> (Vec<Monkey>, Vec<u64>) {
 }
 fn worried_monkey_round(mut monkeys: Vec<Monkey>) -> (Vec<Monkey>, Vec<u64>) {
     let mut inspection_count = vec![0; monkeys.len()];
-    let modulo = monkeys.iter().fold(1, |a, x| a * x.division_number);
+    let modulo = lcm_all(monkeys.iter().map(|m| m.division_number)).expect("divisors too large");
     for i in 0..monkeys.len() {
         let monkey = &mut monkeys[i];
         monkey.inspect();
@@ -95,20 +230,116 @@ fn monkey_business(
     total_inspections.iter().take(2).fold(1, |a, x| a * x)
 }
 
+fn part1(monkeys: &[Monkey]) -> u64 {
+    monkey_business(monkeys.to_vec(), monkey_round, 20)
+}
+
+fn part2(monkeys: &[Monkey]) -> u64 {
+    monkey_business(monkeys.to_vec(), worried_monkey_round, 10000)
+}
+
+/// An item as its worry level modulo every monkey's test divisor, which is all the
+/// tests and operations need. Items never affect each other, so each one is followed
+/// on its own until its (monkey, residues) state repeats.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct Item {
+    monkey: usize,
+    residues: Vec<i64>,
+}
+
+/// Moves an item through one round, noting each monkey that inspects it. An item
+/// thrown to a monkey later in the round gets inspected again in the same round, one
+/// thrown back to itself waits for the next round like it does in `monkey_business`.
+fn item_round(monkeys: &[Monkey], item: &Item, inspectors: &mut Vec<usize>) -> Item {
+    let mut item = item.clone();
+    loop {
+        let monkey = &monkeys[item.monkey];
+        inspectors.push(item.monkey);
+        for (residue, other) in item.residues.iter_mut().zip(monkeys) {
+            *residue = monkey.operation.apply_mod(*residue, other.division_number);
+        }
+        let next = if item.residues[item.monkey] == 0 {
+            monkey.true_monkey_index
+        } else {
+            monkey.false_monkey_index
+        };
+        let done = next <= item.monkey;
+        item.monkey = next;
+        if done {
+            return item;
+        }
+    }
+}
+
+/// The worried monkey business after any number of rounds, without simulating them all.
+fn residue_business(monkeys: &[Monkey], rounds: usize) -> u128 {
+    let mut total_inspections = vec![0u64; monkeys.len()];
+    for (index, monkey) in monkeys.iter().enumerate() {
+        for item in &monkey.items {
+            let initial = Item {
+                monkey: index,
+                residues: monkeys
+                    .iter()
+                    .map(|m| item.rem_euclid(m.division_number))
+                    .collect(),
+            };
+            // inspections[m][r] is how often monkey m inspected the item in the first r rounds
+            let mut inspections = vec![vec![0u64]; monkeys.len()];
+            let mut inspectors = Vec::new();
+            let (cycle, _) = cycle::find(initial, |item| {
+                let next = item_round(monkeys, item, &mut inspectors);
+                for (m, counts) in inspections.iter_mut().enumerate() {
+                    let count = inspectors.iter().filter(|&&i| i == m).count() as u64;
+                    counts.push(counts.last().unwrap() + count);
+                }
+                inspectors.clear();
+                next
+            });
+            for (total, counts) in zip(&mut total_inspections, &inspections) {
+                *total += cycle.extrapolate(counts, rounds);
+            }
+        }
+    }
+    total_inspections.sort_by(|a, b| b.cmp(a));
+    total_inspections
+        .iter()
+        .take(2)
+        .map(|&x| x as u128)
+        .product()
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input<'a> = Vec<Monkey>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
-    let start = Instant::now();
     let args = Cli::from_args();
-    let _ = std::fs::read_to_string(args.path.as_path()).unwrap();
-
-    println!(
-        "monkey business: {}",
-        monkey_business(input_monkeys(), monkey_round, 20)
-    );
-    println!(
-        "monkey business: {}",
-        monkey_business(input_monkeys(), worried_monkey_round, 10000)
-    );
-    println!("time: {}", start.elapsed().as_micros());
+    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let monkeys = or_exit(Day11::parse(&input));
+
+    println!("monkey business: {}", part1(&monkeys));
+    println!("monkey business: {}", part2(&monkeys));
+    if let Some(rounds) = args.rounds {
+        println!(
+            "monkey business after {} rounds: {}",
+            rounds,
+            residue_business(&monkeys, rounds)
+        );
+    }
 }
 
 #[cfg(test)]
@@ -174,96 +405,102 @@ mod tests {
     }
 
     fn init_monkeys() -> Vec<Monkey> {
-        vec![
-            Monkey {
-                items: vec![79, 98],
-                operation: Box::new(|old| old * 19),
-                division_number: 23,
-                true_monkey_index: 2,
-                false_monkey_index: 3,
-            },
-            Monkey {
-                items: vec![54, 65, 75, 74],
-                operation: Box::new(|old| old + 6),
-                division_number: 19,
-                true_monkey_index: 2,
-                false_monkey_index: 0,
-            },
+        Day11::parse(include_str!("../../input/day11-test")).unwrap()
+    }
+
+    #[test]
+    fn test_parse_monkey() {
+        let monkeys = init_monkeys();
+        assert_eq!(monkeys.len(), 4);
+        assert_eq!(
+            monkeys[2],
             Monkey {
                 items: vec![79, 60, 97],
-                operation: Box::new(|old| old * old),
+                operation: Operation::Mul(Operand::Old),
                 division_number: 13,
                 true_monkey_index: 1,
                 false_monkey_index: 3,
-            },
-            Monkey {
-                items: vec![74],
-                operation: Box::new(|old| old + 3),
-                division_number: 17,
-                true_monkey_index: 0,
-                false_monkey_index: 1,
-            },
-        ]
+            }
+        );
+        assert_eq!(monkeys[3].operation, Operation::Add(Operand::Constant(3)));
+        assert_eq!(
+            Day11::parse("Monkey 0:\n  Starting items: 79\n  Operation: new = old ^ 2\n")
+                .unwrap_err()
+                .to_string(),
+            "input line 3, column 24: expected \"+ \" or \"* \", got ^ 2"
+        );
     }
-}
 
-fn input_monkeys() -> Vec<Monkey> {
-    vec![
-        Monkey {
-            items: vec![54, 82, 90, 88, 86, 54],
-            operation: Box::new(|old| old * 7),
-            division_number: 11,
-            true_monkey_index: 2,
-            false_monkey_index: 6,
-        },
-        Monkey {
-            items: vec![91, 65],
-            operation: Box::new(|old| old * 13),
-            division_number: 5,
-            true_monkey_index: 7,
-            false_monkey_index: 4,
-        },
-        Monkey {
-            items: vec![62, 54, 57, 92, 83, 63, 63],
-            operation: Box::new(|old| old + 1),
-            division_number: 7,
-            true_monkey_index: 1,
-            false_monkey_index: 7,
-        },
-        Monkey {
-            items: vec![67, 72, 68],
-            operation: Box::new(|old| old * old),
-            division_number: 2,
-            true_monkey_index: 0,
-            false_monkey_index: 6,
-        },
-        Monkey {
-            items: vec![68, 89, 90, 86, 84, 57, 72, 84],
-            operation: Box::new(|old| old + 7),
-            division_number: 17,
-            true_monkey_index: 3,
-            false_monkey_index: 5,
-        },
-        Monkey {
-            items: vec![79, 83, 64, 58],
-            operation: Box::new(|old| old + 6),
-            division_number: 13,
-            true_monkey_index: 3,
-            false_monkey_index: 0,
-        },
-        Monkey {
-            items: vec![96, 72, 89, 70, 88],
-            operation: Box::new(|old| old + 4),
-            division_number: 3,
-            true_monkey_index: 1,
-            false_monkey_index: 2,
-        },
-        Monkey {
-            items: vec![79],
-            operation: Box::new(|old| old + 8),
-            division_number: 19,
-            true_monkey_index: 4,
-            false_monkey_index: 5,
-        },
-    ]
+    #[test]
+    fn test_parse_checks() {
+        let input = include_str!("../../input/day11-test");
+        let err = Day11::parse(&input.replace("divisible by 13", "divisible by 0")).unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (18, 22, "0"));
+        let err =
+            Day11::parse(&input.replace("true: throw to monkey 1", "true: throw to monkey 4"))
+                .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input line 19, column 30: expected a monkey other than 2 below 4, got 4"
+        );
+        let err =
+            Day11::parse(&input.replace("false: throw to monkey 3", "false: throw to monkey 0"))
+                .unwrap_err();
+        assert_eq!(err.expected, "a monkey other than 0 below 4");
+    }
+
+    #[test]
+    fn test_residue_business() {
+        let monkeys = init_monkeys();
+        assert_eq!(residue_business(&monkeys, 10000), 2713310158);
+        for rounds in [0, 1, 20, 1000] {
+            assert_eq!(
+                residue_business(&monkeys, rounds),
+                monkey_business(monkeys.clone(), worried_monkey_round, rounds as u32) as u128
+            );
+        }
+        // the product of two counts near 10^13 overflows a u64
+        let business = residue_business(&monkeys, 1_000_000_000_000);
+        assert!(business > u64::MAX as u128);
+    }
+
+    #[test]
+    fn test_apply_mod() {
+        let square = Operation::Mul(Operand::Old);
+        assert_eq!(square.apply_mod(4_000_000_006, 4_000_000_007), 1);
+        assert_eq!(square.apply_mod(i64::MAX - 1, i64::MAX), 1);
+        let add = Operation::Add(Operand::Constant(-3));
+        assert_eq!(add.apply_mod(i64::MAX - 1, i64::MAX), i64::MAX - 4);
+    }
+
+    #[test]
+    fn test_residue_business_self_throw() {
+        // both monkeys throw some items back to themselves, which the parser doesn't allow but
+        // the simulations still handle
+        let mut monkeys = Day11::parse(
+            "Monkey 0:
+  Starting items: 79, 98
+  Operation: new = old * 19
+  Test: divisible by 3
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 54, 65
+  Operation: new = old + 6
+  Test: divisible by 2
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+",
+        )
+        .unwrap();
+        monkeys[0].true_monkey_index = 0;
+        monkeys[1].false_monkey_index = 1;
+        for rounds in [1, 20, 1000] {
+            assert_eq!(
+                residue_business(&monkeys, rounds),
+                monkey_business(monkeys.clone(), worried_monkey_round, rounds as u32) as u128
+            );
+        }
+    }
 }
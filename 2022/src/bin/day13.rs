@@ -1,9 +1,20 @@
+use advent_of_code::{
+    input,
+    parse::{from_nom, or_exit, parse_lines, IResult, ParseError},
+    solution::{Solution, Streaming},
+};
+use itertools::Itertools;
 use nom::character::complete;
 use nom::multi::separated_list0;
-use nom::sequence::delimited;
+use nom::sequence::{preceded, terminated};
 use nom::{self, branch, combinator};
+use std::borrow::Borrow;
 use std::cmp::Ordering;
-use std::rc::Rc;
+use std::fmt::{self, Display, Formatter};
+use std::io::BufRead;
+use std::iter::zip;
+use std::slice;
+use std::str::FromStr;
 use std::time::Instant;
 use structopt::StructOpt;
 
@@ -13,23 +24,21 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-#[derive(Debug, PartialEq, Eq)]
-enum Packet {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
     Number(u32),
-    List(Vec<Rc<Packet>>),
+    List(Vec<Packet>),
 }
 
-fn packet(input: &str) -> nom::IResult<&str, Packet> {
+fn packet(input: &str) -> IResult<&str, Packet> {
     branch::alt((
-        delimited(
+        // past the bracket it has to be a list, so report errors from inside it
+        preceded(
             complete::char('['),
-            combinator::map(
-                separated_list0(complete::char(','), |p| {
-                    packet(p).map(|(a, b)| (a, Rc::new(b)))
-                }),
-                Packet::List,
-            ),
-            complete::char(']'),
+            combinator::cut(terminated(
+                combinator::map(separated_list0(complete::char(','), packet), Packet::List),
+                complete::char(']'),
+            )),
         ),
         combinator::map_res(complete::digit1, |n: &str| -> Result<Packet, _> {
             n.parse::<u32>().map(Packet::Number)
@@ -37,7 +46,33 @@ fn packet(input: &str) -> nom::IResult<&str, Packet> {
     ))(input)
 }
 
-fn cmp_list(left: &Vec<Rc<Packet>>, right: &Vec<Rc<Packet>>) -> Ordering {
+impl FromStr for Packet {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        from_nom(s, combinator::all_consuming(packet)(s))
+    }
+}
+
+impl Display for Packet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Packet::Number(n) => write!(f, "{}", n),
+            Packet::List(list) => {
+                write!(f, "[")?;
+                for (i, p) in list.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", p)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+fn cmp_list(left: &[Packet], right: &[Packet]) -> Ordering {
     let mut left_iter = left.iter();
     let mut right_iter = right.iter();
     loop {
@@ -46,7 +81,7 @@ fn cmp_list(left: &Vec<Rc<Packet>>, right: &Vec<Rc<Packet>>) -> Ordering {
             (None, Some(_)) => return Ordering::Less,
             (Some(_), None) => return Ordering::Greater,
             (Some(l), Some(r)) => {
-                let y = cmp(l, r);
+                let y = l.order(r);
                 match y {
                     Ordering::Equal => {
                         continue;
@@ -60,76 +95,186 @@ fn cmp_list(left: &Vec<Rc<Packet>>, right: &Vec<Rc<Packet>>) -> Ordering {
     }
 }
 
-fn cmp(left: &Packet, right: &Packet) -> Ordering {
-    match (left, right) {
-        (Packet::Number(l), Packet::Number(r)) => l.cmp(&r),
-        (Packet::Number(l), r @ Packet::List(_)) => {
-            cmp(&Packet::List(vec![Rc::new(Packet::Number(*l))]), &r)
+impl Packet {
+    /// The order the distress signal wants, which leaves different packets like `[[2]]` and `[2]`
+    /// tied.
+    fn order(&self, other: &Self) -> Ordering {
+        // a number compares as the list holding just that number
+        match (self, other) {
+            (Packet::Number(l), Packet::Number(r)) => l.cmp(r),
+            (l @ Packet::Number(_), Packet::List(r)) => cmp_list(slice::from_ref(l), r),
+            (Packet::List(l), r @ Packet::Number(_)) => cmp_list(l, slice::from_ref(r)),
+            (Packet::List(l), Packet::List(r)) => cmp_list(l, r),
         }
-        (l @ Packet::List(_), Packet::Number(r)) => {
-            cmp(&l, &Packet::List(vec![Rc::new(Packet::Number(*r))]))
+    }
+
+    /// Orders packets by how they are nested, numbers before lists.
+    fn structure(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Packet::Number(l), Packet::Number(r)) => l.cmp(r),
+            (Packet::Number(_), Packet::List(_)) => Ordering::Less,
+            (Packet::List(_), Packet::Number(_)) => Ordering::Greater,
+            (Packet::List(l), Packet::List(r)) => zip(l, r)
+                .map(|(l, r)| l.structure(r))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| l.len().cmp(&r.len())),
         }
-        (Packet::List(l), Packet::List(r)) => cmp_list(l, r),
     }
 }
 
-fn compare_all(input: &str) -> u32 {
-    let mut lines = input.lines();
-    let mut ordered_pair = vec![];
-    let mut i = 0;
-    loop {
-        let first_line = lines.next().expect("expected first line");
-        let second_line = lines.next().expect("expected second line");
-        let (_, first) = packet(first_line).unwrap();
-        let (_, second) = packet(second_line).unwrap();
-        i = i + 1;
-
-        match cmp(&first, &second) {
-            Ordering::Less => ordered_pair.push(i),
-            Ordering::Equal => ordered_pair.push(i),
-            Ordering::Greater => {}
-        }
-        if let None = lines.next() {
-            break;
-        }
+// ties in the signal's order are broken by structure, so only equal packets compare equal
+impl Ord for Packet {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.order(other).then_with(|| self.structure(other))
+    }
+}
+
+impl PartialOrd for Packet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
     }
+}
+
+fn dividers() -> [Packet; 2] {
+    ["[[2]]".parse().unwrap(), "[[6]]".parse().unwrap()]
+}
+
+/// A packet, or `None` for the blank lines between pairs.
+fn packet_line(line: &str) -> Result<Option<Packet>, ParseError> {
+    match line {
+        "" => Ok(None),
+        line => line.parse().map(Some),
+    }
+}
 
-    ordered_pair.iter().sum()
+/// Reads the packets one line at a time, skipping the blank lines between pairs.
+fn packets(input: impl BufRead) -> impl Iterator<Item = Result<Packet, ParseError>> {
+    parse_lines(input, packet_line).filter_map(Result::transpose)
 }
 
-fn sort_all(input: &str) -> Vec<Packet> {
-    let mut packets = input
-        .lines()
-        .filter(|line| line != &"")
-        .map(|line| packet(line).unwrap().1)
-        .collect::<Vec<Packet>>();
-    let mut markers = vec![packet("[[2]]").unwrap().1, packet("[[6]]").unwrap().1];
-    packets.append(&mut markers);
-    packets.sort_by(|a, b| cmp(a, b));
+fn compare_all(packets: &[Packet]) -> usize {
     packets
+        .chunks_exact(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0].order(&pair[1]).is_le())
+        .map(|(i, _)| i + 1)
+        .sum()
 }
 
-fn find_markers(sorted_output: &Vec<Packet>) -> (Option<usize>, Option<usize>) {
-    (
-        sorted_output
-            .iter()
-            .position(|x| *x == packet("[[2]]").unwrap().1)
-            .map(|x| x + 1),
-        sorted_output
-            .iter()
-            .position(|x| *x == packet("[[6]]").unwrap().1)
-            .map(|x| x + 1),
-    )
+fn sort_all(packets: impl IntoIterator<Item = Packet>, markers: &[Packet]) -> Vec<Packet> {
+    let mut packets = packets.into_iter().collect::<Vec<Packet>>();
+    packets.extend_from_slice(markers);
+    packets.sort();
+    packets
+}
+
+/// Where each marker ends up (counting from 1) in sorted output.
+fn find_markers(sorted_output: &[Packet], markers: &[Packet]) -> Vec<Option<usize>> {
+    markers
+        .iter()
+        .map(|marker| {
+            sorted_output
+                .iter()
+                .position(|x| x == marker)
+                .map(|x| x + 1)
+        })
+        .collect()
+}
+
+/// The same positions `find_markers` gives after `sort_all`, counted in one pass over
+/// the packets without holding or sorting them.
+fn marker_positions(
+    packets: impl IntoIterator<Item = impl Borrow<Packet>>,
+    markers: &[Packet],
+) -> Vec<usize> {
+    // the markers go after the packets, so they come after any equal packet
+    let mut positions: Vec<usize> = markers
+        .iter()
+        .enumerate()
+        .map(|(i, marker)| {
+            1 + markers
+                .iter()
+                .enumerate()
+                .filter(|&(j, other)| other < marker || (other == marker && j < i))
+                .count()
+        })
+        .collect();
+    for packet in packets {
+        for (position, marker) in positions.iter_mut().zip(markers) {
+            if packet.borrow() <= marker {
+                *position += 1;
+            }
+        }
+    }
+    positions
+}
+
+/// Both parts in one pass over a stream of packets, holding one pair at a time.
+fn decode_signal(packets: impl IntoIterator<Item = Packet>) -> (usize, usize) {
+    let mut ordered = 0;
+    let pairs = packets
+        .into_iter()
+        .tuples()
+        .enumerate()
+        .inspect(|(i, (left, right))| {
+            if left.order(right).is_le() {
+                ordered += i + 1;
+            }
+        })
+        .flat_map(|(_, (left, right))| [left, right]);
+    let positions = marker_positions(pairs, &dividers());
+    (ordered, positions.iter().product())
+}
+
+fn part1(packets: &[Packet]) -> usize {
+    compare_all(packets)
+}
+
+fn part2(packets: &[Packet]) -> usize {
+    let sorted = sort_all(packets.iter().cloned(), &dividers());
+    find_markers(&sorted, &dividers())
+        .into_iter()
+        .map(|position| position.expect("the markers are sorted with the packets"))
+        .product()
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input<'a> = Vec<Packet>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        packets(input.as_bytes()).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day13 {
+    type Line = Option<Packet>;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        packet_line(line)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        decode_signal(lines.flatten())
+    }
 }
 
 fn main() {
     let start_time = Instant::now();
     let args = Cli::from_args();
-    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    println!("solution 1: {}", compare_all(&input));
-    if let (Some(m1), Some(m2)) = find_markers(&sort_all(&input)) {
-        println!("solution 2: {:?}", m1 * m2);
-    }
+    let input = input::open(args.path.as_path()).unwrap();
+    let (ordered, decoder_key) = decode_signal(packets(input).map(or_exit));
+    println!("solution 1: {}", ordered);
+    println!("solution 2: {:?}", decoder_key);
     println!("time: {}", start_time.elapsed().as_micros());
 }
 
@@ -141,43 +286,43 @@ mod tests {
     #[test]
     fn test_compare_lists() {
         let left = Packet::List(vec![
-            Rc::new(Packet::Number(1)),
-            Rc::new(Packet::Number(1)),
-            Rc::new(Packet::Number(3)),
-            Rc::new(Packet::Number(1)),
-            Rc::new(Packet::Number(1)),
+            Packet::Number(1),
+            Packet::Number(1),
+            Packet::Number(3),
+            Packet::Number(1),
+            Packet::Number(1),
         ]);
         let right = Packet::List(vec![
-            Rc::new(Packet::Number(1)),
-            Rc::new(Packet::Number(1)),
-            Rc::new(Packet::Number(5)),
-            Rc::new(Packet::Number(1)),
-            Rc::new(Packet::Number(1)),
+            Packet::Number(1),
+            Packet::Number(1),
+            Packet::Number(5),
+            Packet::Number(1),
+            Packet::Number(1),
         ]);
-        assert_eq!(cmp(&left, &right), Ordering::Less);
+        assert_eq!(left.order(&right), Ordering::Less);
     }
 
     #[test]
     fn test_compare_example() {
         assert_eq!(
-            cmp(
-                &packet("[[1],[2,3,4]]").unwrap().1,
-                &packet("[[1],4]").unwrap().1
-            ),
+            packet("[[1],[2,3,4]]")
+                .unwrap()
+                .1
+                .order(&packet("[[1],4]").unwrap().1),
             Ordering::Less
         );
         assert_eq!(
-            cmp(
-                &packet("[[1],[]]").unwrap().1,
-                &packet("[[1],4]").unwrap().1
-            ),
+            packet("[[1],[]]")
+                .unwrap()
+                .1
+                .order(&packet("[[1],4]").unwrap().1),
             Ordering::Less
         );
         assert_eq!(
-            cmp(
-                &packet("[[10],[]]").unwrap().1,
-                &packet("[[11],4]").unwrap().1
-            ),
+            packet("[[10],[]]")
+                .unwrap()
+                .1
+                .order(&packet("[[11],4]").unwrap().1),
             Ordering::Less
         );
     }
@@ -188,19 +333,19 @@ mod tests {
         assert_eq!(packet("[]"), Ok(("", Packet::List(vec![]))));
         assert_eq!(
             packet("[1000]"),
-            Ok(("", Packet::List(vec![Rc::new(Packet::Number(1000))])))
+            Ok(("", Packet::List(vec![Packet::Number(1000)])))
         );
         assert_eq!(
             packet("[[1],[2,3,4]]"),
             Ok((
                 "",
                 Packet::List(vec![
-                    Rc::new(Packet::List(vec![Rc::new(Packet::Number(1))])),
-                    Rc::new(Packet::List(vec![
-                        Rc::new(Packet::Number(2)),
-                        Rc::new(Packet::Number(3)),
-                        Rc::new(Packet::Number(4)),
-                    ]))
+                    Packet::List(vec![Packet::Number(1)]),
+                    Packet::List(vec![
+                        Packet::Number(2),
+                        Packet::Number(3),
+                        Packet::Number(4),
+                    ])
                 ])
             ))
         );
@@ -209,11 +354,8 @@ mod tests {
             Ok((
                 "",
                 Packet::List(vec![
-                    Rc::new(Packet::List(vec![Rc::new(Packet::Number(1))])),
-                    Rc::new(Packet::List(vec![
-                        Rc::new(Packet::List(vec![])),
-                        Rc::new(Packet::List(vec![]))
-                    ]))
+                    Packet::List(vec![Packet::Number(1)]),
+                    Packet::List(vec![Packet::List(vec![]), Packet::List(vec![])])
                 ])
             ))
         );
@@ -222,7 +364,7 @@ mod tests {
     #[test]
     fn test_example() {
         let input = include_str!("../../input/day13-test");
-        assert_eq!(compare_all(input), 13);
+        assert_eq!(compare_all(&Day13::parse(input).unwrap()), 13);
     }
 
     #[test]
@@ -233,14 +375,89 @@ mod tests {
             .lines()
             .map(|line| packet(line).unwrap().1)
             .collect::<Vec<Packet>>();
-        let output = sort_all(input);
+        let output = sort_all(Day13::parse(input).unwrap(), &dividers());
         assert_eq!(output, exp_output);
     }
 
     #[test]
     fn test_find_markers() {
         let input = include_str!("../../input/day13-test");
-        let output = sort_all(input);
-        assert_eq!(find_markers(&output), (Some(10), Some(14)));
+        let packets = Day13::parse(input).unwrap();
+        let output = sort_all(packets.clone(), &dividers());
+        assert_eq!(find_markers(&output, &dividers()), vec![Some(10), Some(14)]);
+        assert_eq!(marker_positions(&packets, &dividers()), vec![10, 14]);
+        let markers: Vec<Packet> = ["[[6]]", "[]", "[[2]]", "[3]", "[[6]]"]
+            .iter()
+            .map(|m| m.parse().unwrap())
+            .collect();
+        let output = sort_all(packets.clone(), &markers);
+        let positions = marker_positions(&packets, &markers);
+        for (position, marker) in positions.iter().zip(&markers) {
+            assert_eq!(&output[position - 1], marker);
+        }
+        assert_eq!(positions[0] + 1, positions[4]);
+        assert_eq!(decode_signal(packets.clone()), (13, 140));
+        // [[2]] and [2] are tied in the signal's order, the one without the extra list goes first
+        let markers: Vec<Packet> = ["[[2]]", "[2]"]
+            .iter()
+            .map(|m| m.parse().unwrap())
+            .collect();
+        assert_eq!(markers[0].order(&markers[1]), Ordering::Equal);
+        assert_ne!(markers[0], markers[1]);
+        assert_eq!(markers[0].cmp(&markers[1]), Ordering::Greater);
+        assert_eq!(marker_positions(&packets, &markers), vec![11, 10]);
+        let output = sort_all(packets.clone(), &markers);
+        assert_eq!(find_markers(&output, &markers), vec![Some(11), Some(10)]);
+        assert_eq!(part2(&packets), 140);
+        // a packet without a pair is left out of the comparisons
+        let odd = &packets[..packets.len() - 1];
+        assert_eq!(compare_all(odd), 13);
+        assert_eq!(decode_signal(odd.to_vec()).0, 13);
+    }
+
+    #[test]
+    fn test_display() {
+        for line in include_str!("../../input/day13").lines() {
+            if !line.is_empty() {
+                assert_eq!(line.parse::<Packet>().unwrap().to_string(), line);
+            }
+        }
+        assert_eq!(
+            "[1,[2,]]".parse::<Packet>().unwrap_err().to_string(),
+            "input line 1, column 6: expected ']', got ,]]"
+        );
+        assert!("[1]2".parse::<Packet>().is_err());
+    }
+
+    #[test]
+    fn test_many_packets() {
+        // a long stream of generated packets, checked against sorting them
+        let mut seed = 13u64;
+        let mut lines = String::new();
+        let mut generated = Vec::new();
+        for _ in 0..20000 {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let (a, b, c) = (seed >> 60, (seed >> 50) % 16, (seed >> 40) % 3);
+            let line = match c {
+                0 => format!("[{},[{}]]", a, b),
+                1 => format!("[[{}],{}]", a, b),
+                _ => format!("[[[{}]],[],{}]", a, b),
+            };
+            lines.push_str(&line);
+            lines.push('\n');
+            generated.push(line.parse().unwrap());
+        }
+        let sorted = sort_all(generated.clone(), &dividers());
+        let positions = marker_positions(&generated, &dividers());
+        assert_eq!(
+            positions.iter().map(|&p| Some(p)).collect::<Vec<_>>(),
+            find_markers(&sorted, &dividers())
+        );
+        assert_eq!(
+            decode_signal(packets(lines.as_bytes()).map(Result::unwrap)),
+            (compare_all(&generated), positions[0] * positions[1])
+        );
     }
 }
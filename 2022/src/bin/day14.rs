@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::cmp::{max, min};
 use std::collections::BTreeMap;
 use std::time::Instant;
@@ -17,26 +21,23 @@ enum Item {
 
 type Positions = BTreeMap<(i32, i32), Item>;
 
-fn parse_paths(lines: &str) -> Vec<Vec<(i32, i32)>> {
+fn parse_paths(lines: &str) -> Result<Vec<Vec<(i32, i32)>>, ParseError> {
     lines
         .lines()
         .map(|line| {
             line.split(" -> ")
                 .map(|coord| {
-                    let mut c = coord.split(",");
-                    match (c.next(), c.next()) {
-                        (Some(x), Some(y)) => {
-                            (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap())
-                        }
-                        _ => panic!("unexpected"),
-                    }
+                    let point = coord
+                        .split_once(',')
+                        .and_then(|(x, y)| Some((x.parse::<i32>().ok()?, y.parse::<i32>().ok()?)));
+                    point.ok_or_else(|| ParseError::new(lines, coord, "a point like 498,4"))
                 })
-                .collect::<Vec<(i32, i32)>>()
+                .collect()
         })
-        .collect::<Vec<Vec<(i32, i32)>>>()
+        .collect()
 }
 
-fn paths_to_walls(paths: Vec<Vec<(i32, i32)>>) -> Positions {
+fn paths_to_walls(paths: &[Vec<(i32, i32)>]) -> Positions {
     let mut positions = BTreeMap::new();
     for path in paths.iter() {
         let mut path_points = path.iter();
@@ -106,8 +107,8 @@ fn add_floor(mut positions: Positions) -> Positions {
     positions
 }
 
-fn count_rounds(input: &str) -> u32 {
-    let mut positions = paths_to_walls(parse_paths(input));
+fn count_rounds(paths: &[Vec<(i32, i32)>]) -> u32 {
+    let mut positions = paths_to_walls(paths);
     let mut count_rounds = 0;
     loop {
         if let (new_positions, Some(_)) = sim_round(positions, true) {
@@ -120,8 +121,8 @@ fn count_rounds(input: &str) -> u32 {
     count_rounds
 }
 
-fn count_until_full(input: &str) -> u32 {
-    let mut positions = paths_to_walls(parse_paths(input));
+fn count_until_full(paths: &[Vec<(i32, i32)>]) -> u32 {
+    let mut positions = paths_to_walls(paths);
     positions = add_floor(positions);
     let mut count_rounds = 0;
     loop {
@@ -135,10 +136,30 @@ fn count_until_full(input: &str) -> u32 {
     count_rounds
 }
 
+pub struct Day14;
+
+// each part pours sand into a freshly parsed cave
+impl Solution for Day14 {
+    type Input<'a> = Vec<Vec<(i32, i32)>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_paths(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        count_rounds(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        count_until_full(input)
+    }
+}
+
 fn main() {
     let start_time = Instant::now();
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(parse_paths(&input));
     println!("solution 1: {}", count_rounds(&input));
     println!("solution 2: {}", count_until_full(&input));
     println!("time: {}", start_time.elapsed().as_micros());
@@ -151,7 +172,7 @@ mod tests {
     #[test]
     fn test_parse() {
         let input = include_str!("../../input/day14-test");
-        let p = parse_paths(input);
+        let p = parse_paths(input).unwrap();
         assert_eq!(
             p,
             vec![
@@ -164,7 +185,7 @@ mod tests {
     #[test]
     fn test_sim_round() {
         let input = include_str!("../../input/day14-test");
-        let positions = paths_to_walls(parse_paths(input));
+        let positions = paths_to_walls(&parse_paths(input).unwrap());
         if let (_, Some(sand)) = sim_round(positions, true) {
             assert_eq!(sand, (500, 8));
         } else {
@@ -175,7 +196,7 @@ mod tests {
     #[test]
     fn test_to_round_22() {
         let input = include_str!("../../input/day14-test");
-        let mut positions = paths_to_walls(parse_paths(input));
+        let mut positions = paths_to_walls(&parse_paths(input).unwrap());
         for _ in 0..21 {
             (positions, _) = sim_round(positions, true);
         }
@@ -188,13 +209,13 @@ mod tests {
 
     #[test]
     fn test_to_end() {
-        let input = include_str!("../../input/day14-test");
-        assert_eq!(count_rounds(input), 24);
+        let input = parse_paths(include_str!("../../input/day14-test")).unwrap();
+        assert_eq!(count_rounds(&input), 24);
     }
 
     #[test]
     fn test_count_until_full() {
-        let input = include_str!("../../input/day14-test");
-        assert_eq!(count_until_full(input), 93);
+        let input = parse_paths(include_str!("../../input/day14-test")).unwrap();
+        assert_eq!(count_until_full(&input), 93);
     }
 }
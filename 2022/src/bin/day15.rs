@@ -1,6 +1,10 @@
+use advent_of_code::{
+    interval::IntervalSet,
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::cmp::{max, min};
 use std::collections::BTreeSet;
 use std::time::Instant;
 use structopt::StructOpt;
@@ -12,7 +16,7 @@ struct Cli {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
-struct SensorInfo {
+pub struct SensorInfo {
     sensor: (i32, i32),
     beacon: (i32, i32),
 }
@@ -42,120 +46,87 @@ impl SensorInfo {
     }
 }
 
-fn beacon_covered_by_span(beacon: (i32, i32), y: i32, span: (i32, i32)) -> bool {
-    // does not lay on intersection line
-    if beacon.1 != y {
-        return false;
-    }
-    span.0 <= beacon.0 && span.1 >= beacon.0
-}
-
-fn calc_cover(input: &str, intersect_y: i32) -> i32 {
-    let infos = parse(input);
+fn calc_cover(infos: &[SensorInfo], intersect_y: i32) -> i32 {
     let beacons = infos
         .iter()
         .map(|i| i.beacon)
         .collect::<BTreeSet<(i32, i32)>>();
-    let spans = infos.iter().filter_map(|i| i.intersect_y(intersect_y));
-    let grouped_spans = group_spans(spans);
-    let area_covered_by_span: i32 = grouped_spans
+    let covered = group_spans(infos.iter().filter_map(|i| i.intersect_y(intersect_y)));
+    let beacons_in_span = beacons
         .iter()
-        .map(|(start, end)| end - start + 1)
-        .sum();
-    let mut beacons_in_span = 0;
-    for beacon in beacons.iter() {
-        for span in grouped_spans.iter() {
-            if beacon_covered_by_span(*beacon, intersect_y, *span) {
-                beacons_in_span = beacons_in_span + 1;
-                continue;
-            }
-        }
-    }
-    area_covered_by_span - beacons_in_span
+        .filter(|&&(x, y)| y == intersect_y && covered.contains(x))
+        .count();
+    covered.len() - beacons_in_span as i32
 }
 
-fn calc_spot(input: &str, most: i32) -> i64 {
-    let infos = parse(input);
+fn calc_spot(infos: &[SensorInfo], most: i32) -> i64 {
     for y in 0..=most {
         let spans = infos.iter().filter_map(|i| i.intersect_y(y));
-        let restricted_spans = restrict_spans(spans, most);
-        let grouped_spans = group_spans(restricted_spans);
-        if grouped_spans.len() != 1 {
-            return (y as i64) + ((grouped_spans[0].1 + 1) as i64) * 4_000_000;
+        let restricted = group_spans(spans).intersection(&IntervalSet::from(0..=most));
+        // a gap between two covered groups
+        let mut groups = restricted.iter();
+        if let (Some(first), Some(_)) = (groups.next(), groups.next()) {
+            return (y as i64) + (first.end as i64) * 4_000_000;
         }
     }
     unreachable!();
 }
 
-fn restrict_spans(
-    spans: impl Iterator<Item = (i32, i32)>,
-    most: i32,
-) -> impl Iterator<Item = (i32, i32)> {
-    spans.filter_map(move |(x0, x1)| {
-        if x1 < 0 {
-            return None;
-        }
-        if x0 > most {
-            return None;
-        }
-        Some((max(x0, 0), min(x1, most)))
-    })
-}
-
-fn group_spans(spans: impl Iterator<Item = (i32, i32)>) -> Vec<(i32, i32)> {
-    let mut sorted_spans = Vec::with_capacity(128);
-    sorted_spans.extend(spans);
-    sorted_spans.sort();
-    let mut end_spans = Vec::with_capacity(128);
-    for span in sorted_spans.iter() {
-        let (start, end) = *span;
-        if let Some(last_span @ (last_start, last_end)) = end_spans.pop() {
-            if start <= last_end {
-                // overlap. merge them
-                end_spans.push((last_start, max(end, last_end)));
-            } else if last_end + 1 == start {
-                // ends touching. merge them
-                end_spans.push((last_start, end));
-            } else {
-                // keep seperate
-                end_spans.push(last_span);
-                end_spans.push(span.clone());
-            }
-        } else {
-            end_spans.push(span.clone());
-        }
-    }
-    end_spans
+fn group_spans(spans: impl Iterator<Item = (i32, i32)>) -> IntervalSet<i32> {
+    spans.map(|(x0, x1)| x0..=x1).collect()
 }
 
 lazy_static! {
     static ref RE: Regex =
-        Regex::new(r"Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)")
+        Regex::new(r"^Sensor at x=(-?\d+), y=(-?\d+): closest beacon is at x=(-?\d+), y=(-?\d+)$")
             .unwrap();
 }
 
-fn parse(input: &str) -> Vec<SensorInfo> {
-    RE.captures_iter(input)
-        .map(|m| {
-            (
-                (
-                    m.get(1).unwrap().as_str().parse::<i32>().unwrap(),
-                    m.get(2).unwrap().as_str().parse::<i32>().unwrap(),
-                ),
-                (
-                    m.get(3).unwrap().as_str().parse::<i32>().unwrap(),
-                    m.get(4).unwrap().as_str().parse::<i32>().unwrap(),
-                ),
-            )
+fn parse(input: &str) -> Result<Vec<SensorInfo>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let m = RE
+                .captures(line)
+                .ok_or_else(|| ParseError::new(input, line, "a sensor and its closest beacon"))?;
+            let coordinate = |i| {
+                let text = m.get(i).unwrap().as_str();
+                text.parse::<i32>()
+                    .map_err(|_| ParseError::new(input, text, "a coordinate"))
+            };
+            let tuple = (
+                (coordinate(1)?, coordinate(2)?),
+                (coordinate(3)?, coordinate(4)?),
+            );
+            Ok(tuple.into())
         })
-        .map(|x| x.into())
         .collect()
 }
 
+pub struct Day15;
+
+// each part scans the sensors with its own bounds
+impl Solution for Day15 {
+    type Input<'a> = Vec<SensorInfo>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        calc_cover(input, 2_000_000)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        calc_spot(input, 4_000_000)
+    }
+}
+
 fn main() {
     let start_time = Instant::now();
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(parse(&input));
     println!("solution 1: {}", calc_cover(&input, 2_000_000));
     println!("solution 2: {}", calc_spot(&input, 4_000_000));
     println!("time: {}", start_time.elapsed().as_micros());
@@ -168,7 +139,7 @@ mod tests {
     #[test]
     fn test_parse() {
         let input = include_str!("../../input/day15-test");
-        let infos = parse(input);
+        let infos = parse(input).unwrap();
         assert_eq!(infos.len(), 14);
     }
 
@@ -186,15 +157,15 @@ mod tests {
     #[test]
     fn test_examples() {
         let input = include_str!("../../input/day15-test");
-        let infos = parse(input);
+        let infos = parse(input).unwrap();
         let spans = infos.iter().filter_map(|i| i.intersect_y(10));
         let grouped_spans = group_spans(spans);
-        assert_eq!(grouped_spans, vec![(-2, 24)]);
+        assert_eq!(grouped_spans, IntervalSet::from(-2..=24));
     }
 
     #[test]
     fn test_cover() {
-        let input = include_str!("../../input/day15-test");
+        let input = &parse(include_str!("../../input/day15-test")).unwrap();
         assert_eq!(calc_cover(input, 9), 25);
         assert_eq!(calc_cover(input, 10), 26);
         assert_eq!(calc_cover(input, 11), 28);
@@ -202,7 +173,7 @@ mod tests {
 
     #[test]
     fn test_spot_search() {
-        let input = include_str!("../../input/day15-test");
+        let input = &parse(include_str!("../../input/day15-test")).unwrap();
         assert_eq!(calc_spot(input, 20), 56000011);
     }
 }
@@ -1,9 +1,12 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use lazy_static::lazy_static;
 use regex::{Regex, RegexBuilder};
 use std::cmp::{max, min, Reverse};
 use std::collections::{BTreeMap, HashMap, VecDeque};
 use std::collections::{BTreeSet, BinaryHeap};
-use std::time::Instant;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -16,7 +19,7 @@ struct Cli {
 }
 
 #[derive(Clone, Debug)]
-struct Valve<'a> {
+pub struct Valve<'a> {
     flow_rate: u32,
     tunnels: Vec<&'a str>,
 }
@@ -30,22 +33,21 @@ lazy_static! {
     .unwrap();
 }
 
-fn parse<'a>(input: &'a str) -> BTreeMap<&'a str, Valve> {
-    RE_STATEMENT
-        .captures_iter(input)
-        .map(|m| {
-            (
-                m.get(1).unwrap().as_str(),
-                Valve {
-                    flow_rate: m.get(2).unwrap().as_str().parse::<u32>().unwrap(),
-                    tunnels: m
-                        .get(3)
-                        .unwrap()
-                        .as_str()
-                        .split(", ")
-                        .collect::<Vec<&str>>(),
-                },
-            )
+fn parse<'a>(input: &'a str) -> Result<BTreeMap<&'a str, Valve<'a>>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            let (_, [name, flow_rate, tunnels]) = RE_STATEMENT
+                .captures(line)
+                .ok_or_else(|| {
+                    ParseError::new(input, line, "Valve XX has flow rate=N; tunnels lead to ...")
+                })?
+                .extract();
+            let flow_rate = flow_rate
+                .parse::<u32>()
+                .map_err(|_| ParseError::new(input, flow_rate, "a flow rate"))?;
+            let tunnels = tunnels.split(", ").collect::<Vec<&str>>();
+            Ok((name, Valve { flow_rate, tunnels }))
         })
         .collect()
 }
@@ -83,7 +85,6 @@ struct State<'a> {
     remaining_time: u32,
     to_visit: BTreeSet<&'a str>,
     agents: BinaryHeap<Agent<'a>>,
-    trace: Vec<Tracepoint<'a>>,
 }
 
 impl PartialOrd for State<'_> {
@@ -106,27 +107,6 @@ impl PartialEq for State<'_> {
 
 impl Eq for State<'_> {}
 
-#[derive(Debug, Clone)]
-struct Tracepoint<'a> {
-    pressure: u32,
-    total_flow_rate: u32,
-    remaining_time: u32,
-    to_visit: BTreeSet<&'a str>,
-    agents: BinaryHeap<Agent<'a>>,
-}
-
-impl<'a> Tracepoint<'a> {
-    fn new(state: &State<'a>) -> Self {
-        Self {
-            pressure: state.pressure,
-            total_flow_rate: state.total_flow_rate,
-            remaining_time: state.remaining_time,
-            to_visit: state.to_visit.clone(),
-            agents: state.agents.clone(),
-        }
-    }
-}
-
 fn distance<'a>(
     map: &BTreeMap<&'a str, Valve<'a>>,
     from: &'a str,
@@ -175,30 +155,20 @@ fn search(map: &BTreeMap<&str, Valve>, agent_count: u32, time: u32) -> u32 {
     for i in 0..agent_count {
         agents.push(Agent::new(i, time));
     }
-    let mut init_state = State {
+    let init_state = State {
         pressure: 0,
         total_flow_rate: 0,
         remaining_time: time,
         to_visit,
         agents,
-        trace: vec![],
     };
-    init_state.trace.push(Tracepoint::new(&init_state));
     queue.push(init_state);
     let distance_cache = distance_cache(map);
-    let mut count = 0;
-    let mut best_trace = vec![];
 
     while let Some(state) = queue.pop() {
-        count += 1;
         // out of time
         if state.remaining_time == 0 {
-            let old_best = best_pressure;
             best_pressure = max(best_pressure, state.pressure);
-            if old_best != best_pressure {
-                println!("first");
-                best_trace = state.trace.clone();
-            }
             continue;
         }
 
@@ -266,32 +236,44 @@ fn search(map: &BTreeMap<&str, Valve>, agent_count: u32, time: u32) -> u32 {
                 state.remaining_time - next_updatable_agent.arrival_at_remaining_time;
             let next_pressure = state.pressure + state.total_flow_rate * landing_time;
 
-            let mut next_state = State {
+            let next_state = State {
                 pressure: next_pressure,
                 total_flow_rate: state.total_flow_rate + next_valve_updated.flow_rate,
                 remaining_time: next_updatable_agent.arrival_at_remaining_time,
                 to_visit: next_to_visit,
                 agents: next_agents,
-                trace: state.trace.clone(),
             };
-            next_state.trace.push(Tracepoint::new(&next_state));
 
             queue.push(next_state);
         }
     }
-    println!("count: {}", count);
-    println!("trace: {:?}", best_trace);
     best_pressure
 }
 
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input<'a> = BTreeMap<&'a str, Valve<'a>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search(input, 1, 30)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search(input, 2, 26)
+    }
+}
+
 fn main() {
-    let start_time = Instant::now();
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    let map = parse(&input);
+    let map = or_exit(parse(&input));
     //println!("solution 1: {}", search(&map, 1, args.minutes));
     println!("solution 2: {}", search(&map, 2, 26));
-    println!("time: {}", start_time.elapsed().as_micros());
 }
 
 #[cfg(test)]
@@ -301,13 +283,13 @@ mod tests {
     #[test]
     fn test_parse() {
         let input = include_str!("../../input/day16-test");
-        let _ = parse(input);
+        let _ = parse(input).unwrap();
     }
 
     #[test]
     fn test_distances() {
         let input = include_str!("../../input/day16-test");
-        let map = parse(input);
+        let map = parse(input).unwrap();
         let mut to_buffer = Vec::new();
         distance(&map, "AA", &mut to_buffer);
         assert!(to_buffer.contains(&("DD", 1)));
@@ -319,7 +301,7 @@ mod tests {
     #[test]
     fn test_heap_big_search() {
         let input = include_str!("../../input/day16-test");
-        let map = parse(input);
+        let map = parse(input).unwrap();
         assert_eq!(search(&map, 1, 30), 1651);
     }
 }
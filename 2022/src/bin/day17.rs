@@ -1,5 +1,8 @@
-use sha2::Digest;
-use std::{collections::HashMap, time::Instant};
+use advent_of_code::{
+    cycle::CycleFinder,
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -9,7 +12,7 @@ struct Cli {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Direction {
+pub enum Direction {
     Left,
     Right,
 }
@@ -20,12 +23,6 @@ struct Commands {
     index: usize,
 }
 
-impl Commands {
-    fn as_bytes(&self) -> [u8; 8] {
-        self.index.to_ne_bytes()
-    }
-}
-
 impl Iterator for Commands {
     type Item = Direction;
     fn next(&mut self) -> Option<Self::Item> {
@@ -35,17 +32,27 @@ impl Iterator for Commands {
     }
 }
 
-fn parse(input: &str) -> Commands {
-    let buffer: Vec<Direction> = input
-        .chars()
-        .filter_map(|c| match c {
-            '<' => Some(Direction::Left),
-            '>' => Some(Direction::Right),
-            _ => None,
+impl Commands {
+    fn new(buffer: &[Direction]) -> Self {
+        Commands {
+            buffer: buffer.to_vec(),
+            index: 0,
+        }
+    }
+}
+
+fn parse(input: &str) -> Result<Vec<Direction>, ParseError> {
+    let jets = input.trim_end();
+    if jets.is_empty() {
+        return Err(ParseError::new(input, jets, "< or >"));
+    }
+    jets.char_indices()
+        .map(|(i, c)| match c {
+            '<' => Ok(Direction::Left),
+            '>' => Ok(Direction::Right),
+            _ => Err(ParseError::new(input, &jets[i..], "< or >")),
         })
-        .collect();
-    let cycle = buffer.len();
-    Commands { buffer, index: 0 }
+        .collect()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -121,9 +128,6 @@ impl ShapeGenerator {
     fn new() -> Self {
         ShapeGenerator { index: 4 }
     }
-    fn as_bytes(&self) -> [u8; 8] {
-        self.index.to_ne_bytes()
-    }
 }
 
 impl Iterator for ShapeGenerator {
@@ -252,9 +256,9 @@ fn run_round(
 }
 
 type Height = usize;
-fn run(input: &str, times: u64) -> Height {
+fn run(jets: &[Direction], times: u64) -> Height {
     let mut chamber = Chamber::new();
-    let mut directions = parse(input);
+    let mut directions = Commands::new(jets);
     let mut shape_generator = ShapeGenerator::new();
 
     for _ in 0..times {
@@ -263,63 +267,68 @@ fn run(input: &str, times: u64) -> Height {
     chamber.height()
 }
 
-fn run_with_cycle_search(input: &str, times: u64) -> Height {
+fn run_with_cycle_search(jets: &[Direction], times: u64) -> Height {
     let mut chamber = Chamber::new();
-    let mut directions = parse(input);
+    let mut directions = Commands::new(jets);
     let mut shape_generator = ShapeGenerator::new();
-    let mut number_of_rocks = 0;
-    let mut previous_positions = HashMap::new();
-    let mut skipped_height = 0;
-    let mut jumped = false;
-
-    while number_of_rocks < times {
-        let shape = &shape_generator.next().unwrap();
-        let mut pos = Position::new_shape_position(&chamber);
-
-        number_of_rocks += 1;
-        let end_pos = loop {
-            let next_pos = move_horizontal(&chamber, pos, shape, directions.next().unwrap());
-            pos = match move_vertical(&chamber, next_pos.clone(), shape) {
-                Some(p) => p,
-                None => break next_pos,
-            };
-        };
-        chamber = chamber.set(shape, &end_pos);
-
-        // take hash once enough height accumulated
-        // and not yet jumpped to the future
-        if chamber.height() > 40 && !jumped {
-            let mut hasher = sha2::Sha256::new();
-            hasher.update(&chamber.top_rows(30));
-            hasher.update(&shape_generator.as_bytes());
-            hasher.update(directions.as_bytes());
-            let hash = hasher.finalize();
-            if let Some((last_rocks, last_height)) = previous_positions.get(&hash) {
-                // jump to the future
-                let cycle_size = number_of_rocks - last_rocks;
-                let skipped_cycles = (times - number_of_rocks).div_euclid(cycle_size);
-                number_of_rocks = number_of_rocks + cycle_size * skipped_cycles;
-
-                skipped_height = (chamber.height() - last_height) * (skipped_cycles as usize);
-                jumped = true;
-            } else {
-                previous_positions.insert(hash, (number_of_rocks, chamber.height()));
+    let mut heights = vec![chamber.height()];
+    let mut finder = CycleFinder::new();
+
+    for number_of_rocks in 1..=times as usize {
+        (chamber, _) = run_round(chamber, &shape_generator.next().unwrap(), &mut directions);
+        heights.push(chamber.height());
+
+        // compare states once enough height accumulated
+        if chamber.height() > 40 {
+            let state = (
+                chamber.top_rows(30).to_vec(),
+                shape_generator.index,
+                directions.index,
+            );
+            if let Some(cycle) = finder.check(number_of_rocks, state) {
+                return cycle.extrapolate(&heights, times as usize);
             }
         }
     }
-    chamber.height() + skipped_height
+    chamber.height()
+}
+
+fn part1(jets: &[Direction]) -> Height {
+    run(jets, 2022)
+}
+
+fn part2(jets: &[Direction]) -> Height {
+    run_with_cycle_search(jets, 1_000_000_000_000)
+}
+
+pub struct Day17;
+
+// each part runs the rock simulation from scratch
+impl Solution for Day17 {
+    type Input<'a> = Vec<Direction>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
 }
 
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    let start_time = Instant::now();
+    let input = or_exit(parse(&input));
     println!("solution1 {:?}", run(&input, 2022));
     println!(
         "solution2 {:?}",
         run_with_cycle_search(&input, 1_000_000_000_000)
     );
-    println!("time: {}", start_time.elapsed().as_micros());
 }
 
 #[cfg(test)]
@@ -403,7 +412,7 @@ mod tests {
     #[test]
     fn test_round() {
         let chamber = Chamber::new();
-        let mut directions = parse(include_str!("../../input/day17-test"));
+        let mut directions = Commands::new(&parse(include_str!("../../input/day17-test")).unwrap());
         let (chamber, _) = run_round(chamber, &Shape::horizontal_line(), &mut directions);
         let get = |p| chamber.get(&Position::new(&chamber, p).unwrap());
         assert_eq!(get((0, 0)), false);
@@ -438,7 +447,7 @@ mod tests {
 
     #[test]
     fn test_direction_generator() {
-        let mut directions = parse(include_str!("../../input/day17-test"));
+        let mut directions = Commands::new(&parse(include_str!("../../input/day17-test")).unwrap());
         assert_eq!(directions.next(), Some(Direction::Right));
         assert_eq!(directions.next(), Some(Direction::Right));
         assert_eq!(directions.next(), Some(Direction::Right));
@@ -458,14 +467,14 @@ mod tests {
 
     #[test]
     fn test_example() {
-        assert_eq!(run(include_str!("../../input/day17-test"), 2022), 3068);
+        let jets = parse(include_str!("../../input/day17-test")).unwrap();
+        assert_eq!(run(&jets, 2022), 3068);
     }
 
     #[test]
     fn test_example_with_cycles() {
-        assert_eq!(
-            run_with_cycle_search(include_str!("../../input/day17-test"), 2022),
-            3068
-        );
+        let jets = parse(include_str!("../../input/day17-test")).unwrap();
+        assert_eq!(run_with_cycle_search(&jets, 2022), 3068);
+        assert!(parse("<>x\n").is_err());
     }
 }
@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::cmp::{max, min};
 use std::collections::HashSet;
 use std::time::Instant;
@@ -11,18 +15,24 @@ struct Cli {
 
 type Point = (i32, i32, i32);
 
-fn parse(input: &str) -> HashSet<Point> {
+fn parse(input: &str) -> Result<HashSet<Point>, ParseError> {
     input
         .lines()
         .map(|line| {
-            let mut coords = line.split(',');
-            (
-                coords.next().unwrap().parse().unwrap(),
-                coords.next().unwrap().parse().unwrap(),
-                coords.next().unwrap().parse().unwrap(),
-            )
+            let coords = line
+                .split(',')
+                .map(|coord| {
+                    coord
+                        .parse()
+                        .map_err(|_| ParseError::new(input, coord, "a coordinate"))
+                })
+                .collect::<Result<Vec<i32>, _>>()?;
+            match coords[..] {
+                [x, y, z] => Ok((x, y, z)),
+                _ => Err(ParseError::new(input, line, "three coordinates")),
+            }
         })
-        .collect::<HashSet<Point>>()
+        .collect()
 }
 
 fn adjacent(p: &Point) -> impl Iterator<Item = Point> + '_ {
@@ -92,11 +102,29 @@ fn search_external(droplet: &HashSet<Point>) -> u32 {
         .sum()
 }
 
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input<'a> = HashSet<Point>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        area(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search_external(input)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
     let start_time = Instant::now();
-    let points = parse(&input);
+    let points = or_exit(parse(&input));
     println!("solution 1: {}", area(&points));
     println!("solution 2: {}", search_external(&points));
     println!("time: {}", start_time.elapsed().as_micros());
@@ -108,10 +136,12 @@ mod tests {
 
     #[test]
     fn test_parser() {
-        let input = parse(include_str!("../../input/day18-test"));
+        let input = parse(include_str!("../../input/day18-test")).unwrap();
         assert_eq!(input.len(), 13);
         assert!(input.contains(&(2, 2, 2)));
         assert!(input.contains(&(1, 2, 2)));
+        let err = parse("2,2,2\n1,2\n").unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (2, "three coordinates"));
     }
 
     #[test]
@@ -125,13 +155,13 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let input = parse(include_str!("../../input/day18-test"));
+        let input = parse(include_str!("../../input/day18-test")).unwrap();
         assert_eq!(area(&input), 64);
     }
 
     #[test]
     fn test_outside_detection() {
-        let droplet = parse(include_str!("../../input/day18-test"));
+        let droplet = parse(include_str!("../../input/day18-test")).unwrap();
         let outside = outside(&droplet);
         assert!(!outside.contains(&(2, 2, 5)));
         assert!(!outside
@@ -144,7 +174,7 @@ mod tests {
 
     #[test]
     fn test_search_external() {
-        let droplet = parse(include_str!("../../input/day18-test"));
+        let droplet = parse(include_str!("../../input/day18-test")).unwrap();
         assert_eq!(search_external(&droplet), 58);
     }
 }
@@ -1,10 +1,13 @@
+use advent_of_code::{
+    parse::{self, from_nom, or_exit, tag, IResult},
+    solution::Solution,
+};
 use nom::{
-    bytes::complete::tag,
     character::complete::{self, multispace0},
     combinator::map,
     error::ParseError,
+    multi::many1,
     sequence::{delimited, separated_pair, tuple},
-    IResult,
 };
 use rayon::prelude::{IntoParallelRefIterator, ParallelIterator};
 use std::{cmp::max, collections::BinaryHeap, hash::Hash, time::Instant};
@@ -60,7 +63,7 @@ struct BotCost {
 }
 
 #[derive(Debug, Clone)]
-struct Blueprint {
+pub struct Blueprint {
     geode: BotCost,
     obsidian: BotCost,
     clay: BotCost,
@@ -73,9 +76,9 @@ type BlueprintID = u32;
 /// trailing whitespace, returning the output of `inner`.
 fn ws<'a, F: 'a, O, E: ParseError<&'a str>>(
     inner: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+) -> impl FnMut(&'a str) -> nom::IResult<&'a str, O, E>
 where
-    F: Fn(&'a str) -> IResult<&'a str, O, E>,
+    F: Fn(&'a str) -> nom::IResult<&'a str, O, E>,
 {
     delimited(multispace0, inner, multispace0)
 }
@@ -305,36 +308,55 @@ fn search(resources: Resources, blueprint: Blueprint, minutes: u32) -> u32 {
     best_geode.unwrap()
 }
 
-fn score_blueprint(input: &str) -> u32 {
+fn blueprints(input: &str) -> Result<Vec<(BlueprintID, Blueprint)>, parse::ParseError> {
+    from_nom(input, many1(blueprint)(input))
+}
+
+fn score_blueprint(blueprints: &[(BlueprintID, Blueprint)]) -> u32 {
     let start_resource = Resources {
         ore: 0,
         clay: 0,
         obsidian: 0,
         geode: 0,
     };
-    let lines: Vec<&str> = input.lines().collect();
-    lines
+    blueprints
         .par_iter()
-        .map(|line| blueprint(line).unwrap())
-        .map(|(_, (id, blueprint))| id * search(start_resource.clone(), blueprint, 24))
+        .map(|(id, blueprint)| id * search(start_resource.clone(), blueprint.clone(), 24))
         .sum()
 }
 
-fn score_stolen(input: &str) -> u32 {
+fn score_stolen(blueprints: &[(BlueprintID, Blueprint)]) -> u32 {
     let start_resource = Resources {
         ore: 0,
         clay: 0,
         obsidian: 0,
         geode: 0,
     };
-    let lines: Vec<&str> = input.lines().take(3).collect();
-    lines
+    blueprints[..blueprints.len().min(3)]
         .par_iter()
-        .map(|line| blueprint(line).unwrap())
-        .map(|(_, (_, blueprint))| search(start_resource.clone(), blueprint, 32))
+        .map(|(_, blueprint)| search(start_resource.clone(), blueprint.clone(), 32))
         .reduce(|| 1, |a, x| a * x)
 }
 
+pub struct Day19;
+
+// each part scores a different subset of blueprints
+impl Solution for Day19 {
+    type Input<'a> = Vec<(BlueprintID, Blueprint)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, parse::ParseError> {
+        blueprints(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        score_blueprint(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        score_stolen(input)
+    }
+}
+
 fn main() {
     rayon::ThreadPoolBuilder::new()
         .num_threads(4)
@@ -342,6 +364,7 @@ fn main() {
         .unwrap();
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(blueprints(&input));
     let start_time = Instant::now();
 
     println!("solution 1: {}", score_blueprint(&input));
@@ -378,7 +401,7 @@ mod tests {
 
     #[test]
     fn test_score() {
-        let input = include_str!("../../input/day19-test");
-        assert_eq!(score_blueprint(input), 33);
+        let input = blueprints(include_str!("../../input/day19-test")).unwrap();
+        assert_eq!(score_blueprint(&input), 33);
     }
 }
@@ -1,6 +1,9 @@
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -17,28 +20,29 @@ enum Shape {
 }
 
 #[derive(Clone, Debug)]
-struct Line {
+pub struct Line {
     opponent: Shape,
     mine: Shape,
 }
 
-#[allow(dead_code)]
-fn parse(line: &str) -> Line {
-    let mut split = line.split(' ');
-    Line {
-        opponent: match split.next().unwrap() {
+fn parse(line: &str) -> Result<Line, ParseError> {
+    let (opponent, mine) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(line, line, "two shapes"))?;
+    Ok(Line {
+        opponent: match opponent {
             "A" => Shape::Rock,
             "B" => Shape::Paper,
             "C" => Shape::Scissor,
-            _ => panic!("Unexpected"),
+            _ => return Err(ParseError::new(line, opponent, "A, B or C")),
         },
-        mine: match split.next().unwrap() {
+        mine: match mine {
             "X" => Shape::Rock,
             "Y" => Shape::Paper,
             "Z" => Shape::Scissor,
-            _ => panic!("Unexpected"),
+            _ => return Err(ParseError::new(line, mine, "X, Y or Z")),
         },
-    }
+    })
 }
 
 fn wins(shape: Shape) -> Shape {
@@ -103,34 +107,74 @@ fn score(line: Line) -> usize {
     outcome_score + shape_score
 }
 
-fn parse_two(line: &str) -> Line {
-    let mut split = line.split(' ');
-    let opponent = match split.next().unwrap() {
-        "A" => Shape::Rock,
-        "B" => Shape::Paper,
-        "C" => Shape::Scissor,
-        _ => panic!("Unexpected"),
-    };
-    let x = Line {
+// In part two X, Y and Z say how the round has to end: lose, draw or win
+fn parse_two(line: Line) -> Line {
+    let opponent = line.opponent;
+    Line {
         opponent: opponent.clone(),
-        mine: match split.next().unwrap() {
-            "X" => loses(opponent),
-            "Y" => opponent,
-            "Z" => wins(opponent),
-            _ => panic!("Unexpected"),
+        mine: match line.mine {
+            Shape::Rock => loses(opponent),
+            Shape::Paper => opponent,
+            Shape::Scissor => wins(opponent),
         },
-    };
-    println!("{:?}", x);
-    x
+    }
+}
+
+fn part1(lines: &[Line]) -> usize {
+    lines.iter().cloned().fold(0, |x, y| score(y) + x)
+}
+
+fn part2(lines: &[Line]) -> usize {
+    lines
+        .iter()
+        .cloned()
+        .map(parse_two)
+        .fold(0, |x, y| score(y) + x)
+}
+
+// Scores each line both ways in one pass
+fn scores(lines: impl IntoIterator<Item = Line>) -> (usize, usize) {
+    lines.into_iter().fold((0, 0), |(first, second), line| {
+        (first + score(line.clone()), second + score(parse_two(line)))
+    })
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<Line>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), parse).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day2 {
+    type Line = Line;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        parse(line)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        scores(lines)
+    }
 }
 
 fn main() {
     let args = Cli::from_args();
-    let input = File::open(args.path.as_path()).unwrap();
-    let lines = BufReader::new(input).lines();
-    //let first_score = lines.map(|x| parse(&x.unwrap())).fold(0,|x,y| score(y)+x);
-    let second_score = lines
-        .map(|x| parse_two(&x.unwrap()))
+    let input = input::open(args.path.as_path()).unwrap();
+    let second_score = parse_lines(input, parse)
+        .map(or_exit)
+        .map(parse_two)
         .fold(0, |x, y| score(y) + x);
     println!("{}", second_score);
 }
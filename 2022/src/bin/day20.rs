@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use std::time::Instant;
 use structopt::StructOpt;
 
@@ -37,8 +41,14 @@ impl State {
     }
 }
 
-fn parse(input: &str) -> Vec<isize> {
-    input.lines().map(|line| line.parse().unwrap()).collect()
+fn parse(input: &str) -> Result<Vec<isize>, ParseError> {
+    input
+        .lines()
+        .map(|line| {
+            line.parse()
+                .map_err(|_| ParseError::new(input, line, "a number"))
+        })
+        .collect()
 }
 
 fn move_right(file: &mut Vec<impl Clone>, from: usize, amount: usize) {
@@ -125,15 +135,31 @@ fn sum_of_coordinates(input: &Vec<isize>) -> isize {
         + input[(zero_position + 3000usize).rem_euclid(input.len())]
 }
 
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input<'a> = Vec<isize>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_of_coordinates(&mix(input.clone()))
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_of_coordinates(&multimix(input.clone(), 10))
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(parse(&input));
     let start_time = Instant::now();
-    println!("solution 1: {}", sum_of_coordinates(&mix(parse(&input))));
-    println!(
-        "solution 2: {}",
-        sum_of_coordinates(&multimix(parse(&input), 10))
-    );
+    println!("solution 1: {}", sum_of_coordinates(&mix(input.clone())));
+    println!("solution 2: {}", sum_of_coordinates(&multimix(input, 10)));
     println!("time: {}", start_time.elapsed().as_micros());
 }
 
@@ -190,6 +216,7 @@ mod tests {
     #[test]
     fn test_move_once() {
         let mut input: Vec<State> = parse(include_str!("../../input/day20-test"))
+            .unwrap()
             .iter()
             .enumerate()
             .map(|(i, v)| State::new(*v, i))
@@ -225,21 +252,21 @@ mod tests {
 
     #[test]
     fn test_mix() {
-        let input = parse(include_str!("../../input/day20-test"));
+        let input = parse(include_str!("../../input/day20-test")).unwrap();
         let output = mix(input);
         assert_eq!(output, vec![1, 2, -3, 4, 0, 3, -2]);
     }
 
     #[test]
     fn test_coords() {
-        let input = parse(include_str!("../../input/day20-test"));
+        let input = parse(include_str!("../../input/day20-test")).unwrap();
         let output = mix(input);
         assert_eq!(sum_of_coordinates(&output), 3);
     }
 
     #[test]
     fn test_multimix() {
-        let input = parse(include_str!("../../input/day20-test"));
+        let input = parse(include_str!("../../input/day20-test")).unwrap();
         let output = multimix(input, 10);
         assert_eq!(sum_of_coordinates(&output), 1623178306);
     }
@@ -1,22 +1,36 @@
+use advent_of_code::{
+    arith::{self, ArithError, Arithmetic, BigInt, BigRational, Integer, One, Zero},
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{alpha1, multispace1},
     combinator::map,
     sequence::{self, delimited, terminated, tuple},
-    IResult,
 };
-use std::{collections::HashMap, time::Instant};
+use std::{
+    cmp,
+    collections::HashMap,
+    fmt::{self, Display, Formatter, Write},
+    time::Instant,
+};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
 struct Cli {
     #[structopt(parse(from_os_str))]
     path: std::path::PathBuf,
+    /// print the monkeys as a Graphviz digraph instead of solving
+    #[structopt(long)]
+    dot: bool,
+    /// evaluate part 1 with `checked` i64s that report overflow or `big` integers
+    #[structopt(long, default_value = "checked")]
+    arithmetic: Arithmetic,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Operation<'a> {
+pub enum Operation<'a> {
     Identity(i64),
     Add(&'a str, &'a str),
     Sub(&'a str, &'a str),
@@ -25,8 +39,8 @@ enum Operation<'a> {
     Eq(&'a str, &'a str),
 }
 
-impl Operation<'_> {
-    fn children(&self) -> Option<(&str, &str)> {
+impl<'a> Operation<'a> {
+    fn children(&self) -> Option<(&'a str, &'a str)> {
         match self {
             Operation::Add(a, b) => Some((a, b)),
             Operation::Sub(a, b) => Some((a, b)),
@@ -38,7 +52,20 @@ impl Operation<'_> {
     }
 }
 
-fn monkey<'a>(line: &'a str) -> IResult<&str, (&str, Operation<'_>)> {
+impl Display for Operation<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Operation::Identity(i) => write!(f, "{}", i),
+            Operation::Add(a, b) => write!(f, "{} + {}", a, b),
+            Operation::Sub(a, b) => write!(f, "{} - {}", a, b),
+            Operation::Mul(a, b) => write!(f, "{} * {}", a, b),
+            Operation::Div(a, b) => write!(f, "{} / {}", a, b),
+            Operation::Eq(a, b) => write!(f, "{} == {}", a, b),
+        }
+    }
+}
+
+fn monkey<'a>(line: &'a str) -> IResult<&'a str, (&'a str, Operation<'a>)> {
     sequence::pair(
         terminated(alpha1, tag(": ")),
         alt((
@@ -79,61 +106,52 @@ fn monkey<'a>(line: &'a str) -> IResult<&str, (&str, Operation<'_>)> {
     )(line)
 }
 
-fn parse(input: &str) -> HashMap<&str, Operation> {
-    input.lines().map(|x| monkey(x).unwrap().1).collect()
+fn parse(input: &str) -> Result<HashMap<&str, Operation<'_>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| from_nom(line, monkey(line)).map_err(|err| err.on_line(i + 1)))
+        .collect()
 }
 
-fn op(monkey_name: &str, context: &HashMap<&str, Operation>) -> i64 {
-    let operation = context.get(monkey_name).expect("monkey not found").clone();
-    let value = match operation {
-        Operation::Identity(i) => i,
-        Operation::Add(a, b) => op(a, context) + op(b, context),
-        Operation::Sub(a, b) => op(a, context) - op(b, context),
-        Operation::Mul(a, b) => op(a, context) * op(b, context),
-        Operation::Div(a, b) => op(a, context) / op(b, context),
+/// What a monkey with `operation` yells, given what each of its children yells.
+fn yell<'a, N: Integer + From<i64>>(
+    operation: &Operation<'a>,
+    mut child: impl FnMut(&'a str) -> Result<N, ArithError>,
+) -> Result<N, ArithError> {
+    let value = match *operation {
+        Operation::Identity(i) => N::from(i),
+        Operation::Add(a, b) => arith::add(&child(a)?, &child(b)?)?,
+        Operation::Sub(a, b) => arith::sub(&child(a)?, &child(b)?)?,
+        Operation::Mul(a, b) => arith::mul(&child(a)?, &child(b)?)?,
+        Operation::Div(a, b) => arith::div(&child(a)?, &child(b)?)?,
         Operation::Eq(a, b) => {
-            if op(a, context) == op(b, context) {
-                1
+            if child(a)? == child(b)? {
+                N::one()
             } else {
-                0
+                N::zero()
             }
         }
     };
-    value
+    Ok(value)
 }
 
-fn monkey_think(input: &str) -> i64 {
-    let monkeys = parse(input);
-    op("root", &monkeys)
+fn op<N: Integer + From<i64>>(
+    monkey_name: &str,
+    context: &HashMap<&str, Operation>,
+) -> Result<N, ArithError> {
+    let operation = context.get(monkey_name).expect("monkey not found");
+    yell(operation, |child| op(child, context))
 }
 
-fn simp_op<'a>(name: &'a str, monkeys: &mut HashMap<&'a str, Operation<'a>>) -> Option<i64> {
-    if name == "humn" {
-        return None;
+fn monkey_think(
+    monkeys: &HashMap<&str, Operation>,
+    arithmetic: Arithmetic,
+) -> Result<String, ArithError> {
+    match arithmetic {
+        Arithmetic::Checked => op::<i64>("root", monkeys).map(|value| value.to_string()),
+        Arithmetic::Big => op::<BigInt>("root", monkeys).map(|value| value.to_string()),
     }
-    let operation = monkeys.get(name).expect("monkey not found").clone();
-    let value = match operation {
-        Operation::Identity(i) => Some(i),
-        Operation::Add(a, b) => simp_op(a, monkeys)
-            .zip(simp_op(b, monkeys))
-            .map(|(a, b)| a + b),
-        Operation::Sub(a, b) => simp_op(a, monkeys)
-            .zip(simp_op(b, monkeys))
-            .map(|(a, b)| a - b),
-        Operation::Mul(a, b) => simp_op(a, monkeys)
-            .zip(simp_op(b, monkeys))
-            .map(|(a, b)| a * b),
-        Operation::Div(a, b) => simp_op(a, monkeys)
-            .zip(simp_op(b, monkeys))
-            .map(|(a, b)| a / b),
-        Operation::Eq(a, b) => {
-            (simp_op(a, monkeys).zip(simp_op(b, monkeys))).map(|(a, b)| if a == b { 1 } else { 0 })
-        }
-    };
-    if let Some(v) = value {
-        *monkeys.get_mut(name).unwrap() = Operation::Identity(v);
-    }
-    value
 }
 
 /// This was used to view the topology of the graph to implement the solver.
@@ -175,68 +193,358 @@ fn print_tree(name: &str, monkeys: &HashMap<&str, Operation>) {
     print!(")");
 }
 
-fn solve(input: &str) -> i64 {
-    let mut monkeys = parse(input);
+/// A polynomial in `humn`, lowest power first and without trailing zeros.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Polynomial(Vec<BigRational>);
 
-    // fix root operation
-    let root_monkey = monkeys.get("root").unwrap().clone();
-    let (rleft, rright) = root_monkey.children().unwrap();
-    monkeys.insert("root", Operation::Eq(rleft, rright));
+impl Polynomial {
+    fn constant(c: BigRational) -> Self {
+        Polynomial(vec![c]).trimmed()
+    }
+    fn humn() -> Self {
+        Polynomial(vec![BigRational::zero(), BigRational::one()])
+    }
+    fn trimmed(mut self) -> Self {
+        while self.0.last().is_some_and(Zero::is_zero) {
+            self.0.pop();
+        }
+        self
+    }
+    /// `None` for the zero polynomial.
+    fn degree(&self) -> Option<usize> {
+        self.0.len().checked_sub(1)
+    }
+    fn coefficient(&self, power: usize) -> BigRational {
+        self.0.get(power).cloned().unwrap_or_else(BigRational::zero)
+    }
+    fn add(&self, other: &Polynomial) -> Polynomial {
+        let len = cmp::max(self.0.len(), other.0.len());
+        Polynomial(
+            (0..len)
+                .map(|i| self.coefficient(i) + other.coefficient(i))
+                .collect(),
+        )
+        .trimmed()
+    }
+    fn sub(&self, other: &Polynomial) -> Polynomial {
+        let len = cmp::max(self.0.len(), other.0.len());
+        Polynomial(
+            (0..len)
+                .map(|i| self.coefficient(i) - other.coefficient(i))
+                .collect(),
+        )
+        .trimmed()
+    }
+    fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.0.is_empty() || other.0.is_empty() {
+            return Polynomial(Vec::new());
+        }
+        let mut product = vec![BigRational::zero(); self.0.len() + other.0.len() - 1];
+        for (i, a) in self.0.iter().enumerate() {
+            for (j, b) in other.0.iter().enumerate() {
+                product[i + j] += a * b;
+            }
+        }
+        Polynomial(product).trimmed()
+    }
+    /// Constants are divided like the monkeys do, rounding towards zero. With humn in the
+    /// dividend the division is exact, [`solve`] checks which roots the monkeys agree with.
+    fn div(&self, other: &Polynomial) -> Result<Polynomial, SolveError> {
+        match other.degree() {
+            None => Err(ArithError::DivisionByZero.into()),
+            Some(0) if self.degree() < Some(1) => Ok(Polynomial::constant(
+                (self.coefficient(0) / &other.0[0]).trunc(),
+            )),
+            Some(0) => Ok(Polynomial(self.0.iter().map(|c| c / &other.0[0]).collect())),
+            Some(_) => Err(SolveError::NotPolynomial),
+        }
+    }
+}
 
-    // simplify tree
-    simp_op("root", &mut monkeys);
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum SolveError {
+    /// `humn` ends up in a divisor
+    NotPolynomial,
+    Arith(ArithError),
+    /// only equations up to quadratics are solved
+    Degree(usize),
+}
 
-    inverse("root", 1, &monkeys)
+impl Display for SolveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::NotPolynomial => {
+                write!(f, "humn is divided by, the equation is not a polynomial")
+            }
+            SolveError::Arith(err) => write!(f, "{}", err),
+            SolveError::Degree(degree) => write!(f, "can't solve an equation of degree {}", degree),
+        }
+    }
 }
 
-fn inverse(name: &str, target: i64, monkeys: &HashMap<&str, Operation>) -> i64 {
-    let operation = monkeys.get(name).unwrap();
-    match operation {
-        Operation::Identity(_) => panic!("this function should not be called on identity"),
-        Operation::Add(a, b) => match (monkeys.get(a).unwrap(), monkeys.get(b).unwrap()) {
-            (Operation::Identity(_), Operation::Identity(i)) if a == &"humn" => target - i,
-            (Operation::Identity(i), Operation::Identity(_)) if b == &"humn" => target - i,
-            (Operation::Identity(i), _) => inverse(b, target - i, monkeys),
-            (_, Operation::Identity(i)) => inverse(a, target - i, monkeys),
-            _ => {
-                unreachable!("non-linear path detected")
+impl From<ArithError> for SolveError {
+    fn from(err: ArithError) -> Self {
+        SolveError::Arith(err)
+    }
+}
+
+/// The integers humn can yell for root's two sides to be equal, with every division humn's number
+/// goes through coming out exact. Where one rounds, neighbouring numbers can satisfy root too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Roots {
+    Unique(BigInt),
+    Several(Vec<BigInt>),
+    Unsolvable,
+    Any,
+}
+
+impl Display for Roots {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Roots::Unique(x) => write!(f, "{}", x),
+            Roots::Several(xs) => {
+                let xs: Vec<String> = xs.iter().map(|x| x.to_string()).collect();
+                write!(f, "{}", xs.join(", "))
+            }
+            Roots::Unsolvable => write!(f, "no solution"),
+            Roots::Any => write!(f, "any number"),
+        }
+    }
+}
+
+fn symbolic(name: &str, monkeys: &HashMap<&str, Operation>) -> Result<Polynomial, SolveError> {
+    if name == "humn" {
+        return Ok(Polynomial::humn());
+    }
+    let operation = monkeys.get(name).expect("monkey not found");
+    let polynomial = match *operation {
+        Operation::Identity(i) => Polynomial::constant(BigRational::from_integer(i.into())),
+        Operation::Add(a, b) => symbolic(a, monkeys)?.add(&symbolic(b, monkeys)?),
+        Operation::Sub(a, b) => symbolic(a, monkeys)?.sub(&symbolic(b, monkeys)?),
+        Operation::Mul(a, b) => symbolic(a, monkeys)?.mul(&symbolic(b, monkeys)?),
+        Operation::Div(a, b) => symbolic(a, monkeys)?.div(&symbolic(b, monkeys)?)?,
+        Operation::Eq(_, _) => return Err(SolveError::NotPolynomial),
+    };
+    Ok(polynomial)
+}
+
+/// The integer roots of `polynomial`, the monkeys only yell integers.
+fn roots(polynomial: &Polynomial) -> Result<Roots, SolveError> {
+    let integer = |r: BigRational| {
+        if r.is_integer() {
+            Some(r.to_integer())
+        } else {
+            None
+        }
+    };
+    let mut xs: Vec<BigInt> = match polynomial.degree() {
+        None => return Ok(Roots::Any),
+        Some(0) => Vec::new(),
+        Some(1) => integer(-polynomial.coefficient(0) / polynomial.coefficient(1))
+            .into_iter()
+            .collect(),
+        Some(2) => {
+            let (c, b, a) = (
+                polynomial.coefficient(0),
+                polynomial.coefficient(1),
+                polynomial.coefficient(2),
+            );
+            let discriminant = &b * &b - BigRational::from_integer(4.into()) * &a * &c;
+            if discriminant < BigRational::zero() {
+                return Ok(Roots::Unsolvable);
+            }
+            let (n, d) = (discriminant.numer().sqrt(), discriminant.denom().sqrt());
+            if &n * &n != *discriminant.numer() || &d * &d != *discriminant.denom() {
+                return Ok(Roots::Unsolvable);
+            }
+            let root = BigRational::new(n, d);
+            let twice = BigRational::from_integer(2.into()) * &a;
+            vec![(-&b - &root) / &twice, (-&b + &root) / &twice]
+                .into_iter()
+                .filter_map(integer)
+                .collect()
+        }
+        Some(degree) => return Err(SolveError::Degree(degree)),
+    };
+    Ok(Roots::new(xs))
+}
+
+impl Roots {
+    fn new(mut xs: Vec<BigInt>) -> Self {
+        xs.sort();
+        xs.dedup();
+        match xs.len() {
+            0 => Roots::Unsolvable,
+            1 => Roots::Unique(xs.pop().unwrap()),
+            _ => Roots::Several(xs),
+        }
+    }
+}
+
+fn solve(monkeys: &HashMap<&str, Operation>) -> Result<Roots, SolveError> {
+    // root checks its two sides for equality
+    let (rleft, rright) = monkeys["root"].children().unwrap();
+
+    // humn can be on both sides, so solve left - right = 0
+    let candidates = match roots(&symbolic(rleft, monkeys)?.sub(&symbolic(rright, monkeys)?))? {
+        Roots::Unique(x) => vec![x],
+        Roots::Several(xs) => xs,
+        roots => return Ok(roots),
+    };
+
+    // the polynomial divides exactly, so keep the roots that still work out when the monkeys
+    // yell them without any of their divisions rounding
+    let mut values = HashMap::new();
+    known("root", monkeys, &mut values)?;
+    let mut xs = Vec::new();
+    for x in candidates {
+        let mut exact = true;
+        let left = yell_humn(rleft, &x, monkeys, &values, &mut exact)?;
+        let right = yell_humn(rright, &x, monkeys, &values, &mut exact)?;
+        if exact && left == right {
+            xs.push(x);
+        }
+    }
+    Ok(Roots::new(xs))
+}
+
+/// What `name` yells when humn yells `humn`, clearing `exact` if a division on humn's path rounds.
+/// The monkeys in `values` don't depend on humn.
+fn yell_humn<'a>(
+    name: &'a str,
+    humn: &BigInt,
+    monkeys: &HashMap<&'a str, Operation<'a>>,
+    values: &HashMap<&'a str, BigInt>,
+    exact: &mut bool,
+) -> Result<BigInt, ArithError> {
+    if name == "humn" {
+        return Ok(humn.clone());
+    }
+    if let Some(value) = values.get(name) {
+        return Ok(value.clone());
+    }
+    let operation = &monkeys[name];
+    let mut children = Vec::new();
+    let value: BigInt = yell(operation, |child| {
+        let value = yell_humn(child, humn, monkeys, values, exact)?;
+        children.push(value.clone());
+        Ok(value)
+    })?;
+    if let (Operation::Div(_, _), [a, b]) = (operation, children.as_slice()) {
+        *exact &= &value * b == *a;
+    }
+    Ok(value)
+}
+
+/// Exactly evaluates every monkey that doesn't depend on humn into `values`, `None` for the
+/// ones that do.
+fn known<'a>(
+    name: &'a str,
+    monkeys: &HashMap<&'a str, Operation<'a>>,
+    values: &mut HashMap<&'a str, BigInt>,
+) -> Result<Option<BigInt>, ArithError> {
+    if name == "humn" {
+        return Ok(None);
+    }
+    let operation = &monkeys[name];
+    if let Some((a, b)) = operation.children() {
+        if let (None, _) | (_, None) = (known(a, monkeys, values)?, known(b, monkeys, values)?) {
+            return Ok(None);
+        }
+    }
+    let value: BigInt = yell(operation, |child| Ok(values[child].clone()))?;
+    values.insert(name, value.clone());
+    Ok(Some(value))
+}
+
+fn write_dot(
+    out: &mut String,
+    name: &str,
+    monkeys: &HashMap<&str, Operation>,
+    values: &HashMap<&str, BigInt>,
+) {
+    const RED: &str = "color=red, penwidth=2";
+    let on_path = |name: &str| !values.contains_key(name);
+    let operation = &monkeys[name];
+    let mut label = format!("{}\\n{}", name, operation);
+    if let (Some(_), Some(value)) = (operation.children(), values.get(name)) {
+        label.push_str(&format!("\\n= {}", value));
+    }
+    if on_path(name) {
+        writeln!(out, "    {} [label=\"{}\", {}];", name, label, RED).unwrap();
+    } else {
+        writeln!(out, "    {} [label=\"{}\"];", name, label).unwrap();
+    }
+    if let Some((a, b)) = operation.children() {
+        for child in [a, b] {
+            if on_path(child) {
+                writeln!(out, "    {} -> {} [{}];", name, child, RED).unwrap();
+            } else {
+                writeln!(out, "    {} -> {};", name, child).unwrap();
             }
-        },
-        Operation::Sub(a, b) => match (monkeys.get(a).unwrap(), monkeys.get(b).unwrap()) {
-            (Operation::Identity(_), Operation::Identity(i)) if a == &"humn" => target + i,
-            (Operation::Identity(i), Operation::Identity(_)) if b == &"humn" => i - target,
-            (Operation::Identity(i), _) => inverse(b, i - target, monkeys),
-            (_, Operation::Identity(i)) => inverse(a, target + i, monkeys),
-            _ => unreachable!("non-linear path detected"),
-        },
-        Operation::Mul(a, b) => match (monkeys.get(a).unwrap(), monkeys.get(b).unwrap()) {
-            (Operation::Identity(_), Operation::Identity(i)) if a == &"humn" => target / i,
-            (Operation::Identity(i), Operation::Identity(_)) if b == &"humn" => target / i,
-            (Operation::Identity(i), _) => inverse(b, target / i, monkeys),
-            (_, Operation::Identity(i)) => inverse(a, target / i, monkeys),
-            _ => unreachable!("non-linear path detected"),
-        },
-        Operation::Div(a, b) => match (monkeys.get(a).unwrap(), monkeys.get(b).unwrap()) {
-            (Operation::Identity(_), Operation::Identity(i)) if a == &"humn" => target * i,
-            (Operation::Identity(i), Operation::Identity(_)) if b == &"humn" => i / target,
-            (Operation::Identity(i), _) => inverse(b, i / target, monkeys),
-            (_, Operation::Identity(i)) => inverse(a, target * i, monkeys),
-            _ => unreachable!("non-linear path detected"),
-        },
-        Operation::Eq(a, b) => match (monkeys.get(a).unwrap(), monkeys.get(b).unwrap()) {
-            (Operation::Identity(i), _) => inverse(b, *i, monkeys),
-            (_, Operation::Identity(i)) => inverse(a, *i, monkeys),
-            _ => unreachable!("non-linear path detected"),
-        },
+            write_dot(out, child, monkeys, values);
+        }
+    }
+}
+
+/// Graphviz source for the tree under root, with the path to humn in red and
+/// the exact value of every subtree that doesn't depend on humn.
+fn dot(monkeys: &HashMap<&str, Operation>) -> Result<String, ArithError> {
+    let mut values = HashMap::new();
+    known("root", monkeys, &mut values)?;
+    let mut out = String::from("digraph monkeys {\n");
+    write_dot(&mut out, "root", monkeys, &values);
+    out.push_str("}\n");
+    Ok(out)
+}
+
+fn part1(input: &HashMap<&str, Operation>) -> String {
+    monkey_think(input, Arithmetic::Big).unwrap()
+}
+
+fn part2(input: &HashMap<&str, Operation>) -> Roots {
+    solve(input).unwrap_or_else(|err| panic!("{}", err))
+}
+
+pub struct Day21;
+
+// each part evaluates the monkeys differently
+impl Solution for Day21 {
+    type Input<'a> = HashMap<&'a str, Operation<'a>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
     }
 }
 
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(parse(&input));
+    if args.dot {
+        match dot(&input) {
+            Ok(graph) => print!("{}", graph),
+            Err(err) => eprintln!("error: {}", err),
+        }
+        return;
+    }
     let start_time = Instant::now();
-    println!("solution 1: {}", monkey_think(&input));
-    println!("solution 2: {:?}", solve(&input));
+    match monkey_think(&input, args.arithmetic) {
+        Ok(value) => println!("solution 1: {}", value),
+        Err(err) => eprintln!("error: {}", err),
+    }
+    match solve(&input) {
+        Ok(roots) => println!("solution 2: {}", roots),
+        Err(err) => eprintln!("error: {}", err),
+    }
     println!("time: {}", start_time.elapsed().as_micros());
 }
 
@@ -258,23 +566,122 @@ mod tests {
     }
 
     #[test]
-    fn test_monkey_think() {
-        let input = include_str!("../../input/day21-test");
-        assert_eq!(monkey_think(input), 152);
+    fn test_parse_error() {
+        let err = parse("root: pppw + sjmn\ndbpl: 5 apples\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input line 2, column 9: expected end of input, got apples"
+        );
     }
 
     #[test]
-    fn test_simplify() {
-        let mut monkeys = parse(include_str!("../../input/day21-test"));
-        let original = op("root", &monkeys);
-        simp_op("root", &mut monkeys);
-        let new = op("root", &monkeys);
-        assert_eq!(original, new);
+    fn test_monkey_think() {
+        let input = parse(include_str!("../../input/day21-test")).unwrap();
+        assert_eq!(
+            monkey_think(&input, Arithmetic::Checked),
+            Ok("152".to_string())
+        );
+        assert_eq!(monkey_think(&input, Arithmetic::Big), Ok("152".to_string()));
     }
 
     #[test]
     fn test_solver() {
-        let input = include_str!("../../input/day21-test");
-        assert_eq!(solve(input), 301);
+        let input = parse(include_str!("../../input/day21-test")).unwrap();
+        assert_eq!(solve(&input), Ok(Roots::Unique(301.into())));
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let input = parse("root: aaaa * bbbb\naaaa: 9223372036854775807\nbbbb: 2\n").unwrap();
+        assert_eq!(
+            monkey_think(&input, Arithmetic::Checked),
+            Err(ArithError::Overflow)
+        );
+        assert_eq!(
+            monkey_think(&input, Arithmetic::Big),
+            Ok("18446744073709551614".to_string())
+        );
+        let input = parse("root: aaaa / bbbb\naaaa: 5\nbbbb: cccc - cccc\ncccc: 3\n").unwrap();
+        assert_eq!(
+            monkey_think(&input, Arithmetic::Big),
+            Err(ArithError::DivisionByZero)
+        );
+    }
+
+    fn solve_root(root: &str, monkeys: &str) -> Result<Roots, SolveError> {
+        let input = format!("root: {}\nhumn: 5\n{}", root, monkeys);
+        solve(&parse(&input).unwrap())
+    }
+
+    #[test]
+    fn test_symbolic() {
+        // humn on both sides: 2x = x + 4
+        let twice = "aaaa: humn + humn\nbbbb: humn + four\nfour: 4\n";
+        assert_eq!(
+            solve_root("aaaa + bbbb", twice),
+            Ok(Roots::Unique(4.into()))
+        );
+        // x * x = 49
+        let square = "aaaa: humn * humn\nbbbb: 49\n";
+        assert_eq!(
+            solve_root("aaaa - bbbb", square),
+            Ok(Roots::Several(vec![(-7).into(), 7.into()]))
+        );
+        // (x + 2) / 2 = x / 2 + 1
+        let same = "aaaa: cccc / two\ncccc: humn + two\nbbbb: dddd + one\ndddd: humn / two\ntwo: 2\none: 1\n";
+        assert_eq!(solve_root("aaaa + bbbb", same), Ok(Roots::Any));
+        let never = "aaaa: humn + one\nbbbb: humn + two\none: 1\ntwo: 2\n";
+        assert_eq!(solve_root("aaaa * bbbb", never), Ok(Roots::Unsolvable));
+        let half = "aaaa: humn * two\nbbbb: 3\ntwo: 2\n";
+        assert_eq!(solve_root("aaaa + bbbb", half), Ok(Roots::Unsolvable));
+    }
+
+    #[test]
+    fn test_rounding() {
+        // the monkeys yell 7 / 2 as 3
+        let constant = "aaaa: humn + bbbb\nbbbb: seven / two\ncccc: 5\nseven: 7\ntwo: 2\n";
+        assert_eq!(
+            solve_root("aaaa + cccc", constant),
+            Ok(Roots::Unique(2.into()))
+        );
+        // x / 2 * 2 = 7 holds exactly for 7, but the monkeys round 7 / 2 down
+        let rounded = "aaaa: bbbb * two\nbbbb: humn / two\ncccc: 7\ntwo: 2\n";
+        assert_eq!(solve_root("aaaa + cccc", rounded), Ok(Roots::Unsolvable));
+        // 7 / 2 = 3 too, but only 6 divides exactly
+        let halved = "aaaa: humn / two\ncccc: 3\ntwo: 2\n";
+        assert_eq!(
+            solve_root("aaaa + cccc", halved),
+            Ok(Roots::Unique(6.into()))
+        );
+    }
+
+    #[test]
+    fn test_symbolic_errors() {
+        let divided = "aaaa: bbbb / humn\nbbbb: 3\n";
+        assert_eq!(
+            solve_root("aaaa + bbbb", divided),
+            Err(SolveError::NotPolynomial)
+        );
+        let cubed = "aaaa: humn * bbbb\nbbbb: humn * humn\n";
+        assert_eq!(solve_root("aaaa + bbbb", cubed), Err(SolveError::Degree(3)));
+    }
+
+    #[test]
+    fn test_dot() {
+        let graph = dot(&parse(include_str!("../../input/day21-test")).unwrap()).unwrap();
+        assert!(graph.starts_with(
+            "digraph monkeys {\n    root [label=\"root\\npppw + sjmn\", color=red, penwidth=2];\n"
+        ));
+        assert!(graph.contains("    sjmn [label=\"sjmn\\ndrzm * dbpl\\n= 150\"];\n"));
+        assert!(graph.contains("    ptdq -> humn [color=red, penwidth=2];\n"));
+        assert!(graph.contains("    ptdq -> dvpt;\n"));
+        assert!(graph.contains("    humn [label=\"humn\\n5\", color=red, penwidth=2];\n"));
+        assert!(graph.ends_with("}\n"));
+        assert_eq!(graph.matches(" -> ").count(), 14);
+        // the annotations are exact where i64s would overflow
+        let input = "root: aaaa + humn\naaaa: bbbb * bbbb\nbbbb: 9223372036854775807\nhumn: 5\n";
+        assert!(dot(&parse(input).unwrap())
+            .unwrap()
+            .contains("\\n= 85070591730234615847396907784232501249\"];\n"));
     }
 }
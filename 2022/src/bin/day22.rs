@@ -1,6 +1,10 @@
-use nom::{branch::alt, bytes::complete::tag, combinator::map, multi::many1, IResult};
-use regex::internal::Inst;
-use std::{cmp::min, time::Instant};
+use advent_of_code::{
+    grid::Grid,
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
+use nom::{branch::alt, combinator::map, multi::many1};
+use std::cmp::min;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -10,25 +14,20 @@ struct Cli {
 }
 
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Wall,
     Open,
     OffMap,
 }
 
-struct Grid {
-    field: Vec<Tile>,
-    width: i32,
-    height: i32,
-}
-
 #[derive(Clone, Debug, Copy, PartialEq, Eq)]
 struct Movement {
     orientation: (i32, i32),
     steps: i32,
 }
 
-enum Instruction {
+#[derive(Debug)]
+pub enum Instruction {
     Step(i32),
     Right,
     Left,
@@ -42,18 +41,17 @@ fn instructions(ins: &str) -> IResult<&str, Vec<Instruction>> {
     )))(ins)
 }
 
-fn parse(input: &str) -> (Grid, Vec<Instruction>) {
-    let mut parts = input.split("\n\n");
-    let ascii_grid = parts.next().unwrap();
-    let text_instructions = parts.next().unwrap();
-
-    (
-        Grid::parse(ascii_grid),
-        instructions(text_instructions).unwrap().1,
-    )
+fn parse(input: &str) -> Result<(Grid<Tile>, Vec<Instruction>), ParseError> {
+    let (ascii_grid, text_instructions) = input
+        .split_once("\n\n")
+        .ok_or_else(|| ParseError::new(input, &input[input.len()..], "a blank line"))?;
+    let grid = grid(ascii_grid).map_err(|err| ParseError::new(input, err, "' ', '.' or '#'"))?;
+    let instructions = from_nom(text_instructions, instructions(text_instructions))
+        .map_err(|err| err.on_line(ascii_grid.lines().count() + 2))?;
+    Ok((grid, instructions))
 }
 
-fn instructions_to_movements(instructions: Vec<Instruction>) -> Vec<Movement> {
+fn instructions_to_movements(instructions: &[Instruction]) -> Vec<Movement> {
     let mut orient_index = 0isize;
     let orientations = [(1, 0), (0, 1), (-1, 0), (0, -1)];
     let mut moves = vec![];
@@ -70,68 +68,57 @@ fn instructions_to_movements(instructions: Vec<Instruction>) -> Vec<Movement> {
     moves
 }
 
-impl Grid {
-    fn parse(input: &str) -> Self {
-        let height = input.lines().count() as i32;
-        let width = input.find('\n').unwrap() as i32;
-        let field = input
-            .replace("\n", "")
-            .as_str()
-            .chars()
-            .map(|c| match c {
-                ' ' => Tile::OffMap,
-                '.' => Tile::Open,
-                '#' => Tile::Wall,
-                _ => panic!("unexpected character"),
-            }) // handle start and end position
-            .collect();
-        Grid {
-            field,
-            width,
-            height,
-        }
+/// The board's rows can be ragged, so this checks the tiles itself and leaves
+/// the padding to [`Grid::parse`]. Gives the rest of the input from a bad tile.
+fn grid(input: &str) -> Result<Grid<Tile>, &str> {
+    if let Some(i) = input.find(|c| !" .#\n".contains(c)) {
+        return Err(&input[i..]);
     }
+    Ok(Grid::parse(input, |c| match c {
+        '.' => Tile::Open,
+        '#' => Tile::Wall,
+        _ => Tile::OffMap,
+    }))
+}
 
-    fn get(&self, pos: &(i32, i32)) -> Tile {
-        if pos.0 >= self.width || pos.0 < 0 || pos.1 >= self.height || pos.1 < 0 {
-            return Tile::OffMap;
-        }
-        self.field[((self.width * pos.1) + pos.0) as usize]
-    }
-    fn get_start_pos(&self) -> (i32, i32) {
-        let y = 0;
-        let x = (0..self.width).fold(i32::MAX, |a, i| match self.get(&(i, y)) {
-            Tile::Open => min(a, i),
-            _ => a,
-        });
-        (x, y)
-    }
-    fn step_position(&self, current: &(i32, i32), vector: &(i32, i32)) -> (i32, i32) {
-        assert!(vector.0.abs() <= 1);
-        assert!(vector.1.abs() <= 1);
-        let mut new_position = (
-            (current.0 + vector.0).rem_euclid(self.width),
-            (current.1 + vector.1).rem_euclid(self.height),
-        );
-        loop {
-            match self.get(&(new_position.0, new_position.1)) {
-                Tile::OffMap => {
-                    new_position = (
-                        (new_position.0 + vector.0).rem_euclid(self.width),
-                        (new_position.1 + vector.1).rem_euclid(self.height),
-                    );
-                }
-                _ => {
-                    return new_position;
-                }
+fn get(grid: &Grid<Tile>, pos: &(i32, i32)) -> Tile {
+    grid.get(pos.0, pos.1).unwrap_or(Tile::OffMap)
+}
+
+fn get_start_pos(grid: &Grid<Tile>) -> (i32, i32) {
+    let y = 0;
+    let x = (0..grid.width).fold(i32::MAX, |a, i| match get(grid, &(i, y)) {
+        Tile::Open => min(a, i),
+        _ => a,
+    });
+    (x, y)
+}
+
+fn step_position(grid: &Grid<Tile>, current: &(i32, i32), vector: &(i32, i32)) -> (i32, i32) {
+    assert!(vector.0.abs() <= 1);
+    assert!(vector.1.abs() <= 1);
+    let mut new_position = (
+        (current.0 + vector.0).rem_euclid(grid.width),
+        (current.1 + vector.1).rem_euclid(grid.height),
+    );
+    loop {
+        match get(grid, &(new_position.0, new_position.1)) {
+            Tile::OffMap => {
+                new_position = (
+                    (new_position.0 + vector.0).rem_euclid(grid.width),
+                    (new_position.1 + vector.1).rem_euclid(grid.height),
+                );
+            }
+            _ => {
+                return new_position;
             }
         }
     }
 }
 
-fn move_once(grid: &Grid, current: &(i32, i32), vector: &(i32, i32)) -> (i32, i32) {
-    let potential_pos = grid.step_position(&current, &vector);
-    let next_pos = match grid.get(&potential_pos) {
+fn move_once(grid: &Grid<Tile>, current: &(i32, i32), vector: &(i32, i32)) -> (i32, i32) {
+    let potential_pos = step_position(grid, current, vector);
+    let next_pos = match get(grid, &potential_pos) {
         Tile::Open => potential_pos,
         Tile::Wall => current.clone(),
         Tile::OffMap => panic!("should not be able to get here"),
@@ -139,7 +126,7 @@ fn move_once(grid: &Grid, current: &(i32, i32), vector: &(i32, i32)) -> (i32, i3
     next_pos
 }
 
-fn perform_movement(grid: &Grid, current: &(i32, i32), movement: &Movement) -> (i32, i32) {
+fn perform_movement(grid: &Grid<Tile>, current: &(i32, i32), movement: &Movement) -> (i32, i32) {
     let mut pos = current.clone();
     for _step in 0..movement.steps {
         pos = move_once(grid, &pos, &movement.orientation);
@@ -157,22 +144,34 @@ fn score_orientation(orientation: &(i32, i32)) -> i32 {
     }
 }
 
-fn calculate_password(input: &str) -> i32 {
-    let (grid, instructions) = parse(input);
+fn calculate_password(grid: &Grid<Tile>, instructions: &[Instruction]) -> i32 {
     let movements = instructions_to_movements(instructions);
-    let mut pos = grid.get_start_pos();
+    let mut pos = get_start_pos(grid);
     for mv in movements.iter() {
-        pos = perform_movement(&grid, &pos, &mv);
+        pos = perform_movement(grid, &pos, mv);
     }
     (pos.1 + 1) * 1000 + (pos.0 + 1) * 4 + score_orientation(&movements.last().unwrap().orientation)
 }
 
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input<'a> = (Grid<Tile>, Vec<Instruction>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1((grid, instructions): &Self::Input<'_>) -> impl std::fmt::Display {
+        calculate_password(grid, instructions)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    let start_time = Instant::now();
-    println!("solution 1: {}", calculate_password(&input));
-    println!("time: {}", start_time.elapsed().as_micros());
+    let (grid, instructions) = or_exit(parse(&input));
+    println!("solution 1: {}", calculate_password(&grid, &instructions));
 }
 
 #[cfg(test)]
@@ -181,9 +180,9 @@ mod tests {
 
     #[test]
     fn test_solution() {
-        let (grid, instructions) = parse(include_str!("../../input/day22-test"));
-        let movements = instructions_to_movements(instructions);
-        let mut pos = grid.get_start_pos();
+        let (grid, instructions) = parse(include_str!("../../input/day22-test")).unwrap();
+        let movements = instructions_to_movements(&instructions);
+        let mut pos = get_start_pos(&grid);
         for mv in movements.iter() {
             pos = perform_movement(&grid, &pos, &mv);
         }
@@ -192,7 +191,22 @@ mod tests {
 
     #[test]
     fn test_password_calculation() {
-        let password = calculate_password(include_str!("../../input/day22-test"));
-        assert_eq!(password, 6032);
+        let (grid, instructions) = parse(include_str!("../../input/day22-test")).unwrap();
+        assert_eq!(calculate_password(&grid, &instructions), 6032);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("  ..\n  .x\n\n10R5").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input line 2, column 4: expected ' ', '.' or '#', got x"
+        );
+        let err = parse("  ..\n  ..\n\n10R5U").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input line 4, column 5: expected end of input, got U"
+        );
+        assert!(parse("  ..\n  ..\n").is_err());
     }
 }
@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use std::{
     collections::{HashMap, HashSet},
@@ -70,22 +74,22 @@ enum ElfState {
     Noop,
 }
 
-fn parse(input: &str) -> HashMap<(i32, i32), Elf> {
-    let width = input.find('\n').unwrap();
-    input
-        .replace("\n", "")
-        .as_str()
-        .chars()
-        .enumerate()
-        .filter_map(|(i, c)| match c {
-            '.' => None,
-            '#' => Some((
-                (i.rem_euclid(width) as i32, i.div_euclid(width) as i32),
-                Elf::new(),
-            )),
-            _ => panic!("unexpected character"),
-        }) // handle start and end position
-        .collect()
+fn parse(input: &str) -> Result<Vec<(i32, i32)>, ParseError> {
+    let mut positions = Vec::new();
+    for (y, line) in input.lines().enumerate() {
+        for (x, c) in line.char_indices() {
+            match c {
+                '.' => {}
+                '#' => positions.push((x as i32, y as i32)),
+                _ => return Err(ParseError::new(input, &line[x..], "'.' or '#'")),
+            }
+        }
+    }
+    Ok(positions)
+}
+
+fn place_elves(positions: &[(i32, i32)]) -> HashMap<(i32, i32), Elf<'static>> {
+    positions.iter().map(|&pos| (pos, Elf::new())).collect()
 }
 
 fn adjacent(centre: &(i32, i32)) -> impl Iterator<Item = (i32, i32)> + '_ {
@@ -198,8 +202,8 @@ fn rule(
     }
 }
 
-fn simulation(input: &str, rounds: u32) -> i32 {
-    let mut elves = parse(input);
+fn simulation(positions: &[(i32, i32)], rounds: u32) -> i32 {
+    let mut elves = place_elves(positions);
     for _ in 1..=rounds {
         elves = first_half(elves);
         elves = second_half(elves);
@@ -207,8 +211,8 @@ fn simulation(input: &str, rounds: u32) -> i32 {
     get_empty_tiles(&elves)
 }
 
-fn simulate_until_stopped(input: &str) -> u32 {
-    let mut elves = parse(input);
+fn simulate_until_stopped(positions: &[(i32, i32)]) -> u32 {
+    let mut elves = place_elves(positions);
     let mut round = 1;
     loop {
         elves = first_half(elves);
@@ -249,9 +253,29 @@ fn elves_to_string<'a>(elves: &HashMap<(i32, i32), Elf>) -> String {
     s
 }
 
+pub struct Day23;
+
+// each part runs the elf simulation from scratch
+impl Solution for Day23 {
+    type Input<'a> = Vec<(i32, i32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        simulation(input, 10)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        simulate_until_stopped(input)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = or_exit(parse(&input));
     let start_time = Instant::now();
     println!("solution 1: {}", simulation(&input, 10));
     println!("solution 2: {}", simulate_until_stopped(&input));
@@ -264,14 +288,13 @@ mod tests {
 
     #[test]
     fn test_example() {
-        assert_eq!(simulation(include_str!("../../input/day23-test"), 10), 110);
+        let input = parse(include_str!("../../input/day23-test")).unwrap();
+        assert_eq!(simulation(&input, 10), 110);
     }
 
     #[test]
     fn test_example_until_stopped() {
-        assert_eq!(
-            simulate_until_stopped(include_str!("../../input/day23-test")),
-            20
-        );
+        let input = parse(include_str!("../../input/day23-test")).unwrap();
+        assert_eq!(simulate_until_stopped(&input), 20);
     }
 }
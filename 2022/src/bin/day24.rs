@@ -1,4 +1,5 @@
-use std::{cmp::min, collections::BinaryHeap, convert::TryInto, time::Instant};
+use advent_of_code::{parse::ParseError, search::bfs, solution::Solution};
+use std::{convert::TryInto, time::Instant};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -55,7 +56,7 @@ enum HistoryTile {
 }
 
 // A view of the valley at future history
-struct ValleyHistory {
+pub struct ValleyHistory {
     blizzard_map: Vec<HistoryTile>,
     width: i32,
     height: i32,
@@ -200,42 +201,12 @@ fn parse(input: &str) -> Valley {
     }
 }
 
-fn adjacent(centre: &(i32, i32)) -> impl Iterator<Item = (i32, i32)> + '_ {
+fn adjacent(centre: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
     [(0, 0), (1, 0), (0, 1), (-1, 0), (0, -1)]
         .iter()
         .map(move |x| (centre.0 + x.0, centre.1 + x.1))
 }
 
-#[derive(Debug, PartialEq, Eq, Ord)]
-struct Ranker {
-    position: (i32, i32),
-    target: (i32, i32),
-    time: i32,
-    distance_to_target: i32,
-}
-
-impl Ranker {
-    fn new(position: (i32, i32), target: (i32, i32), time: i32) -> Self {
-        let (x, y) = position;
-        let (end_x, end_y) = target;
-        Ranker {
-            distance_to_target: (end_x - x).abs() + (end_y - y).abs(),
-            position,
-            target,
-            time,
-        }
-    }
-}
-
-impl PartialOrd for Ranker {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        // other -> self. order matters because we want a min-heap
-        other
-            .distance_to_target
-            .partial_cmp(&self.distance_to_target)
-    }
-}
-
 fn get_start() -> (i32, i32) {
     (0, -1)
 }
@@ -249,62 +220,40 @@ fn search(
     segment: ((i32, i32), (i32, i32)),
 ) -> Option<i32> {
     let (start, target) = segment;
-    let mut queue = BinaryHeap::with_capacity(1_000_000);
-    let height = history.height;
-    let width = history.width;
-    let mut visited = vec![false; (width * height * history.full_cycle_length) as usize];
-
-    // use cyclic condition to avoid repeats
-    let to_index = |x: i32, y: i32, t: i32| -> usize {
-        ((t.rem_euclid(history.full_cycle_length) * height * width)
-            + (y.rem_euclid(height) * width)
-            + x.rem_euclid(width))
-        .try_into()
-        .unwrap()
-    };
-    let mut best = None;
-    let mut candidates = Vec::with_capacity(5);
-    queue.push(Ranker::new(start, target, start_time));
-    while let Some(
-        Ranker {
-            position,
-            target,
-            time,
-            distance_to_target,
+    // the blizzards repeat, so times are kept within one cycle to avoid revisiting states
+    let cycle = history.full_cycle_length;
+    bfs(
+        (start, start_time.rem_euclid(cycle)),
+        |&(position, time)| {
+            let time = (time + 1).rem_euclid(cycle);
+            adjacent(position)
+                .filter(move |adj| matches!(history.get(adj, time), HistoryTile::Ground))
+                .map(move |adj| (adj, time))
         },
-    ) = queue.pop()
-    {
-        if position == target {
-            best = best.or(Some(time)).map(|best_time| min(best_time, time));
-            continue;
-        }
+        |&(position, _)| position == target,
+    )
+    .map(|path| start_time + path.cost as i32)
+}
 
-        // bound all branches that are worst than the best found case.
-        if let Some(best_time) = best {
-            if best_time < time + distance_to_target {
-                continue;
-            }
-        }
+pub struct Day24;
 
-        candidates.extend(
-            adjacent(&position)
-                .filter(|adj| !visited[to_index(adj.0, adj.1, time + 1)])
-                .filter_map(|adj| match history.get(&adj, time + 1) {
-                    HistoryTile::Ground => Some(Ranker::new(adj, target, time + 1)),
-                    HistoryTile::Blizzard => None,
-                    HistoryTile::Wall => None,
-                }),
-        );
-        for rank @ Ranker {
-            position: (next_x, next_y),
-            ..
-        } in candidates.drain(..)
-        {
-            visited[to_index(next_x, next_y, time + 1)] = true;
-            queue.push(rank);
-        }
+impl Solution for Day24 {
+    type Input<'a> = ValleyHistory;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(ValleyHistory::new(parse(input)))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search(input, 0, (get_start(), get_end(input))).expect("no path found")
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        search(input, 0, (get_start(), get_end(input)))
+            .and_then(|time| search(input, time, (get_end(input), get_start())))
+            .and_then(|time| search(input, time, (get_start(), get_end(input))))
+            .expect("no path found")
     }
-    best
 }
 
 fn main() {
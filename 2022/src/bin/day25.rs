@@ -1,4 +1,9 @@
-use std::{collections::VecDeque, time::Instant};
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming, Unsolved},
+};
+use std::{collections::VecDeque, fmt::Display, time::Instant};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -7,9 +12,9 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-fn snafu_to_number(snafu: &str) -> i64 {
+fn snafu_to_number(snafu: &str) -> Result<i64, ParseError> {
     let mut accum = 0;
-    for (i, c) in snafu.chars().rev().enumerate() {
+    for (i, (j, c)) in snafu.char_indices().rev().enumerate() {
         accum += 5_i64.pow(i as u32)
             * match c {
                 '=' => -2,
@@ -17,10 +22,10 @@ fn snafu_to_number(snafu: &str) -> i64 {
                 '0' => 0,
                 '1' => 1,
                 '2' => 2,
-                _ => panic!("unexpected character"),
+                _ => return Err(ParseError::new(snafu, &snafu[j..], "a SNAFU digit")),
             }
     }
-    accum
+    Ok(accum)
 }
 
 fn get_snafu_digits(number: i64) -> u32 {
@@ -35,7 +40,6 @@ fn get_snafu_digits(number: i64) -> u32 {
 fn number_to_snafu(mut number: i64) -> String {
     let mut digit_string = VecDeque::new();
     loop {
-        println!("loop");
         let mut rem = number % 5;
         number = number / 5;
         // generalized from the balanced ternary number system
@@ -69,12 +73,43 @@ fn number_to_snafu(mut number: i64) -> String {
         .to_string()
 }
 
+fn part1(numbers: impl IntoIterator<Item = i64>) -> String {
+    number_to_snafu(numbers.into_iter().sum())
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input<'a> = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), snafu_to_number).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input.iter().copied())
+    }
+}
+
+impl Streaming for Day25 {
+    type Line = i64;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        snafu_to_number(line)
+    }
+
+    fn answers(numbers: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        (part1(numbers), Unsolved)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
-    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let input = input::open(args.path.as_path()).unwrap();
     let start_time = Instant::now();
-    let result = input.lines().fold(0, |a, line| a + snafu_to_number(line));
-    println!("solution 1: {}", number_to_snafu(result));
+    // the lines are streamed, stopping at the first bad one
+    let numbers = parse_lines(input, snafu_to_number).map(or_exit);
+    println!("solution 1: {}", part1(numbers));
 }
 #[cfg(test)]
 mod tests {
@@ -82,19 +117,20 @@ mod tests {
 
     #[test]
     fn test_snafu_to_number() {
-        assert_eq!(snafu_to_number("1=-0-2"), 1747);
-        assert_eq!(snafu_to_number("12111"), 906);
-        assert_eq!(snafu_to_number("2=0="), 198);
-        assert_eq!(snafu_to_number("21"), 11);
-        assert_eq!(snafu_to_number("2=01"), 201);
-        assert_eq!(snafu_to_number("111"), 31);
-        assert_eq!(snafu_to_number("20012"), 1257);
-        assert_eq!(snafu_to_number("112"), 32);
-        assert_eq!(snafu_to_number("1=-1="), 353);
-        assert_eq!(snafu_to_number("1-12"), 107);
-        assert_eq!(snafu_to_number("12"), 7);
-        assert_eq!(snafu_to_number("1="), 3);
-        assert_eq!(snafu_to_number("122"), 37);
+        assert_eq!(snafu_to_number("1=-0-2"), Ok(1747));
+        assert_eq!(snafu_to_number("12111"), Ok(906));
+        assert_eq!(snafu_to_number("2=0="), Ok(198));
+        assert_eq!(snafu_to_number("21"), Ok(11));
+        assert_eq!(snafu_to_number("2=01"), Ok(201));
+        assert_eq!(snafu_to_number("111"), Ok(31));
+        assert_eq!(snafu_to_number("20012"), Ok(1257));
+        assert_eq!(snafu_to_number("112"), Ok(32));
+        assert_eq!(snafu_to_number("1=-1="), Ok(353));
+        assert_eq!(snafu_to_number("1-12"), Ok(107));
+        assert_eq!(snafu_to_number("12"), Ok(7));
+        assert_eq!(snafu_to_number("1="), Ok(3));
+        assert_eq!(snafu_to_number("122"), Ok(37));
+        assert!(snafu_to_number("1=3").is_err());
     }
 
     #[test]
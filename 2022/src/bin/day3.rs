@@ -1,8 +1,11 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
 use itertools::Itertools;
 use std::collections::BTreeSet;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -27,6 +30,16 @@ fn priority(c: char) -> u32 {
     }
 }
 
+fn rucksack(line: &str) -> Result<&str, ParseError> {
+    if let Some(i) = line.find(|c: char| !c.is_ascii_alphabetic()) {
+        return Err(ParseError::new(line, &line[i..], "an item letter"));
+    }
+    if !line.len().is_multiple_of(2) {
+        return Err(ParseError::new(line, line, "two equal compartments"));
+    }
+    Ok(line)
+}
+
 fn parse_line(line: &str) -> Rucksack {
     let len = line.len();
     ((&line[..len / 2]), (&line[len / 2..]))
@@ -63,30 +76,72 @@ fn item_set(r: Rucksack) -> BTreeSet<char> {
     set
 }
 
-fn main() {
-    let args = Cli::from_args();
-    let input = File::open(args.path.as_path()).unwrap();
-    let lines = BufReader::new(input).lines();
-    let sum = lines
-        .map(|line| intersect(parse_line(&line.unwrap())))
-        .fold(0, |a, x| a + priority(x));
-    println!("{:?}", sum);
-
-    let input = File::open(args.path.as_path()).unwrap();
-    let lines = BufReader::new(input).lines();
-    let sum = lines
-        .map(|line| item_set(parse_line(&line.unwrap())))
+// Both parts in one pass, a group of three rucksacks at a time
+fn priorities(lines: impl IntoIterator<Item = impl AsRef<str>>) -> (u32, u32) {
+    lines
+        .into_iter()
         .chunks(3)
         .into_iter()
-        .map(|it| {
-            it.reduce(intersect_foldable)
+        .map(|group| {
+            let group = group.collect::<Vec<_>>();
+            let misplaced = group
+                .iter()
+                .map(|line| priority(intersect(parse_line(line.as_ref()))))
+                .sum::<u32>();
+            let badge = group
+                .iter()
+                .map(|line| item_set(parse_line(line.as_ref())))
+                .reduce(intersect_foldable)
                 .unwrap()
                 .iter()
                 .map(|x| priority(*x))
-                .sum::<u32>()
+                .sum::<u32>();
+            (misplaced, badge)
         })
-        .sum::<u32>();
-    println!("{}", sum);
+        .fold((0, 0), |(a, b), (x, y)| (a + x, b + y))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Vec<&'a str>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| rucksack(line).map_err(|err| err.on_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        priorities(input).0
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        priorities(input).1
+    }
+}
+
+impl Streaming for Day3 {
+    type Line = String;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        rucksack(line).map(str::to_string)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        priorities(lines)
+    }
+}
+
+fn main() {
+    let args = Cli::from_args();
+    let input = input::open(args.path.as_path()).unwrap();
+    let lines = parse_lines(input, Day3::line).map(or_exit);
+    let (misplaced, badges) = priorities(lines);
+    println!("{:?}", misplaced);
+    println!("{}", badges);
 }
 
 #[cfg(test)]
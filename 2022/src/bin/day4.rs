@@ -1,7 +1,12 @@
+use advent_of_code::{
+    input,
+    interval::IntervalSet,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
+use lazy_static::lazy_static;
 use regex::Regex;
-use std::fs::File;
-use std::io::prelude::*;
-use std::io::BufReader;
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -10,68 +15,85 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-fn parse_line() -> impl Fn(&str) -> ((u32, u32), (u32, u32)) {
-    let re = Regex::new(r"^(\d+)-(\d+),(\d+)-(\d+)$").unwrap();
-    move |line| {
-        let caps = re.captures(line).unwrap();
+type ElvePair = ((u32, u32), (u32, u32));
+
+lazy_static! {
+    static ref RE: Regex = Regex::new(r"^(\d+)-(\d+),(\d+)-(\d+)$").unwrap();
+}
+
+fn parse_line(line: &str) -> Result<ElvePair, ParseError> {
+    let caps = RE
+        .captures(line)
+        .ok_or_else(|| ParseError::new(line, line, "two section ranges"))?;
+    let section = |i| {
+        let text = caps.get(i).unwrap().as_str();
+        text.parse::<u32>()
+            .map_err(|_| ParseError::new(line, text, "a section number"))
+    };
+    Ok(((section(1)?, section(2)?), (section(3)?, section(4)?)))
+}
+
+fn sections(elve_pair: ElvePair) -> (IntervalSet<u32>, IntervalSet<u32>) {
+    let ((a0, a1), (b0, b1)) = elve_pair;
+    (IntervalSet::from(a0..=a1), IntervalSet::from(b0..=b1))
+}
+
+fn overlap_fully(elve_pair: ElvePair) -> bool {
+    let (a, b) = sections(elve_pair);
+    a.is_subset(&b) || b.is_subset(&a)
+}
+
+fn overlap_at_all(elve_pair: ElvePair) -> bool {
+    let (a, b) = sections(elve_pair);
+    !a.intersection(&b).is_empty()
+}
+
+// Counts both kinds of overlap in one pass
+fn overlaps(elve_pairs: impl IntoIterator<Item = ElvePair>) -> (usize, usize) {
+    elve_pairs.into_iter().fold((0, 0), |(fully, at_all), x| {
         (
-            (
-                caps.get(1).unwrap().as_str().parse::<u32>().unwrap(),
-                caps.get(2).unwrap().as_str().parse::<u32>().unwrap(),
-            ),
-            (
-                caps.get(3).unwrap().as_str().parse::<u32>().unwrap(),
-                caps.get(4).unwrap().as_str().parse::<u32>().unwrap(),
-            ),
+            fully + usize::from(overlap_fully(x)),
+            at_all + usize::from(overlap_at_all(x)),
         )
-    }
+    })
 }
 
-fn overlap_fully(elve_pair: ((u32, u32), (u32, u32))) -> bool {
-    let start = if elve_pair.0 .0 < elve_pair.1 .0 {
-        elve_pair.0
-    } else {
-        elve_pair.1
-    };
-    let end = if elve_pair.0 .0 < elve_pair.1 .0 {
-        elve_pair.1
-    } else {
-        elve_pair.0
-    };
-    end.1 <= start.1 || end.1 == start.1 || end.0 == start.0
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = Vec<ElvePair>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), parse_line).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        overlaps(input.iter().copied()).0
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        overlaps(input.iter().copied()).1
+    }
 }
 
-fn overlap_at_all(elve_pair: ((u32, u32), (u32, u32))) -> bool {
-    let start = if elve_pair.0 .0 < elve_pair.1 .0 {
-        elve_pair.0
-    } else {
-        elve_pair.1
-    };
-    let end = if elve_pair.0 .0 < elve_pair.1 .0 {
-        elve_pair.1
-    } else {
-        elve_pair.0
-    };
-    start.0 <= end.0 && start.1 >= end.0
+impl Streaming for Day4 {
+    type Line = ElvePair;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        parse_line(line)
+    }
+
+    fn answers(elve_pairs: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        overlaps(elve_pairs)
+    }
 }
 
 fn main() {
     let args = Cli::from_args();
-    let input = File::open(args.path.as_path()).unwrap();
-    let lines = BufReader::new(input).lines();
-    let parser = parse_line();
-    let overlap_count = lines
-        .map(|line| parser(&line.unwrap()))
-        .filter(|x| overlap_fully(*x))
-        .count();
+    let input = input::open(args.path.as_path()).unwrap();
+    let (overlap_count, overlap_at_all_count) =
+        overlaps(parse_lines(input, parse_line).map(or_exit));
     println!("{}", overlap_count);
-
-    let input = File::open(args.path.as_path()).unwrap();
-    let lines = BufReader::new(input).lines();
-    let overlap_at_all_count = lines
-        .map(|line| parser(&line.unwrap()))
-        .filter(|x| overlap_at_all(*x))
-        .count();
     println!("{}", overlap_at_all_count);
 }
 
@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use regex::Regex;
 use structopt::StructOpt;
 
@@ -29,23 +33,35 @@ fn parse_stack(pic: &str) -> Vec<Vec<char>> {
     stacks
 }
 
-fn instruction_parser() -> impl Fn(&str) -> (usize, usize, usize) {
-    let re = Regex::new(r"move (\d+) from (\d+) to (\d+)").unwrap();
+type Instruction = (usize, usize, usize);
+
+fn instruction_parser<'a>(
+    input: &'a str,
+    stacks: usize,
+) -> impl Fn(&'a str) -> Result<Instruction, ParseError> {
+    let re = Regex::new(r"^move (\d+) from (\d+) to (\d+)$").unwrap();
     move |instruction| {
-        let cap = re.captures(instruction).unwrap();
-        (
-            cap.get(1).unwrap().as_str().parse::<usize>().unwrap(),
-            cap.get(2).unwrap().as_str().parse::<usize>().unwrap() - 1, // handle start-from-zero
-            cap.get(3).unwrap().as_str().parse::<usize>().unwrap() - 1, // handle start-from-zero
-        )
+        let cap = re
+            .captures(instruction)
+            .ok_or_else(|| ParseError::new(input, instruction, "move N from A to B"))?;
+        let number = |i| {
+            let text = cap.get(i).unwrap().as_str();
+            match text.parse::<usize>() {
+                Ok(n) if i == 1 || (1..=stacks).contains(&n) => Ok(n),
+                _ => Err(ParseError::new(
+                    input,
+                    text,
+                    format!("a stack from 1 to {}", stacks),
+                )),
+            }
+        };
+        // handle start-from-zero
+        Ok((number(1)?, number(2)? - 1, number(3)? - 1))
     }
 }
 
-#[allow(dead_code)]
-fn perform_instructions<'a>(mut stacks: Vec<Vec<char>>, ins: &str) -> Vec<Vec<char>> {
-    let parse = instruction_parser();
-    for line in ins.lines() {
-        let (mv, from, to) = parse(line);
+fn perform_instructions(mut stacks: Vec<Vec<char>>, ins: &[Instruction]) -> Vec<Vec<char>> {
+    for &(mv, from, to) in ins {
         for _ in 0..mv {
             let tmp = stacks.get_mut(from).unwrap().pop().unwrap();
             stacks.get_mut(to).unwrap().push(tmp);
@@ -54,11 +70,9 @@ fn perform_instructions<'a>(mut stacks: Vec<Vec<char>>, ins: &str) -> Vec<Vec<ch
     stacks
 }
 
-fn stack_mover<'a>(mut stacks: Vec<Vec<char>>, ins: &str) -> Vec<Vec<char>> {
+fn stack_mover(mut stacks: Vec<Vec<char>>, ins: &[Instruction]) -> Vec<Vec<char>> {
     let mut mover = vec![];
-    let parse = instruction_parser();
-    for line in ins.lines() {
-        let (mv, from, to) = parse(line);
+    for &(mv, from, to) in ins {
         for _ in 0..mv {
             let tmp = stacks.get_mut(from).unwrap().pop().unwrap();
             mover.push(tmp);
@@ -80,15 +94,48 @@ fn spell(stacks: Vec<Vec<char>>) -> String {
     result
 }
 
+fn parse(input: &str) -> Result<(Vec<Vec<char>>, Vec<Instruction>), ParseError> {
+    let (stacks_input, instruction_input) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(
+            input,
+            &input[input.len()..],
+            "a blank line after the stacks",
+        )
+    })?;
+    let stacks = parse_stack(stacks_input);
+    let instructions = instruction_input
+        .lines()
+        .map(instruction_parser(input, stacks.len()))
+        .collect::<Result<_, _>>()?;
+    Ok((stacks, instructions))
+}
+
+pub struct Day5;
+
+// each part replays the crane instructions against a fresh stack
+impl Solution for Day5 {
+    type Input<'a> = (Vec<Vec<char>>, Vec<Instruction>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1((stacks, instructions): &Self::Input<'_>) -> impl std::fmt::Display {
+        spell(perform_instructions(stacks.clone(), instructions))
+    }
+
+    fn part2((stacks, instructions): &Self::Input<'_>) -> impl std::fmt::Display {
+        spell(stack_mover(stacks.clone(), instructions))
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    let mut dev = input.split("\n\n");
-    let stacks_input = dev.next().unwrap();
-    let instruction_input = dev.next().unwrap();
+    let (stacks, instructions) = or_exit(parse(&input));
 
-    println!("{:?}", parse_stack(stacks_input));
-    let end = stack_mover(parse_stack(stacks_input), instruction_input);
+    println!("{:?}", stacks);
+    let end = stack_mover(stacks, &instructions);
     println!("{:?}", end);
     let s = spell(end);
     println!("{:?}", s);
@@ -96,7 +143,14 @@ fn main() {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
 
     #[test]
-    fn test_parser() {}
+    fn test_parser() {
+        let input = "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 2 from 1 to 3\n";
+        assert_eq!(
+            parse(input).unwrap_err().to_string(),
+            "input line 6, column 18: expected a stack from 1 to 2, got 3"
+        );
+    }
 }
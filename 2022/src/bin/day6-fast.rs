@@ -1,4 +1,8 @@
 /// Experimental test to make it go faster using a bit mask
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -27,13 +31,48 @@ fn get_start(buffer: &str, distinct: usize) -> Option<usize> {
         .map(|x| x + distinct)
 }
 
+fn signal(input: &str) -> Result<&str, ParseError> {
+    let signal = input.trim_end();
+    match signal.find(|c: char| !c.is_ascii_lowercase()) {
+        Some(i) => Err(ParseError::new(input, &signal[i..], "a lowercase letter")),
+        None => Ok(signal),
+    }
+}
+
+fn part1(input: &str) -> usize {
+    get_start(input, 4).unwrap()
+}
+
+fn part2(input: &str) -> usize {
+    get_start(input, 14).unwrap()
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = &'a str;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        signal(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    let start_of_packet = get_start(&input, 4).unwrap();
+    let input = or_exit(signal(&input));
+    let start_of_packet = get_start(input, 4).unwrap();
     println!("Start of packet: {}", start_of_packet);
 
-    let start_of_message = get_start(&input, 14).unwrap();
+    let start_of_message = get_start(input, 14).unwrap();
     println!("Start of message: {}", start_of_message);
 }
 
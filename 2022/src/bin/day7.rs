@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use itertools::Itertools;
 use std::collections::BTreeMap;
 use structopt::StructOpt;
@@ -9,7 +13,7 @@ struct Cli {
 }
 
 #[derive(Debug, Clone)]
-enum Dentry {
+pub enum Dentry {
     File(usize),
     Directory,
 }
@@ -31,10 +35,11 @@ fn perform<'a>(
     tree: &mut BTreeMap<String, Dentry>,
     stack: &mut Vec<&'a str>,
     remaining: &'a str,
-) -> Option<&'a str> {
+) -> Result<Option<&'a str>, ParseError> {
+    let text = remaining;
     let pos = match remaining.find("\n") {
         Some(t) => t,
-        None => return None,
+        None => return Ok(None),
     };
     let (line, remaining) = remaining.split_at(pos);
     let (_, remaining) = remaining.split_at(1); // ignore leading newline
@@ -54,14 +59,14 @@ fn perform<'a>(
             tree.insert(filepath(stack, name), Dentry::Directory);
         }
         [size, name] => {
-            tree.insert(
-                filepath(stack, name),
-                Dentry::File(size.parse::<usize>().unwrap()),
-            );
+            let size = size
+                .parse::<usize>()
+                .map_err(|_| ParseError::new(text, size, "a file size"))?;
+            tree.insert(filepath(stack, name), Dentry::File(size));
         }
-        _ => panic!("unexpected"),
+        _ => return Err(ParseError::new(text, line, "a command or entry")),
     }
-    return Some(remaining);
+    Ok(Some(remaining))
 }
 
 fn find_dir_size(tree: &BTreeMap<String, Dentry>, dir: &str) -> usize {
@@ -79,44 +84,63 @@ fn find_dir_size(tree: &BTreeMap<String, Dentry>, dir: &str) -> usize {
     })
 }
 
-fn main() {
-    let args = Cli::from_args();
-    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+fn build_tree(input: &str) -> Result<BTreeMap<String, Dentry>, ParseError> {
     let mut tree = BTreeMap::new();
     let mut stack = Vec::new();
-    let mut remain = input.as_str();
-    loop {
-        remain = match perform(&mut tree, &mut stack, remain) {
+    let mut remain = input;
+    for line in 1.. {
+        remain = match perform(&mut tree, &mut stack, remain).map_err(|err| err.on_line(line))? {
             Some(t) => t,
             None => break,
         };
     }
-    let sum_dir = tree
-        .iter()
-        .filter_map(|(k, v)| match v {
-            Dentry::Directory => Some(find_dir_size(&tree, k)),
-            Dentry::File(_) => None,
-        })
-        .filter(|v| v <= &100000)
-        .sum::<usize>();
-
-    println!("sum: {:?}", sum_dir);
-
-    let used = find_dir_size(&tree, "");
-    let max = 70_000_000;
-    let free = max - used;
-    let required_to_free = 30_000_000 - free;
-    let best_pick = tree
-        .iter()
-        .filter_map(|(k, v)| match v {
-            Dentry::Directory => Some(find_dir_size(&tree, k)),
-            Dentry::File(_) => None,
-        })
-        .filter(|v| v >= &required_to_free)
-        .sorted()
-        .next()
-        .unwrap();
-    println!("best pick: {}", best_pick);
+    Ok(tree)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = BTreeMap<String, Dentry>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        build_tree(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        input
+            .iter()
+            .filter_map(|(k, v)| match v {
+                Dentry::Directory => Some(find_dir_size(input, k)),
+                Dentry::File(_) => None,
+            })
+            .filter(|v| v <= &100000)
+            .sum::<usize>()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let used = find_dir_size(input, "");
+        let max = 70_000_000;
+        let free = max - used;
+        let required_to_free = 30_000_000 - free;
+        input
+            .iter()
+            .filter_map(|(k, v)| match v {
+                Dentry::Directory => Some(find_dir_size(input, k)),
+                Dentry::File(_) => None,
+            })
+            .filter(|v| v >= &required_to_free)
+            .sorted()
+            .next()
+            .unwrap()
+    }
+}
+
+fn main() {
+    let args = Cli::from_args();
+    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
+    let tree = or_exit(Day7::parse(&input));
+    println!("sum: {}", Day7::part1(&tree));
+    println!("best pick: {}", Day7::part2(&tree));
 }
 
 #[cfg(test)]
@@ -137,6 +161,7 @@ mod tests {
                  8504156 c.dat\n\
                  dir d\n",
         )
+        .unwrap()
         .unwrap();
         assert_eq!(
             result,
@@ -148,7 +173,7 @@ mod tests {
         );
         assert_eq!(stack.len(), 0);
 
-        let _result = perform(&mut tree, &mut stack, result).unwrap();
+        let _result = perform(&mut tree, &mut stack, result).unwrap().unwrap();
         assert_eq!(stack.len(), 0);
     }
 
@@ -181,7 +206,7 @@ mod tests {
                        7214296 k\n";
         let mut remain = example;
         loop {
-            remain = match perform(&mut tree, &mut stack, remain) {
+            remain = match perform(&mut tree, &mut stack, remain).unwrap() {
                 Some(t) => t,
                 None => break,
             };
@@ -1,3 +1,8 @@
+use advent_of_code::{
+    grid::Grid,
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use itertools::iproduct;
 use std::cmp::max;
 use std::iter::zip;
@@ -9,94 +14,82 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-type Grid = (Vec<u8>, (usize, usize));
-
-fn parse(input: &str) -> (Vec<u8>, (usize, usize)) {
-    let result = input
-        .replace("\n", "")
-        .chars()
-        .map(|c| c.to_digit(10).unwrap())
-        .map(|u| u as u8)
-        .collect();
-    let height = input.lines().count();
-    let width = input.find("\n").unwrap();
-    (result, (width, height))
+fn parse(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::try_parse(input, |c| c.to_digit(10).map(|d| d as u8), "a tree height")
 }
 
-fn get(grid: &Grid, x: usize, y: usize) -> u8 {
-    grid.0[grid.1 .0 * y + x]
-}
-fn set(grid: &mut Grid, x: usize, y: usize, v: u8) {
-    grid.0[grid.1 .0 * y + x] = v
+fn get(grid: &Grid<u8>, x: i32, y: i32) -> u8 {
+    grid.get(x, y).unwrap()
 }
 
-fn visible(grid: &Grid) -> Grid {
+fn visible(grid: &Grid<u8>) -> Grid<u8> {
     let mut result = [
-        (vec![0; grid.1 .0 * grid.1 .1], grid.1),
-        (vec![0; grid.1 .0 * grid.1 .1], grid.1),
-        (vec![0; grid.1 .0 * grid.1 .1], grid.1),
-        (vec![0; grid.1 .0 * grid.1 .1], grid.1),
-        (vec![0; grid.1 .0 * grid.1 .1], grid.1),
+        Grid::filled(grid.width, grid.height, 0),
+        Grid::filled(grid.width, grid.height, 0),
+        Grid::filled(grid.width, grid.height, 0),
+        Grid::filled(grid.width, grid.height, 0),
+        Grid::filled(grid.width, grid.height, 0),
     ];
-    for y in 0..(grid.1 .1) {
+    for y in 0..grid.height {
         let mut tallest_tree = 0;
-        for x in 0..(grid.1 .0) {
+        for x in 0..grid.width {
             let v = get(grid, x, y);
             let visible = tallest_tree < v;
             tallest_tree = max(tallest_tree, v);
-            set(&mut result[0], x, y, visible as u8);
+            result[0].set(x, y, visible as u8);
         }
     }
-    for y in 0..(grid.1 .1) {
+    for y in 0..grid.height {
         let mut tallest_tree = 0;
-        for x in (0..(grid.1 .0)).rev() {
+        for x in (0..grid.width).rev() {
             let v = get(grid, x, y);
             let visible = tallest_tree < v;
             tallest_tree = max(tallest_tree, v);
-            set(&mut result[1], x, y, visible as u8);
+            result[1].set(x, y, visible as u8);
         }
     }
-    for x in 0..(grid.1 .1) {
+    for x in 0..grid.height {
         let mut tallest_tree = 0;
-        for y in 0..(grid.1 .0) {
+        for y in 0..grid.width {
             let v = get(grid, x, y);
             let visible = tallest_tree < v;
             tallest_tree = max(tallest_tree, v);
-            set(&mut result[2], x, y, visible as u8);
+            result[2].set(x, y, visible as u8);
         }
     }
-    for x in 0..(grid.1 .1) {
+    for x in 0..grid.height {
         let mut tallest_tree = 0;
-        for y in (0..(grid.1 .0)).rev() {
+        for y in (0..grid.width).rev() {
             let v = get(grid, x, y);
             let visible = tallest_tree < v;
             tallest_tree = max(tallest_tree, v);
-            set(&mut result[3], x, y, visible as u8);
+            result[3].set(x, y, visible as u8);
         }
     }
-    for i in 0..(grid.1 .0) {
-        set(&mut result[4], i, 0, 1);
-        set(&mut result[4], i, (grid.1 .1) - 1, 1);
+    for i in 0..grid.width {
+        result[4].set(i, 0, 1);
+        result[4].set(i, grid.height - 1, 1);
     }
-    for i in 0..(grid.1 .1) {
-        set(&mut result[4], 0, i, 1);
-        set(&mut result[4], (grid.1 .1) - 1, i, 1);
+    for i in 0..grid.height {
+        result[4].set(0, i, 1);
+        result[4].set(grid.height - 1, i, 1);
     }
     let phase = zip(
-        zip(result[0].0.iter(), result[1].0.iter()),
-        zip(result[2].0.iter(), result[3].0.iter()),
+        zip(result[0].cells().iter(), result[1].cells().iter()),
+        zip(result[2].cells().iter(), result[3].cells().iter()),
     )
     .map(|((a, b), (c, d))| (a != &0 || b != &0 || c != &0 || d != &0) as u8);
-    let out = zip(phase, result[4].0.iter())
-        .map(|(a, b)| (a != 0 || b != &0) as u8)
-        .collect();
-    (out, grid.1)
+    let mut out = Grid::filled(grid.width, grid.height, 0);
+    for (tile, (a, b)) in zip(out.cells_mut(), zip(phase, result[4].cells().iter())) {
+        *tile = (a != 0 || b != &0) as u8;
+    }
+    out
 }
 
-fn scenic_score(grid: &Grid, x: usize, y: usize) -> usize {
+fn scenic_score(grid: &Grid<u8>, x: i32, y: i32) -> usize {
     let my_height = get(grid, x, y);
     // go right
-    let right = (x..grid.1 .0)
+    let right = (x..grid.width)
         .skip(1)
         .fold((false, 0), |a, i| match (a.0, get(grid, i, y)) {
             (true, _) => a,
@@ -115,7 +108,7 @@ fn scenic_score(grid: &Grid, x: usize, y: usize) -> usize {
         })
         .1;
     // go down
-    let down = (y..grid.1 .1)
+    let down = (y..grid.height)
         .skip(1)
         .fold((false, 0), |a, i| match (a.0, get(grid, x, i)) {
             (true, _) => a,
@@ -136,16 +129,38 @@ fn scenic_score(grid: &Grid, x: usize, y: usize) -> usize {
     up * down * left * right
 }
 
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input<'a> = Grid<u8>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let visibility = visible(input);
+        visibility.cells().iter().filter(|x| **x == 1).count()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        iproduct!((0..input.width), (0..input.height))
+            .map(|(x, y)| scenic_score(input, x, y))
+            .max()
+            .unwrap()
+    }
+}
+
 fn main() {
     let args = Cli::from_args();
     let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    let grid = parse(&input);
+    let grid = or_exit(parse(&input));
     let visibility = visible(&grid);
     println!(
         "visible trees: {}",
-        visibility.0.iter().filter(|x| **x == 1).count()
+        visibility.cells().iter().filter(|x| **x == 1).count()
     );
-    let mut coords = iproduct!((0..grid.1 .0), (0..grid.1 .1)).collect::<Vec<(usize, usize)>>();
+    let mut coords = iproduct!((0..grid.width), (0..grid.height)).collect::<Vec<(i32, i32)>>();
     coords.sort_by_cached_key(|(j, i)| scenic_score(&grid, *j, *i));
     let (x, y) = coords.iter().last().unwrap();
     println!("x: {}, y: {}, score: {}", x, y, scenic_score(&grid, *x, *y));
@@ -158,22 +173,22 @@ mod tests {
     #[test]
     fn test_get_start() {
         let test = include_str!("../../input/day8-test");
-        let result = parse(test);
-        assert_eq!(result.1 .0, 5);
-        assert_eq!(result.1 .1, 5);
+        let result = parse(test).unwrap();
+        assert_eq!(result.width, 5);
+        assert_eq!(result.height, 5);
     }
 
     #[test]
     fn test_example() {
         let test = include_str!("../../input/day8-test");
-        let result = visible(&parse(test));
-        assert_eq!(result.0.iter().filter(|x| **x == 1).count(), 21);
+        let result = visible(&parse(test).unwrap());
+        assert_eq!(result.cells().iter().filter(|x| **x == 1).count(), 21);
     }
 
     #[test]
     fn test_scenic() {
         let test = include_str!("../../input/day8-test");
-        assert_eq!(scenic_score(&parse(test), 2, 1), 4);
-        assert_eq!(scenic_score(&parse(test), 2, 3), 8);
+        assert_eq!(scenic_score(&parse(test).unwrap(), 2, 1), 4);
+        assert_eq!(scenic_score(&parse(test).unwrap(), 2, 3), 8);
     }
 }
@@ -1,4 +1,10 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
 use std::collections::HashSet;
+use std::fmt::Display;
 use structopt::StructOpt;
 
 #[derive(StructOpt)]
@@ -32,14 +38,28 @@ fn move_tail(head: (i32, i32), tail: (i32, i32)) -> (i32, i32) {
     )
 }
 
-fn perform_general(knots: Vec<(i32, i32)>, dir: &str) -> Vec<(i32, i32)> {
-    let mut new_head = match dir {
-        "U" => (knots[0].0, knots[0].1 + 1),
-        "D" => (knots[0].0, knots[0].1 - 1),
-        "R" => (knots[0].0 + 1, knots[0].1),
-        "L" => (knots[0].0 - 1, knots[0].1),
-        _ => panic!("unexpected"),
+/// A step for the head and how many times to take it.
+type Motion = ((i32, i32), usize);
+
+fn motion(line: &str) -> Result<Motion, ParseError> {
+    let (direction, times) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(line, line, "a direction and a count"))?;
+    let step = match direction {
+        "U" => (0, 1),
+        "D" => (0, -1),
+        "R" => (1, 0),
+        "L" => (-1, 0),
+        _ => return Err(ParseError::new(line, direction, "U, D, R or L")),
     };
+    let times = times
+        .parse()
+        .map_err(|_| ParseError::new(line, times, "a count"))?;
+    Ok((step, times))
+}
+
+fn perform_general(knots: Vec<(i32, i32)>, (dx, dy): (i32, i32)) -> Vec<(i32, i32)> {
+    let mut new_head = (knots[0].0 + dx, knots[0].1 + dy);
     let mut new_rope = Vec::with_capacity(40);
     new_rope.push(new_head);
     for tail in knots.iter().skip(1) {
@@ -49,36 +69,72 @@ fn perform_general(knots: Vec<(i32, i32)>, dir: &str) -> Vec<(i32, i32)> {
     new_rope
 }
 
-fn calc(lines: &str, snake_size: usize) -> usize {
-    let mut previous_tails = HashSet::new();
-    previous_tails.insert((0, 0));
-    lines
-        .lines()
-        .fold(
-            (previous_tails, vec![(0, 0); snake_size]),
-            |(mut previous_tails, mut current), line| {
-                let parts = line.split(" ").collect::<Vec<&str>>();
-                match parts.as_slice() {
-                    [direction, times] => {
-                        for _ in 0..(times.parse::<usize>().unwrap()) {
-                            current = perform_general(current, direction);
-                            previous_tails.insert(*(current.last().unwrap()));
-                        }
-                        (previous_tails, current)
-                    }
-                    _ => panic!("Error"),
-                }
-            },
-        )
-        .0
-        .len()
+/// How many positions each of `knots` visits on a rope of `snake_size` knots. A knot only
+/// follows the knots ahead of it, so shorter ropes are answered by the same pass.
+fn visited(
+    motions: impl IntoIterator<Item = Motion>,
+    snake_size: usize,
+    knots: &[usize],
+) -> Vec<usize> {
+    let mut previous = vec![HashSet::from([(0, 0)]); knots.len()];
+    let mut current = vec![(0, 0); snake_size];
+    for (step, times) in motions {
+        for _ in 0..times {
+            current = perform_general(current, step);
+            for (previous, &knot) in previous.iter_mut().zip(knots) {
+                previous.insert(current[knot]);
+            }
+        }
+    }
+    previous.iter().map(HashSet::len).collect()
+}
+
+fn calc(motions: &[Motion], snake_size: usize) -> usize {
+    visited(motions.iter().copied(), snake_size, &[snake_size - 1])[0]
+}
+
+fn parse(input: &str) -> Result<Vec<Motion>, ParseError> {
+    parse_lines(input.as_bytes(), motion).collect()
+}
+
+pub struct Day9;
+
+// each part replays the moves with a different rope length
+impl Solution for Day9 {
+    type Input<'a> = Vec<Motion>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        calc(input, 2)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        calc(input, 10)
+    }
+}
+
+impl Streaming for Day9 {
+    type Line = Motion;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        motion(line)
+    }
+
+    fn answers(motions: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let tails = visited(motions, 10, &[1, 9]);
+        (tails[0], tails[1])
+    }
 }
 
 fn main() {
     let args = Cli::from_args();
-    let input = std::fs::read_to_string(args.path.as_path()).unwrap();
-    println!("{:?}", calc(&input, 2));
-    println!("{:?}", calc(&input, 10));
+    let input = input::open(args.path.as_path()).unwrap();
+    let tails = visited(parse_lines(input, motion).map(or_exit), 10, &[1, 9]);
+    println!("{:?}", tails[0]);
+    println!("{:?}", tails[1]);
 }
 
 #[cfg(test)]
@@ -87,7 +143,7 @@ mod tests {
 
     #[test]
     fn test_case() {
-        let input = include_str!("../../input/day9-test");
+        let input = &parse(include_str!("../../input/day9-test")).unwrap();
         assert_eq!(calc(input, 2), 13);
     }
 
@@ -101,17 +157,17 @@ mod tests {
 
     #[test]
     fn test_case_general() {
-        let input = include_str!("../../input/day9-test");
+        let input = &parse(include_str!("../../input/day9-test")).unwrap();
         assert_eq!(calc(input, 10), 1);
-        let input = include_str!("../../input/day9-test2");
+        let input = &parse(include_str!("../../input/day9-test2")).unwrap();
         assert_eq!(calc(input, 10), 36);
     }
 
     #[test]
     fn test_solution() {
-        let input = include_str!("../../input/day9");
+        let input = &parse(include_str!("../../input/day9")).unwrap();
         assert_eq!(calc(input, 2), 6494);
-        let input = include_str!("../../input/day9");
+        let input = &parse(include_str!("../../input/day9")).unwrap();
         assert_eq!(calc(input, 10), 2691);
     }
 }
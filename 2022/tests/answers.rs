@@ -0,0 +1,26 @@
+use advent_of_code::answers;
+
+fn check(args: &[&str]) {
+    answers::check(env!("CARGO_BIN_EXE_aoc"), env!("CARGO_MANIFEST_DIR"), args);
+}
+
+#[test]
+fn examples() {
+    for day in (1..=25).filter(|&day| day != 19) {
+        check(&["--examples", "-d", &day.to_string()]);
+    }
+}
+
+// the day 19 example alone takes minutes without optimisations
+#[test]
+#[ignore]
+fn day19_example() {
+    check(&["--examples", "-d", "19"]);
+}
+
+// the real inputs take minutes to solve, run with `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn real_inputs() {
+    check(&[]);
+}
@@ -1,12 +1,13 @@
+use advent_of_code::{
+    input,
+    parse::{or_exit, parse_lines, ParseError},
+    solution::{Solution, Streaming},
+};
 use clap::{arg, command, Parser};
 use regex::Regex;
-use std::io::Read;
+use std::fmt::Display;
 use std::sync::OnceLock;
 use std::time::Instant;
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-};
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
@@ -82,29 +83,75 @@ fn word_digit(line: &str) -> Option<u32> {
     format!("{}{}", first, last).parse::<u32>().ok()
 }
 
-fn result<R: Read>(reader: BufReader<R>, f: impl Fn(&str) -> Option<u32>) -> Option<u32> {
-    Some(
-        reader
-            .lines()
-            .map(|line| f(&line.unwrap()))
-            .collect::<Option<Vec<u32>>>()?
-            .iter()
-            .sum::<u32>(),
+type Calibration = (Option<u32>, Option<u32>);
+
+// The value of a line read with digits only and with spelled out digits too
+fn calibration(line: &str) -> Result<Calibration, ParseError> {
+    match word_digit(line) {
+        Some(word) => Ok((number_digit(line), Some(word))),
+        None => Err(ParseError::new(line, line, "a line with a digit")),
+    }
+}
+
+// Both calibration sums in one pass, each is `None` once a line has no digit for it
+fn results(calibrations: impl IntoIterator<Item = Calibration>) -> Calibration {
+    calibrations
+        .into_iter()
+        .fold((Some(0), Some(0)), |(numbers, words), (number, word)| {
+            (
+                numbers.zip(number).map(|(a, x)| a + x),
+                words.zip(word).map(|(a, x)| a + x),
+            )
+        })
+}
+
+// Part 1 has no answer when a line only spells its digits out, like in part 2's example
+fn answer(sum: Option<u32>) -> String {
+    sum.map_or_else(
+        || "no digit on some line".to_string(),
+        |sum| sum.to_string(),
     )
 }
 
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<Calibration>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), calibration).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        answer(results(input.iter().copied()).0)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        answer(results(input.iter().copied()).1)
+    }
+}
+
+impl Streaming for Day1 {
+    type Line = Calibration;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        calibration(line)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let (numbers, words) = results(lines);
+        (answer(numbers), answer(words))
+    }
+}
+
 fn main() {
     let args = Cli::parse();
-    let input = File::open(args.path.as_path()).unwrap();
+    let input = input::open(args.path.as_path()).unwrap();
     let start = Instant::now();
-    let reader = BufReader::new(&input);
-    let sum = result(reader, number_digit).expect("Error occured");
-    println!("{:?}", sum);
-
-    let input = File::open(args.path.as_path()).unwrap();
-    let reader = BufReader::new(&input);
-    let sum = result(reader, word_digit).expect("Error occured");
-    println!("{:?}", sum);
+    // the lines are streamed, stopping at the first bad one
+    let (numbers, words) = results(parse_lines(input, calibration).map(or_exit));
+    println!("{}", answer(numbers));
+    println!("{}", answer(words));
 
     println!("Time elapsed: {:?}", start.elapsed());
 }
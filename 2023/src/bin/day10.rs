@@ -1,6 +1,7 @@
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::iter::Iterator;
 
+use advent_of_code::{parse::ParseError, polygon, solution::Solution};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -8,6 +9,11 @@ use clap::Parser;
 struct Cli {
     #[arg(long, short)]
     path: std::path::PathBuf,
+
+    /// Count the enclosed tiles with the shoelace formula and Pick's theorem instead of
+    /// scanning every row
+    #[arg(long)]
+    polygon: bool,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -171,6 +177,34 @@ fn count_enclosed_area_by_loop(
     inside.len() as u32
 }
 
+/// The tiles of the loop through `start`, in order.
+fn loop_path(map: &HashMap<(i32, i32), Vec<(i32, i32)>>, start: (i32, i32)) -> Vec<(i32, i32)> {
+    let mut path = vec![start];
+    let (mut previous, mut current) = (start, map[&start][0]);
+    while current != start {
+        path.push(current);
+        let next = *map[&current]
+            .iter()
+            .find(|&&next| next != previous)
+            .expect("Loop ends in a dead end");
+        previous = current;
+        current = next;
+    }
+    path
+}
+
+/// The loop's tile centres make a lattice polygon, and the enclosed tiles are its interior points.
+fn count_enclosed_area_by_polygon(
+    map: &HashMap<(i32, i32), Vec<(i32, i32)>>,
+    start: (i32, i32),
+) -> u32 {
+    let vertices = loop_path(map, start)
+        .into_iter()
+        .map(|(x, y)| (x as i64, y as i64))
+        .collect::<Vec<_>>();
+    polygon::interior_points(&vertices) as u32
+}
+
 fn pipe_to_char(pipe: (&(i32, i32), &Vec<(i32, i32)>)) -> char {
     let ((pipe_x, pipe_y), adjacent) = pipe;
     let delta = adjacent
@@ -189,15 +223,36 @@ fn pipe_to_char(pipe: (&(i32, i32), &Vec<(i32, i32)>)) -> char {
     }
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input<'a> = ((i32, i32), HashMap<(i32, i32), Vec<(i32, i32)>>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(build_pipe_map(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (start, map) = input;
+        find_furthest_point(map.clone(), *start)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (start, map) = input;
+        count_enclosed_area_by_loop(map.clone(), *start)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
     let (start, map) = build_pipe_map(&input);
     println!("Part 1: {}", find_furthest_point(map.clone(), start));
-    println!(
-        "Part 2: {}",
-        count_enclosed_area_by_loop(map.clone(), start)
-    );
+    if args.polygon {
+        println!("Part 2: {}", count_enclosed_area_by_polygon(&map, start));
+    } else {
+        println!("Part 2: {}", count_enclosed_area_by_loop(map, start));
+    }
 }
 
 #[cfg(test)]
@@ -238,4 +293,29 @@ mod tests {
         let (start, map) = build_pipe_map(input);
         assert_eq!(count_enclosed_area_by_loop(map, start), 8);
     }
+
+    #[test]
+    fn test_enclosed_area_by_polygon() {
+        for (input, expected) in [
+            (include_str!("../../input/day10-example-1"), 1),
+            (include_str!("../../input/day10-example-3"), 4),
+            (include_str!("../../input/day10-example-4"), 8),
+        ] {
+            let (start, map) = build_pipe_map(input);
+            assert_eq!(count_enclosed_area_by_polygon(&map, start), expected);
+        }
+        let (start, map) = build_pipe_map(include_str!("../../input/day10"));
+        assert_eq!(
+            count_enclosed_area_by_polygon(&map, start),
+            count_enclosed_area_by_loop(map, start)
+        );
+    }
+
+    #[test]
+    fn test_loop_path() {
+        let (start, map) = build_pipe_map(include_str!("../../input/day10-example-2"));
+        let path = loop_path(&map, start);
+        assert_eq!(path.len(), 16);
+        assert_eq!(path.len() as u32 / 2, find_furthest_point(map, start));
+    }
 }
@@ -5,6 +5,10 @@ use std::{
     u128::MAX,
 };
 
+use advent_of_code::{
+    parse::{or_exit, ParseError},
+    solution::Solution,
+};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -15,20 +19,26 @@ struct Cli {
 }
 
 // grid parse
-fn expand_galaxy(input: &str, expansion_factor: usize) -> BTreeSet<(i64, i64)> {
+fn galaxies(input: &str) -> Result<BTreeSet<(usize, usize)>, ParseError> {
     let mut original_grid = BTreeSet::new();
     for (y, line) in input.lines().enumerate() {
-        for (x, c) in line.chars().enumerate() {
+        for (x, (i, c)) in line.char_indices().enumerate() {
             match c {
                 '#' => {
                     original_grid.insert((x, y));
                 }
                 '.' => {}
-                _ => panic!("Invalid char: {}", c),
+                _ => return Err(ParseError::new(input, &line[i..], "'.' or '#'")),
             }
         }
     }
+    Ok(original_grid)
+}
 
+fn expand_galaxy(
+    original_grid: &BTreeSet<(usize, usize)>,
+    expansion_factor: usize,
+) -> BTreeSet<(i64, i64)> {
     let max_x = original_grid.iter().map(|(x, _)| *x).max().unwrap();
     let max_y = original_grid.iter().map(|(_, y)| *y).max().unwrap();
 
@@ -49,8 +59,8 @@ fn expand_galaxy(input: &str, expansion_factor: usize) -> BTreeSet<(i64, i64)> {
         .collect::<BTreeSet<usize>>();
 
     original_grid
-        .into_iter()
-        .map(|(x, y)| {
+        .iter()
+        .map(|&(x, y)| {
             (
                 (x + (expansion_factor - 1)
                     * empty_x
@@ -65,7 +75,7 @@ fn expand_galaxy(input: &str, expansion_factor: usize) -> BTreeSet<(i64, i64)> {
         .collect()
 }
 
-fn sum_of_neighbour_distance(input: &str, factor: usize) -> i64 {
+fn sum_of_neighbour_distance(input: &BTreeSet<(usize, usize)>, factor: usize) -> i64 {
     let map = expand_galaxy(input, factor);
     let mut total = 0;
     for left in map.iter() {
@@ -79,9 +89,29 @@ fn sum_of_neighbour_distance(input: &str, factor: usize) -> i64 {
     total
 }
 
+pub struct Day11;
+
+// the galaxy is expanded by a different factor in each part
+impl Solution for Day11 {
+    type Input<'a> = BTreeSet<(usize, usize)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        galaxies(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_of_neighbour_distance(input, 2)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_of_neighbour_distance(input, 1_000_000)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
+    let input = or_exit(galaxies(&input));
     println!("Part 1: {}", sum_of_neighbour_distance(&input, 2));
     println!("Part 2: {}", sum_of_neighbour_distance(&input, 1_000_000));
 }
@@ -91,8 +121,8 @@ mod tests {
 
     #[test]
     fn test_expand_galaxy() {
-        let input = include_str!("../../input/day11-example-1");
-        let output = expand_galaxy(input, 2);
+        let input = galaxies(include_str!("../../input/day11-example-1")).unwrap();
+        let output = expand_galaxy(&input, 2);
         println!("{:?}", output);
         assert_eq!(output.len(), 9);
         assert!(output.contains(&(4, 0)));
@@ -101,20 +131,19 @@ mod tests {
 
     #[test]
     fn test_example() {
-        let input = include_str!("../../input/day11-example-1");
+        let input = galaxies(include_str!("../../input/day11-example-1")).unwrap();
         assert_eq!(sum_of_neighbour_distance(&input, 2), 374);
     }
 
     #[test]
     fn test_example_with_expansion_ten() {
-        let input = include_str!("../../input/day11-example-1");
+        let input = galaxies(include_str!("../../input/day11-example-1")).unwrap();
         assert_eq!(sum_of_neighbour_distance(&input, 10), 1030);
     }
 
     #[test]
     fn test_example_with_expansion_one_hundred() {
-        let input = include_str!("../../input/day11-example-1");
-        let output = expand_galaxy(input, 100);
+        let input = galaxies(include_str!("../../input/day11-example-1")).unwrap();
         assert_eq!(sum_of_neighbour_distance(&input, 100), 8410);
     }
 }
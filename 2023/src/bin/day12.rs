@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 
+use advent_of_code::{
+    parse::{from_nom, IResult, ParseError},
+    solution::Solution,
+};
 use ahash::RandomState;
 use bumpalo::Bump;
 use clap::Parser;
@@ -9,7 +13,6 @@ use nom::{
     combinator::{opt, value},
     multi::many1,
     sequence::{separated_pair, terminated},
-    IResult,
 };
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
@@ -21,7 +24,7 @@ struct Cli {
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
-enum SpringCondition {
+pub enum SpringCondition {
     Operational,
     Damaged,
     Unknown,
@@ -119,8 +122,7 @@ fn valid_combos<'a>(
     result
 }
 
-fn part1(input: &str) -> u64 {
-    let (_, records) = records(input).unwrap();
+fn part1(records: &[(Vec<SpringCondition>, Vec<u32>)]) -> u64 {
     records
         .iter()
         .map(|(conditions, criteria)| {
@@ -133,8 +135,7 @@ fn part1(input: &str) -> u64 {
         .sum::<u64>()
 }
 
-fn part2(input: &str) -> u64 {
-    let (_, records) = records(input).unwrap();
+fn part2(records: &[(Vec<SpringCondition>, Vec<u32>)]) -> u64 {
     records
         .iter()
         .map(|(conditions, criteria)| {
@@ -173,11 +174,30 @@ fn part2(input: &str) -> u64 {
         .sum::<u64>()
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input<'a> = Vec<(Vec<SpringCondition>, Vec<u32>)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, records(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
-    println!("Part 1: {}", part1(&input));
-    println!("Part 2: {}", part2(&input));
+    let (_, records) = records(&input).unwrap();
+    println!("Part 1: {}", part1(&records));
+    println!("Part 2: {}", part2(&records));
 }
 
 #[cfg(test)]
@@ -529,12 +549,12 @@ mod tests {
     #[test]
     fn test_example_part1() {
         let input = "???.### 1,1,3\n.??..??...?##. 1,1,3\n?#?#?#?#?#?#?#? 1,3,1,6\n????.#...#... 4,1,1\n????.######..#####. 1,6,5\n?###???????? 3,2,1";
-        assert_eq!(part1(input), 21);
+        assert_eq!(part1(&records(input).unwrap().1), 21);
     }
 
     #[test]
     fn test_example_part2() {
         let input = "???.### 1,1,3\n.??..??...?##. 1,1,3\n?#?#?#?#?#?#?#? 1,3,1,6\n????.#...#... 4,1,1\n????.######..#####. 1,6,5\n?###???????? 3,2,1";
-        assert_eq!(part2(input), 525152);
+        assert_eq!(part2(&records(input).unwrap().1), 525152);
     }
 }
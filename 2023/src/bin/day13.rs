@@ -1,12 +1,15 @@
+use advent_of_code::{
+    grid::Grid,
+    parse::{from_nom, tag, IResult, ParseError},
+    solution::Solution,
+};
 use clap::Parser;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::newline,
     combinator::{map, opt, value},
     multi::many1,
     sequence::terminated,
-    IResult,
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -17,37 +20,13 @@ struct Cli {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Item {
+pub enum Item {
     Ash,
     Rock,
 }
 
-struct Grid {
-    items: Vec<Vec<Item>>,
-    width: i32,
-    height: i32,
-}
-
-impl Grid {
-    fn new(items: Vec<Vec<Item>>) -> Self {
-        let height = items.len() as i32;
-        let width = items[0].len() as i32;
-        Self {
-            items,
-            width,
-            height,
-        }
-    }
-    fn get(&self, x: i32, y: i32) -> Option<Item> {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.items[y as usize][x as usize])
-    }
-}
-
 // parse a grid of characters
-fn grid(input: &str) -> IResult<&str, Grid> {
+fn grid(input: &str) -> IResult<&str, Grid<Item>> {
     map(
         many1(terminated(
             many1(alt((
@@ -60,7 +39,7 @@ fn grid(input: &str) -> IResult<&str, Grid> {
     )(input)
 }
 
-fn grids(input: &str) -> IResult<&str, Vec<Grid>> {
+fn grids(input: &str) -> IResult<&str, Vec<Grid<Item>>> {
     many1(terminated(grid, opt(newline)))(input)
 }
 
@@ -72,17 +51,15 @@ fn flip_y((x, y): (i32, i32), y_axis: i32) -> (i32, i32) {
     (x, 2 * y_axis - y - 1)
 }
 
-fn horizontal_flip(grid: &Grid, max_error: usize) -> Option<i32> {
+fn horizontal_flip(grid: &Grid<Item>, max_error: usize) -> Option<i32> {
     (1..grid.width).find(|axis| {
-        grid.items
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, item)| ((x, y), item)))
-            .map(|((x, y), item)| (flip_x((x as i32, y as i32), *axis), item))
+        grid.all()
+            .into_iter()
+            .map(|(x, y, item)| (flip_x((x, y), *axis), item))
             .map(|((fx, fy), item)| (item, grid.get(fx, fy)))
             .filter_map(|(item, other)| match (item, other) {
                 (_, None) => None,
-                (first, Some(flipped)) => Some(*first == flipped),
+                (first, Some(flipped)) => Some(first == flipped),
             })
             .filter(|x| !x)
             .count()
@@ -90,18 +67,16 @@ fn horizontal_flip(grid: &Grid, max_error: usize) -> Option<i32> {
     })
 }
 
-fn vertical_flip(grid: &Grid, max_error: usize) -> Option<i32> {
+fn vertical_flip(grid: &Grid<Item>, max_error: usize) -> Option<i32> {
     // enumerate over all cell in grid
     (1..grid.height).find(|axis| {
-        grid.items
-            .iter()
-            .enumerate()
-            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, item)| ((x, y), item)))
-            .map(|((x, y), item)| (flip_y((x as i32, y as i32), *axis), item))
+        grid.all()
+            .into_iter()
+            .map(|(x, y, item)| (flip_y((x, y), *axis), item))
             .map(|((fx, fy), item)| (item, grid.get(fx, fy)))
             .filter_map(|(item, other)| match (item, other) {
                 (_, None) => None,
-                (first, Some(flipped)) => Some(*first == flipped),
+                (first, Some(flipped)) => Some(first == flipped),
             })
             .filter(|x| !x)
             .count()
@@ -109,8 +84,7 @@ fn vertical_flip(grid: &Grid, max_error: usize) -> Option<i32> {
     })
 }
 
-fn solve(input: &str, error: usize) -> i32 {
-    let grids = grids(input).unwrap().1;
+fn solve(grids: &[Grid<Item>], error: usize) -> i32 {
     grids
         .iter()
         .map(|grid| {
@@ -120,11 +94,30 @@ fn solve(input: &str, error: usize) -> i32 {
         .sum()
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input<'a> = Vec<Grid<Item>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, grids(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve(input, 0)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve(input, 2)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
-    println!("Part 1: {}", solve(&input, 0));
-    println!("Part 2: {}", solve(&input, 2));
+    let (_, grids) = grids(&input).unwrap();
+    println!("Part 1: {}", solve(&grids, 0));
+    println!("Part 2: {}", solve(&grids, 2));
 }
 
 #[cfg(test)]
@@ -183,7 +176,7 @@ mod tests {
     #[test]
     fn test_example_part1() {
         let input = include_str!("../../input/day13-example");
-        assert_eq!(solve(input, 0), 405);
+        assert_eq!(solve(&grids(input).unwrap().1, 0), 405);
     }
 
     #[test]
@@ -1,8 +1,6 @@
-use std::{
-    cell::RefCell,
-    collections::{hash_map::Entry, HashMap},
-};
+use std::cell::RefCell;
 
+use advent_of_code::{grid::Grid, parse::ParseError, solution::Solution};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -13,96 +11,38 @@ struct Cli {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Item {
+pub enum Item {
     Round,
     Cube,
     Empty,
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-struct Grid {
-    items: Vec<Item>,
-    width: usize,
-    height: usize,
-}
-
-impl Grid {
-    fn new(items: Vec<Vec<Item>>) -> Self {
-        let height = items.len();
-        let width = items[0].len();
-        let items = items.into_iter().flat_map(|x| x.into_iter()).collect();
-        Self {
-            items,
-            width,
-            height,
-        }
-    }
-
-    fn get(&self, x: usize, y: usize) -> Option<Item> {
-        if x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.items[y * self.width + x])
-    }
-
-    fn update(self, new_rounds: &mut Vec<(usize, usize)>) -> Self {
-        let mut items = self.items;
-        // reset grid
-        for item in items.iter_mut() {
-            *item = match item {
-                Item::Round => Item::Empty,
-                Item::Cube => Item::Cube,
-                Item::Empty => Item::Empty,
-            };
-        }
-        // update grid
-        for (x, y) in new_rounds {
-            items[*y * self.width + *x] = Item::Round;
-        }
-        Self {
-            items,
-            width: self.width,
-            height: self.height,
-        }
+fn update(mut grid: Grid<Item>, new_rounds: &[(i32, i32)]) -> Grid<Item> {
+    // reset grid
+    for item in grid.cells_mut() {
+        *item = match item {
+            Item::Round => Item::Empty,
+            Item::Cube => Item::Cube,
+            Item::Empty => Item::Empty,
+        };
     }
-}
-
-impl std::fmt::Debug for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        //for self.items
-        //for line in self.items.iter() {
-        //    for item in line.iter() {
-        //        match item {
-        //            Item::Round => write!(f, "O")?,
-        //            Item::Cube => write!(f, "#")?,
-        //            Item::Empty => write!(f, ".")?,
-        //        }
-        //    }
-        //    writeln!(f)?;
-        //}
-        Ok(())
+    // update grid
+    for (x, y) in new_rounds {
+        grid.set(*x, *y, Item::Round);
     }
+    grid
 }
 
-fn grid(input: &str) -> Grid {
-    Grid::new(
-        input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|c| match c {
-                        '.' => Item::Empty,
-                        'O' => Item::Round,
-                        '#' => Item::Cube,
-                        _ => unreachable!(),
-                    })
-                    .collect()
-            })
-            .collect(),
-    )
+fn grid(input: &str) -> Grid<Item> {
+    Grid::parse(input, |c| match c {
+        '.' => Item::Empty,
+        'O' => Item::Round,
+        '#' => Item::Cube,
+        _ => unreachable!(),
+    })
 }
 
-fn new_round_positions(visits: impl Iterator<Item = (Item)>) -> impl Iterator<Item = usize> {
+fn new_round_positions(visits: impl Iterator<Item = Item>) -> impl Iterator<Item = i32> {
     visits
         .enumerate()
         .scan(0, move |highest, (w, item)| match item {
@@ -112,14 +52,14 @@ fn new_round_positions(visits: impl Iterator<Item = (Item)>) -> impl Iterator<It
                 Some(Some(*highest - 1))
             }
             Item::Cube => {
-                *highest = w + 1;
+                *highest = w as i32 + 1;
                 Some(None)
             }
         })
         .flatten()
 }
 
-fn calc_load(grid: &Grid) -> usize {
+fn calc_load(grid: &Grid<Item>) -> i32 {
     (0..grid.width)
         .flat_map(|x| {
             new_round_positions((0..grid.height).map(move |y| grid.get(x, y).unwrap()))
@@ -130,10 +70,10 @@ fn calc_load(grid: &Grid) -> usize {
 }
 
 thread_local! {
-    static BUF: RefCell<Vec<(usize, usize)>> = RefCell::new(Vec::new());
+    static BUF: RefCell<Vec<(i32, i32)>> = const { RefCell::new(Vec::new()) };
 }
 
-fn cycle(mut grid: Grid) -> Grid {
+fn cycle(mut grid: Grid<Item>) -> Grid<Item> {
     BUF.with(|buf| {
         let buf = &mut *buf.borrow_mut();
         grid = {
@@ -142,7 +82,7 @@ fn cycle(mut grid: Grid) -> Grid {
                 new_round_positions((0..grid.height).map(move |y| grid_ref.get(x, y).unwrap()))
                     .map(move |y| (x, y))
             }));
-            grid.update(buf)
+            update(grid, buf)
         };
         buf.clear();
         grid = {
@@ -151,7 +91,7 @@ fn cycle(mut grid: Grid) -> Grid {
                 new_round_positions((0..grid.width).map(move |x| grid_ref.get(x, y).unwrap()))
                     .map(move |x| (x, y))
             }));
-            grid.update(buf)
+            update(grid, buf)
         };
         buf.clear();
         grid = {
@@ -162,7 +102,7 @@ fn cycle(mut grid: Grid) -> Grid {
                 )
                 .map(move |y| (x, grid_ref.height - y - 1))
             }));
-            grid.update(buf)
+            update(grid, buf)
         };
         buf.clear();
         grid = {
@@ -173,50 +113,47 @@ fn cycle(mut grid: Grid) -> Grid {
                 )
                 .map(move |x| (grid_ref.width - x - 1, y))
             }));
-            grid.update(buf)
+            update(grid, buf)
         };
         buf.clear();
         grid
     })
 }
 
-fn part2(input: Grid) -> usize {
-    let mut grid = input;
-    let mut i = 0;
-    let mut visited = HashMap::new();
-    let until = 1_000_000_000;
-    loop {
-        match visited.entry(grid.clone()) {
-            Entry::Occupied(entry) => {
-                println!("found at: {}", i);
-                let cycle_time = i - entry.get();
-                let remaining = until - i;
-                let times = remaining / cycle_time;
-                i += times * cycle_time;
-            }
-            Entry::Vacant(entry) => {
-                entry.insert(i);
-            }
-        }
-        grid = cycle(grid);
-        i += 1;
-        if i == until {
-            break;
-        }
-    }
-    let grid_ref = &grid;
+fn part2(input: Grid<Item>) -> i32 {
+    let (spin_cycle, grids) = advent_of_code::cycle::find(input, |grid| cycle(grid.clone()));
+    let grid = &grids[spin_cycle.reduce(1_000_000_000)];
     (0..grid.width)
-        .flat_map(|x| (0..grid.height).map(move |y| (x, y, grid_ref.get(x, y).unwrap())))
+        .flat_map(|x| (0..grid.height).map(move |y| (x, y, grid.get(x, y).unwrap())))
         .filter(|(_, _, item)| *item == Item::Round)
         .map(|(_, y, _)| grid.height - y)
         .sum()
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input<'a> = Grid<Item>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(grid(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        calc_load(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input.clone())
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
-    println!("Part 1: {}", calc_load(&grid(&input)));
-    println!("Part 2: {}", part2(grid(&input)));
+    let grid = grid(&input);
+    println!("Part 1: {}", calc_load(&grid));
+    println!("Part 2: {}", part2(grid));
 }
 
 #[cfg(test)]
@@ -1,12 +1,14 @@
+use advent_of_code::{
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
 use clap::Parser;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::alpha1,
-    combinator::{map, opt, value},
-    multi::many1,
-    sequence::{preceded, terminated, tuple},
-    IResult,
+    combinator::{consumed, map, value},
+    multi::separated_list1,
+    sequence::{preceded, tuple},
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -45,7 +47,7 @@ impl<'a> std::fmt::Debug for Slot<'a> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Operation {
+pub enum Operation {
     Assign(u32),
     Remove,
 }
@@ -60,15 +62,17 @@ fn hash(input: &[u8]) -> u32 {
     current_value
 }
 
-fn sum_of_hash(input: &str) -> u32 {
-    let input: Vec<u8> = input.bytes().filter(|c| *c != b'\n').collect();
-    let sequence = input.as_slice().split(|c| *c == b',');
-    sequence.map(hash).sum()
+// Each step is kept with its text, which part 1 hashes as is
+type Step<'a> = (&'a str, (&'a [u8], Operation));
+
+fn sum_of_hash(steps: &[Step]) -> u32 {
+    steps.iter().map(|(text, _)| hash(text.as_bytes())).sum()
 }
 
-fn instructions(input: &str) -> IResult<&str, Vec<(&[u8], Operation)>> {
-    many1(terminated(
-        tuple((
+fn instructions(input: &str) -> IResult<&str, Vec<Step<'_>>> {
+    separated_list1(
+        tag(","),
+        consumed(tuple((
             map(alpha1, str::as_bytes),
             alt((
                 map(
@@ -77,15 +81,13 @@ fn instructions(input: &str) -> IResult<&str, Vec<(&[u8], Operation)>> {
                 ),
                 value(Operation::Remove, tag("-")),
             )),
-        )),
-        opt(tag(",")),
-    ))(input)
+        ))),
+    )(input)
 }
 
-fn interpret(input: &str) -> HashTable {
+fn interpret<'a>(steps: &[Step<'a>]) -> HashTable<'a> {
     let mut table = HashTable::default();
-    let instructions = instructions(input).unwrap().1;
-    for (key, operation) in instructions {
+    for &(_, (key, operation)) in steps {
         let hash = hash(key);
         match operation {
             Operation::Assign(value) => {
@@ -119,11 +121,35 @@ fn score(table: &HashTable) -> u32 {
     score
 }
 
+fn part2(steps: &[Step]) -> u32 {
+    score(&interpret(steps))
+}
+
+pub struct Day15;
+
+// part 1 hashes the raw steps while part 2 interprets them
+impl Solution for Day15 {
+    type Input<'a> = Vec<Step<'a>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, instructions(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_of_hash(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
+    let input = or_exit(from_nom(&input, instructions(&input)));
     println!("Part 1: {}", sum_of_hash(&input));
-    println!("Part 2: {}", score(&interpret(&input)));
+    println!("Part 2: {}", part2(&input));
 }
 
 #[cfg(test)]
@@ -132,18 +158,18 @@ mod tests {
 
     #[test]
     fn test_sum_of_hash() {
-        assert_eq!(
-            sum_of_hash("rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7"),
-            1320
-        );
+        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7\n";
+        let steps = from_nom(input, instructions(input)).unwrap();
+        assert_eq!(sum_of_hash(&steps), 1320);
     }
 
     #[test]
     fn test_parser() {
         let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        let (_, instructions) = instructions(input).unwrap();
+        let (_, steps) = instructions(input).unwrap();
+        assert_eq!(steps[2].0, "qp=3");
         assert_eq!(
-            instructions,
+            steps.into_iter().map(|(_, step)| step).collect::<Vec<_>>(),
             vec![
                 (b"rn".as_slice(), Operation::Assign(1)),
                 (b"cm".as_slice(), Operation::Remove),
@@ -163,7 +189,8 @@ mod tests {
     #[test]
     fn test_example_score() {
         let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
-        let table = interpret(input);
+        let steps = from_nom(input, instructions(input)).unwrap();
+        let table = interpret(&steps);
         println!("{:?}", table);
         assert_eq!(score(&table), 145);
     }
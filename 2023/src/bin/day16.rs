@@ -1,14 +1,15 @@
-use std::{fmt::Debug, iter::repeat};
-
+use advent_of_code::{
+    grid::Grid,
+    parse::{from_nom, tag, IResult, ParseError},
+    solution::Solution,
+};
 use clap::Parser;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::newline,
     combinator::{map, opt, value},
     multi::many1,
     sequence::terminated,
-    IResult,
 };
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
@@ -19,7 +20,7 @@ struct Cli {
     path: std::path::PathBuf,
 }
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Item {
+pub enum Item {
     Empty,
     ForwardMirror,
     BackMirror,
@@ -33,73 +34,29 @@ impl Default for Item {
     }
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
-struct Grid<T> {
-    items: Vec<T>,
-    width: i32,
-    height: i32,
-}
-
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct State {
-    left: Grid<bool>,
-    right: Grid<bool>,
-    up: Grid<bool>,
-    down: Grid<bool>,
+    left: Beams,
+    right: Beams,
+    up: Beams,
+    down: Beams,
 }
 
-impl<T: Copy + Default> Grid<T> {
-    fn new(arrays: Vec<Vec<T>>) -> Self {
-        let height = arrays.len() as i32;
-        let width = arrays[0].len() as i32;
-        let mut items = Vec::with_capacity(((width + 2) * (height + 2)) as usize);
-        items.extend(repeat(T::default()).take(width as usize + 2));
-        for y in 0..height {
-            items.push(T::default());
-            items.extend(arrays[y as usize].iter());
-            items.push(T::default());
-        }
-        items.extend(repeat(T::default()).take(width as usize + 2));
-        assert_eq!(items.len(), ((width + 2) * (height + 2)) as usize);
-        Self {
-            items,
-            width,
-            height,
-        }
-    }
-
-    fn fresh(width: i32, height: i32, item: T) -> Self {
-        let items = vec![item; ((width + 2) * (height + 2)) as usize];
-        assert_eq!(items.len(), ((width + 2) * (height + 2)) as usize);
-        Self {
-            items,
-            width,
-            height,
-        }
-    }
+/// Beams heading one way, with a border around the grid so beams can enter from outside.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Beams(Grid<bool>);
 
-    fn get(&self, x: i32, y: i32) -> T {
-        if x < -1 || y < -1 || x > self.width || y > self.height {
-            return Default::default();
-        }
-        let index = (((y + 1) * (self.width + 2)) + (x + 1)) as usize;
-        self.items[index]
+impl Beams {
+    fn fresh(width: i32, height: i32) -> Self {
+        Self(Grid::filled(width + 2, height + 2, false))
     }
 
-    fn set(&mut self, x: i32, y: i32, item: T) {
-        self.items[(((y + 1) * (self.width + 2)) + (x + 1)) as usize] = item;
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.0.get(x + 1, y + 1).unwrap_or_default()
     }
-}
 
-impl<T: Copy + Clone + Debug + Default> Debug for Grid<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                write!(f, "{: <6?}", self.get(x, y))?;
-            }
-            writeln!(f, "\n")?;
-        }
-        Ok(())
+    fn set(&mut self, x: i32, y: i32, beam: bool) {
+        self.0.set(x + 1, y + 1, beam);
     }
 }
 
@@ -120,17 +77,17 @@ fn grid(input: &str) -> IResult<&str, Grid<Item>> {
 }
 
 fn update(state: &State, grid: &Grid<Item>) -> State {
-    let mut left = Grid::fresh(grid.width, grid.height, false);
-    let mut right = Grid::fresh(grid.width, grid.height, false);
-    let mut up = Grid::fresh(grid.width, grid.height, false);
-    let mut down = Grid::fresh(grid.width, grid.height, false);
+    let mut left = Beams::fresh(grid.width, grid.height);
+    let mut right = Beams::fresh(grid.width, grid.height);
+    let mut up = Beams::fresh(grid.width, grid.height);
+    let mut down = Beams::fresh(grid.width, grid.height);
     for y in 0..grid.height {
         for x in 0..grid.width {
             let cur_left = state.left.get(x, y);
             let cur_right = state.right.get(x, y);
             let cur_up = state.up.get(x, y);
             let cur_down = state.down.get(x, y);
-            match grid.get(x, y) {
+            match grid.get(x, y).unwrap() {
                 Item::Empty => {
                     right.set(x, y, cur_right || state.right.get(x - 1, y));
                     left.set(x, y, cur_left || state.left.get(x + 1, y));
@@ -181,10 +138,10 @@ fn simulate_until_stable(state: State, grid: &Grid<Item>) -> State {
     }
 }
 
-fn count_energized(state: &State) -> u32 {
+fn count_energized(state: &State, grid: &Grid<Item>) -> u32 {
     let mut count = 0;
-    for y in 0..state.left.height {
-        for x in 0..state.left.width {
+    for y in 0..grid.height {
+        for x in 0..grid.width {
             if state.left.get(x, y)
                 || state.right.get(x, y)
                 || state.up.get(x, y)
@@ -197,22 +154,20 @@ fn count_energized(state: &State) -> u32 {
     count
 }
 
-fn part1(input: &str) -> u32 {
-    let grid = grid(input).unwrap().1;
-    let mut right = Grid::fresh(grid.width, grid.height, false);
+fn part1(grid: &Grid<Item>) -> u32 {
+    let mut right = Beams::fresh(grid.width, grid.height);
     right.set(-1, 0, true);
     let state = State {
-        up: Grid::fresh(grid.width, grid.height, false),
-        down: Grid::fresh(grid.width, grid.height, false),
-        left: Grid::fresh(grid.width, grid.height, false),
+        up: Beams::fresh(grid.width, grid.height),
+        down: Beams::fresh(grid.width, grid.height),
+        left: Beams::fresh(grid.width, grid.height),
         right,
     };
     let state = simulate_until_stable(state, &grid);
-    count_energized(&state)
+    count_energized(&state, grid)
 }
 
-fn part2(input: &str) -> u32 {
-    let grid = grid(input).unwrap().1;
+fn part2(grid: &Grid<Item>) -> u32 {
     let mut starting_positions: Vec<(i32, i32)> = Default::default();
     for x in 0..grid.width {
         starting_positions.push((x, -1)); // down
@@ -227,57 +182,76 @@ fn part2(input: &str) -> u32 {
         .map(|pos| {
             match pos {
                 (x, y) if *y == -1 => State {
-                    up: Grid::fresh(grid.width, grid.height, false),
+                    up: Beams::fresh(grid.width, grid.height),
                     down: {
-                        let mut g = Grid::fresh(grid.width, grid.height, false);
+                        let mut g = Beams::fresh(grid.width, grid.height);
                         g.set(*x, *y, true);
                         g
                     },
-                    left: Grid::fresh(grid.width, grid.height, false),
-                    right: Grid::fresh(grid.width, grid.height, false),
+                    left: Beams::fresh(grid.width, grid.height),
+                    right: Beams::fresh(grid.width, grid.height),
                 }, // down
                 (x, y) if *y == grid.height => State {
                     up: {
-                        let mut g = Grid::fresh(grid.width, grid.height, false);
+                        let mut g = Beams::fresh(grid.width, grid.height);
                         g.set(*x, *y, true);
                         g
                     },
-                    down: Grid::fresh(grid.width, grid.height, false),
-                    left: Grid::fresh(grid.width, grid.height, false),
-                    right: Grid::fresh(grid.width, grid.height, false),
+                    down: Beams::fresh(grid.width, grid.height),
+                    left: Beams::fresh(grid.width, grid.height),
+                    right: Beams::fresh(grid.width, grid.height),
                 }, // up
                 (x, y) if *x == -1 => State {
-                    up: Grid::fresh(grid.width, grid.height, false),
-                    down: Grid::fresh(grid.width, grid.height, false),
-                    left: Grid::fresh(grid.width, grid.height, false),
+                    up: Beams::fresh(grid.width, grid.height),
+                    down: Beams::fresh(grid.width, grid.height),
+                    left: Beams::fresh(grid.width, grid.height),
                     right: {
-                        let mut g = Grid::fresh(grid.width, grid.height, false);
+                        let mut g = Beams::fresh(grid.width, grid.height);
                         g.set(*x, *y, true);
                         g
                     },
                 }, // right
                 (x, y) if *x == grid.width => State {
-                    up: Grid::fresh(grid.width, grid.height, false),
-                    down: Grid::fresh(grid.width, grid.height, false),
+                    up: Beams::fresh(grid.width, grid.height),
+                    down: Beams::fresh(grid.width, grid.height),
                     left: {
-                        let mut g = Grid::fresh(grid.width, grid.height, false);
+                        let mut g = Beams::fresh(grid.width, grid.height);
                         g.set(*x, *y, true);
                         g
                     },
-                    right: Grid::fresh(grid.width, grid.height, false),
+                    right: Beams::fresh(grid.width, grid.height),
                 }, // left
                 (x, y) => panic!("Unexpected initial position. x: {}, y: {}", x, y),
             }
         })
         .map(|s| simulate_until_stable(s, &grid))
-        .map(|s| count_energized(&s))
+        .map(|s| count_energized(&s, grid))
         .max()
         .unwrap()
 }
 
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input<'a> = Grid<Item>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, grid(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let input = std::fs::read_to_string(args.path).unwrap();
+    let (_, input) = grid(&input).unwrap();
     let start = std::time::Instant::now();
     println!("Part 1: {}", part1(&input));
     println!("Time: {}ms", start.elapsed().as_millis());
@@ -294,12 +268,12 @@ mod tests {
     fn test_day16_example_one_step() {
         let input = include_str!("../../input/day16-example");
         let grid = grid(input).unwrap().1;
-        let mut right = Grid::fresh(grid.width, grid.height, false);
+        let mut right = Beams::fresh(grid.width, grid.height);
         right.set(0, 0, true);
         let state = State {
-            left: Grid::fresh(grid.width, grid.height, false),
-            up: Grid::fresh(grid.width, grid.height, false),
-            down: Grid::fresh(grid.width, grid.height, false),
+            left: Beams::fresh(grid.width, grid.height),
+            up: Beams::fresh(grid.width, grid.height),
+            down: Beams::fresh(grid.width, grid.height),
             right,
         };
         let state = update(&state, &grid);
@@ -315,12 +289,12 @@ mod tests {
     #[test]
     fn test_day16_example() {
         let input = include_str!("../../input/day16-example");
-        assert_eq!(part1(input), 46);
+        assert_eq!(part1(&grid(input).unwrap().1), 46);
     }
 
     #[test]
     fn test_day16_part2_example() {
         let input = include_str!("../../input/day16-example");
-        assert_eq!(part2(input), 51);
+        assert_eq!(part2(&grid(input).unwrap().1), 51);
     }
 }
@@ -1,5 +1,6 @@
-use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::collections::HashMap;
 
+use advent_of_code::{parse::ParseError, search::astar, solution::Solution};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -33,45 +34,9 @@ enum Direction {
 struct State {
     x: i32,
     y: i32,
-    distance: u32,
     direction: Direction,
-    destination: (i32, i32),
 }
 
-struct StateOrd(State);
-
-impl PartialOrd for StateOrd {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some((other.0.distance + other.heuristic()).cmp(&(self.0.distance + self.heuristic())))
-    }
-}
-
-impl Ord for StateOrd {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (other.0.distance + other.heuristic()).cmp(&(self.0.distance + self.heuristic()))
-    }
-}
-
-impl PartialEq for StateOrd {
-    fn eq(&self, other: &Self) -> bool {
-        (self.0.distance + self.heuristic()) == (other.0.distance + other.heuristic())
-    }
-}
-
-impl StateOrd {
-    fn heuristic(&self) -> u32 {
-        let Self(State {
-            x,
-            y,
-            destination: (dest_x, dest_y),
-            ..
-        }) = self;
-        (x - dest_x).unsigned_abs() + (y - dest_y).unsigned_abs()
-    }
-}
-
-impl Eq for StateOrd {}
-
 fn part1_state_generator(s: &State) -> Vec<Direction> {
     match s.direction {
         Direction::Start => vec![
@@ -146,60 +111,62 @@ fn traverse(
     grid: &HashMap<(i32, i32), u32>,
     generator: impl Fn(&State) -> Vec<Direction>,
 ) -> Option<u32> {
-    let mut states = BinaryHeap::new();
     let destination = (
         *grid.keys().map(|(x, _)| x).max().unwrap(),
         *grid.keys().map(|(_, y)| y).max().unwrap(),
     );
-    states.push(StateOrd(State {
+    let start = State {
         x: 0,
         y: 0,
-        distance: 0,
         direction: Direction::Start,
-        destination,
-    }));
-    let mut visited: HashSet<(i32, i32, Direction)> = HashSet::default();
-    while let Some(StateOrd(
-        state @ State {
-            x,
-            y,
-            distance,
-            direction,
-            ..
-        },
-    )) = states.pop()
-    {
-        if visited.contains(&(x, y, direction)) {
-            continue;
-        }
-        if (x, y) == destination {
-            return Some(distance);
-        }
-        let next_directions = generator(&state);
-        for next_direction in next_directions.into_iter() {
-            let new_x = match next_direction {
+    };
+    let neighbours = |state: &State| {
+        let State { x, y, .. } = *state;
+        generator(state).into_iter().filter_map(move |direction| {
+            let new_x = match direction {
                 Direction::Left(_) => x - 1,
                 Direction::Right(_) => x + 1,
                 _ => x,
             };
-            let new_y = match next_direction {
+            let new_y = match direction {
                 Direction::Up(_) => y - 1,
                 Direction::Down(_) => y + 1,
                 _ => y,
             };
-            if let Some(value) = grid.get(&(new_x, new_y)) {
-                states.push(StateOrd(State {
-                    x: new_x,
-                    y: new_y,
-                    distance: distance + value,
-                    direction: next_direction,
-                    destination,
-                }));
-            }
-        }
-        visited.insert((x, y, direction));
+            let value = grid.get(&(new_x, new_y))?;
+            let state = State {
+                x: new_x,
+                y: new_y,
+                direction,
+            };
+            Some((state, *value))
+        })
+    };
+    let heuristic = |state: &State| {
+        (state.x - destination.0).unsigned_abs() + (state.y - destination.1).unsigned_abs()
+    };
+    astar(start, neighbours, heuristic, |state| {
+        (state.x, state.y) == destination
+    })
+    .map(|path| path.cost)
+}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input<'a> = HashMap<(i32, i32), u32>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(parse_grid(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        traverse(input, part1_state_generator).unwrap()
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        traverse(input, part2_state_generator).unwrap()
     }
-    None
 }
 
 fn main() {
@@ -210,7 +177,6 @@ fn main() {
         "Part 1: {}",
         traverse(&grid, part1_state_generator).unwrap()
     );
-
     println!(
         "Part 2: {}",
         traverse(&grid, part2_state_generator).unwrap()
@@ -221,25 +187,6 @@ fn main() {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_state_ordering() {
-        let state1 = StateOrd(State {
-            x: 0,
-            y: 0,
-            distance: 0,
-            direction: Direction::Left(0),
-            destination: (1, 1),
-        });
-        let state2 = StateOrd(State {
-            x: 0,
-            y: 0,
-            distance: 1,
-            direction: Direction::Left(0),
-            destination: (1, 1),
-        });
-        assert!(state2 < state1);
-    }
-
     #[test]
     fn test_example() {
         let input = include_str!("../../input/day17-example");
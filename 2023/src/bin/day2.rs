@@ -1,15 +1,18 @@
-use std::{fs::read, time::Instant};
+use std::{fmt::Display, io::BufRead, time::Instant};
 
+use advent_of_code::{
+    input,
+    parse::{from_nom, or_exit, parse_lines, tag, IResult},
+    solution::{Solution, Streaming},
+};
 use clap::Parser;
 use nom::{
     branch::alt,
-    bytes::complete::tag,
     character::complete::{digit1, multispace0},
     combinator::{map, map_res},
     error::ParseError,
     multi::separated_list1,
     sequence::{delimited, pair, separated_pair},
-    IResult,
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -34,7 +37,7 @@ struct Reveal {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct Game {
+pub struct Game {
     id: u32,
     reveals: Vec<Reveal>,
 }
@@ -48,9 +51,9 @@ enum Color {
 
 fn ws<'a, F: 'a, O, E: ParseError<&'a str>>(
     inner: F,
-) -> impl FnMut(&'a str) -> IResult<&'a str, O, E>
+) -> impl FnMut(&'a str) -> nom::IResult<&'a str, O, E>
 where
-    F: FnMut(&'a str) -> IResult<&'a str, O, E>,
+    F: FnMut(&'a str) -> nom::IResult<&'a str, O, E>,
 {
     delimited(multispace0, inner, multispace0)
 }
@@ -135,44 +138,91 @@ fn max_possible_cubes(game: &Game) -> MaxConstraint {
         })
 }
 
-fn min_sum_power(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|g| game(g).expect("could not parse").1)
-        .map(interpret_game)
-        .fold(0, |acc, g| {
-            let MaxConstraint { red, blue, green } = max_possible_cubes(&g);
-            acc + (red * blue * green)
-        })
+fn parse_game(line: &str) -> Result<Game, advent_of_code::parse::ParseError> {
+    from_nom(line, game(line)).map(interpret_game)
 }
 
-fn id_sum(input: &str, constraint: &MaxConstraint) -> u32 {
-    input
-        .lines()
-        .map(|g| game(g).expect("could not parse").1)
-        .map(interpret_game)
-        .filter(|g| possible_game(g, constraint))
-        .fold(0, |acc, g| acc + g.id)
+fn games(
+    input: impl BufRead,
+) -> impl Iterator<Item = Result<Game, advent_of_code::parse::ParseError>> {
+    parse_lines(input, parse_game)
 }
 
-fn main() {
-    let args = Cli::parse();
-    let start = Instant::now();
-    let input = read(args.path.as_path()).unwrap();
+// both parts in one pass, so the games never have to be held in memory
+fn totals(games: impl Iterator<Item = Game>) -> (u32, u32) {
     let constraint = MaxConstraint {
         red: 12,
         green: 13,
         blue: 14,
     };
+    games.fold((0, 0), |(ids, powers), g| {
+        let id = if possible_game(&g, &constraint) {
+            g.id
+        } else {
+            0
+        };
+        (ids + id, powers + power(&g))
+    })
+}
 
-    println!(
-        "Part 1: {}",
-        id_sum(std::str::from_utf8(&input).unwrap(), &constraint)
-    );
-    println!(
-        "Part 2: {}",
-        min_sum_power(std::str::from_utf8(&input).unwrap())
-    );
+fn power(game: &Game) -> u32 {
+    let MaxConstraint { red, blue, green } = max_possible_cubes(game);
+    red * blue * green
+}
+
+fn min_sum_power<'a>(games: impl IntoIterator<Item = &'a Game>) -> u32 {
+    games.into_iter().fold(0, |acc, g| acc + power(g))
+}
+
+fn id_sum<'a>(games: impl IntoIterator<Item = &'a Game>, constraint: &MaxConstraint) -> u32 {
+    games
+        .into_iter()
+        .filter(|g| possible_game(g, constraint))
+        .fold(0, |acc, g| acc + g.id)
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<Game>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, advent_of_code::parse::ParseError> {
+        games(input.as_bytes()).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let constraint = MaxConstraint {
+            red: 12,
+            green: 13,
+            blue: 14,
+        };
+        id_sum(input, &constraint)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        min_sum_power(input)
+    }
+}
+
+impl Streaming for Day2 {
+    type Line = Game;
+
+    fn line(line: &str) -> Result<Self::Line, advent_of_code::parse::ParseError> {
+        parse_game(line)
+    }
+
+    fn answers(games: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        totals(games)
+    }
+}
+
+fn main() {
+    let args = Cli::parse();
+    let start = Instant::now();
+    let games = games(input::open(args.path.as_path()).unwrap()).map(or_exit);
+    let (id_sum, min_sum_power) = totals(games);
+    println!("Part 1: {}", id_sum);
+    println!("Part 2: {}", min_sum_power);
 
     println!("Time elapsed: {:?}", start.elapsed());
 }
@@ -245,7 +295,7 @@ mod tests {
                        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
                        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
                        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        let id_sum = id_sum(example, &constraint);
+        let id_sum = id_sum(&Day2::parse(example).unwrap(), &constraint);
         assert_eq!(id_sum, 8)
     }
 
@@ -273,7 +323,7 @@ mod tests {
                        Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
                        Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
                        Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        let min_sum_power = min_sum_power(example);
+        let min_sum_power = min_sum_power(&Day2::parse(example).unwrap());
         assert_eq!(min_sum_power, 2286)
     }
 }
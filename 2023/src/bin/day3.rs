@@ -1,5 +1,6 @@
 use std::{collections::HashMap, fs::read, time::Instant};
 
+use advent_of_code::{parse::ParseError, solution::Solution};
 use clap::{command, Parser};
 
 #[derive(Parser, Debug, Clone)]
@@ -10,7 +11,7 @@ struct Cli {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Period,
     Symbol(char),
     Number(u32),
@@ -26,7 +27,7 @@ impl From<char> for Tile {
     }
 }
 
-struct Schematic {
+pub struct Schematic {
     tiles: Vec<Tile>,
     width: i32,
     height: i32,
@@ -159,6 +160,24 @@ fn get_gear_ratio(schematic: &Schematic) -> u32 {
         .sum()
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Schematic;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(Schematic::new(input).unwrap())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        sum_of_part_numbers(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        get_gear_ratio(input)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let start = Instant::now();
@@ -1,13 +1,22 @@
-use std::{collections::HashMap, convert::TryInto, fs::read, time::Instant};
+use std::{
+    collections::{HashMap, VecDeque},
+    convert::TryInto,
+    fmt::Display,
+    io::BufRead,
+    time::Instant,
+};
 
+use advent_of_code::{
+    input,
+    parse::{from_nom, or_exit, parse_lines, tag, IResult, ParseError},
+    solution::{Solution, Streaming},
+};
 use clap::{command, Parser};
 use nom::{
-    bytes::complete::tag,
     character::complete::{digit1, multispace0},
     combinator::map_res,
     multi::many1,
     sequence::{delimited, pair, separated_pair},
-    IResult,
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -48,12 +57,16 @@ fn calc_points(card: Card) -> u32 {
         .expect("cast failed")
 }
 
-fn part1(input: &str) -> u32 {
-    input
-        .lines()
-        .map(|line| card(line).unwrap().1)
-        .map(calc_points)
-        .sum()
+fn parse_card(line: &str) -> Result<Card, ParseError> {
+    from_nom(line, card(line))
+}
+
+fn cards(input: impl BufRead) -> Result<Vec<Card>, ParseError> {
+    parse_lines(input, parse_card).collect()
+}
+
+fn part1(cards: &[Card]) -> u32 {
+    cards.iter().cloned().map(calc_points).sum()
 }
 
 fn card_count(
@@ -85,8 +98,8 @@ fn card_count(
     count
 }
 
-fn part2(input: &str) -> u32 {
-    let cards: HashMap<_, _> = input.lines().map(|line| card(line).unwrap().1).collect();
+fn part2(cards: &[Card]) -> u32 {
+    let cards: HashMap<_, _> = cards.iter().cloned().collect();
     let mut mem = HashMap::default();
     cards
         .keys()
@@ -94,13 +107,59 @@ fn part2(input: &str) -> u32 {
         .sum()
 }
 
+/// Both parts a card at a time. Cards only win copies of the cards after them, so it's enough
+/// to keep the copies won for the next few.
+fn scratchcards(cards: impl Iterator<Item = Card>) -> (u32, u32) {
+    let mut copies: VecDeque<u32> = VecDeque::new();
+    cards.fold((0, 0), |(points, count), card| {
+        let held = 1 + copies.pop_front().unwrap_or(0);
+        let (_, (winning, numbers)) = &card;
+        let wins = numbers.iter().filter(|n| winning.contains(n)).count();
+        if copies.len() < wins {
+            copies.resize(wins, 0);
+        }
+        copies.iter_mut().take(wins).for_each(|c| *c += held);
+        (points + calc_points(card), count + held)
+    })
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = Vec<Card>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        cards(input.as_bytes())
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day4 {
+    type Line = Card;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        parse_card(line)
+    }
+
+    fn answers(cards: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        scratchcards(cards)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let start = Instant::now();
-    let f = read(args.path.as_path()).unwrap();
-    let input = std::str::from_utf8(&f).unwrap();
-    println!("Part 1: {}", part1(input));
-    println!("Part 2: {}", part2(input));
+    let cards = parse_lines(input::open(args.path.as_path()).unwrap(), parse_card);
+    let (points, count) = scratchcards(cards.map(or_exit));
+    println!("Part 1: {}", points);
+    println!("Part 2: {}", count);
     println!("Time elapsed: {:?}", start.elapsed());
 }
 
@@ -140,7 +199,7 @@ mod tests {
                        Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
                        Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
                        Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
-        assert_eq!(part1(example), 13);
+        assert_eq!(part1(&cards(example.as_bytes()).unwrap()), 13);
     }
 
     #[test]
@@ -155,4 +214,16 @@ mod tests {
         let mut mem = HashMap::default();
         assert_eq!(card_count(&cards, &mut mem, 1), 15);
     }
+
+    #[test]
+    fn test_scratchcards() {
+        let example = "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
+                       Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
+                       Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
+                       Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83
+                       Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
+                       Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11";
+        let cards = cards(example.as_bytes()).unwrap();
+        assert_eq!(scratchcards(cards.into_iter()), (13, 30));
+    }
 }
@@ -1,15 +1,18 @@
 use std::{fs::read, time::Instant};
 
+use advent_of_code::{
+    interval::IntervalSet,
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
 use clap::Parser;
 use nom::{
-    bytes::complete::{is_not, tag},
+    bytes::complete::is_not,
     character::complete::{digit1, line_ending, multispace0, newline},
     combinator::map_res,
     multi::{self, many0, many1},
     sequence::{delimited, pair, terminated, tuple},
-    IResult,
 };
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
 type Instruction = (i64, i64, i64);
 type Almanac<'a> = (Vec<i64>, Vec<(&'a str, Vec<Instruction>)>);
@@ -19,6 +22,10 @@ type Almanac<'a> = (Vec<i64>, Vec<(&'a str, Vec<Instruction>)>);
 struct Cli {
     #[arg(long, short)]
     path: std::path::PathBuf,
+
+    /// Also print the seed ranges that end up in the location range START LEN
+    #[arg(long, num_args = 2, value_names = ["START", "LEN"])]
+    locations: Option<Vec<i64>>,
 }
 
 fn number(input: &str) -> IResult<&str, i64> {
@@ -45,7 +52,7 @@ fn seeds(input: &str) -> IResult<&str, Vec<i64>> {
     delimited(tag("seeds:"), many1(number), many0(newline))(input)
 }
 
-fn almanac(input: &str) -> IResult<&str, Almanac> {
+fn almanac(input: &str) -> IResult<&str, Almanac<'_>> {
     pair(seeds, maps)(input)
 }
 
@@ -71,6 +78,54 @@ fn apply_all_transforms(seed: i64, list_instructions: &[(&str, Vec<Instruction>)
         .fold(seed, |s, (_, ins)| transforms(s, ins))
 }
 
+/// Pushes whole ranges through one map, splitting them where the instructions start and end.
+/// Like `transforms`, the first instruction covering a value wins.
+fn forward(ranges: &IntervalSet<i64>, instructions: &[Instruction]) -> IntervalSet<i64> {
+    let mut rest = ranges.clone();
+    let mut mapped = IntervalSet::new();
+    for &(destination, source, range) in instructions {
+        let covered = IntervalSet::from(source..source + range);
+        mapped = mapped.union(&rest.intersection(&covered).shift(destination - source));
+        rest = rest.difference(&covered);
+    }
+    mapped.union(&rest)
+}
+
+/// Every value that `forward` would send into `ranges`.
+fn inverse(ranges: &IntervalSet<i64>, instructions: &[Instruction]) -> IntervalSet<i64> {
+    let mut covered = IntervalSet::new();
+    let mut preimage = IntervalSet::new();
+    for &(destination, source, range) in instructions {
+        let from = ranges
+            .intersection(&IntervalSet::from(destination..destination + range))
+            .shift(source - destination)
+            .difference(&covered);
+        preimage = preimage.union(&from);
+        covered.insert(source..source + range);
+    }
+    preimage.union(&ranges.difference(&covered))
+}
+
+/// The seeds read as start and length pairs, which `parse` checks they come in.
+fn seed_ranges(seeds: &[i64]) -> IntervalSet<i64> {
+    seeds
+        .chunks_exact(2)
+        .map(|pair| pair[0]..pair[0] + pair[1])
+        .collect()
+}
+
+fn locations(seeds: &IntervalSet<i64>, maps: &[(&str, Vec<Instruction>)]) -> IntervalSet<i64> {
+    maps.iter()
+        .fold(seeds.clone(), |r, (_, ins)| forward(&r, ins))
+}
+
+/// The seeds, out of all possible ones, that end up somewhere in `locations`.
+fn seeds_for(locations: &IntervalSet<i64>, maps: &[(&str, Vec<Instruction>)]) -> IntervalSet<i64> {
+    maps.iter()
+        .rev()
+        .fold(locations.clone(), |r, (_, ins)| inverse(&r, ins))
+}
+
 fn part1((seeds, maps): &Almanac) -> i64 {
     seeds
         .iter()
@@ -78,20 +133,34 @@ fn part1((seeds, maps): &Almanac) -> i64 {
         .fold(i64::MAX, i64::min)
 }
 
-fn part2((seed_pairs_seq, maps): &Almanac) -> i64 {
-    let seed_pairs: Vec<(i64, i64)> = seed_pairs_seq
-        .chunks(2)
-        .map(|pair| match pair {
-            [a, b] => (*a, *b),
-            _ => panic!("seed not paired"),
-        })
-        .collect();
-
-    seed_pairs
-        .par_iter()
-        .flat_map(|(a, b)| (*a..*a + *b))
-        .map(|original_seed| apply_all_transforms(original_seed, maps))
-        .reduce(|| i64::MAX, i64::min)
+fn part2((seeds, maps): &Almanac) -> i64 {
+    locations(&seed_ranges(seeds), maps)
+        .min()
+        .expect("no seeds")
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input<'a> = Almanac<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let almanac = from_nom(input, almanac(input))?;
+        if almanac.0.len() % 2 != 0 {
+            let seeds = input.lines().next().unwrap_or_default().trim_end();
+            let last = seeds.rsplit(' ').next().unwrap_or_default();
+            return Err(ParseError::new(input, last, "a length after the last seed"));
+        }
+        Ok(almanac)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
 }
 
 fn main() {
@@ -99,9 +168,21 @@ fn main() {
     let start = Instant::now();
     let f = read(args.path.as_path()).unwrap();
     let input = std::str::from_utf8(&f).unwrap();
-    let input = almanac(input).unwrap().1;
+    let input = or_exit(Day5::parse(input));
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
+    if let Some(range) = args.locations {
+        let (seeds, maps) = &input;
+        let wanted = seeds_for(&IntervalSet::from(range[0]..range[0] + range[1]), maps);
+        let planted = seed_ranges(seeds);
+        let reached = planted.intersection(&wanted);
+        println!("Seeds for locations: {}", wanted);
+        println!(
+            "Planted seeds for locations: {} ({} seeds)",
+            reached,
+            reached.len()
+        );
+    }
     println!("Time elapsed: {:?}", start.elapsed());
 }
 
@@ -334,4 +415,45 @@ mod tests {
         assert_eq!(transform(53, &(42, 0, 7)), None);
         assert_eq!(transform(53, &(57, 7, 4)), None);
     }
+
+    #[test]
+    fn test_unpaired_seeds() {
+        let input = include_str!("../../input/day5-example").replacen(" 13", "", 1);
+        assert_eq!(
+            Day5::parse(&input).unwrap_err().to_string(),
+            "input line 1, column 14: expected a length after the last seed, got 55"
+        );
+    }
+
+    fn example() -> Almanac<'static> {
+        almanac(include_str!("../../input/day5-example")).unwrap().1
+    }
+
+    #[test]
+    fn test_forward() {
+        let maps = vec![(50, 98, 2), (52, 50, 48)];
+        let seeds = vec![79..93, 55..68, 90..110].into_iter().collect();
+        assert_eq!(
+            forward(&seeds, &maps),
+            vec![50..52, 57..70, 81..110].into_iter().collect()
+        );
+    }
+
+    #[test]
+    fn test_seeds_for() {
+        let (seeds, maps) = example();
+        let wanted = seeds_for(&IntervalSet::from(46..47), &maps);
+        assert_eq!(
+            seed_ranges(&seeds).intersection(&wanted),
+            IntervalSet::from(82..83)
+        );
+        for start in 0..100 {
+            let target = IntervalSet::from(start..start + 7);
+            let wanted = seeds_for(&target, &maps);
+            for seed in 0..120 {
+                let location = apply_all_transforms(seed, &maps);
+                assert_eq!(wanted.contains(seed), target.contains(location));
+            }
+        }
+    }
 }
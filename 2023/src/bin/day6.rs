@@ -1,4 +1,22 @@
-use std::time::Instant;
+use std::{fs::read_to_string, time::Instant};
+
+use advent_of_code::{
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
+use clap::Parser;
+use nom::{
+    character::complete::{digit1, line_ending, space1},
+    multi::many1,
+    sequence::{preceded, separated_pair},
+};
+
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    #[arg(long, short)]
+    path: std::path::PathBuf,
+}
 
 // Solved via maths using quadratic formula
 
@@ -7,18 +25,95 @@ fn bounds(t: f64, d: f64) -> (f64, f64) {
     ((t - q) / 2.0, (t + q) / 2.0)
 }
 
+// strictly between, matching the record distance doesn't win the race
 fn integer_between_bounds(t: f64, d: f64) -> i64 {
     let (n, m) = bounds(t, d);
-    m.floor() as i64 - n.ceil() as i64 + 1
+    m.ceil() as i64 - n.floor() as i64 - 1
+}
+
+// The digits of each column are kept as text, as the two parts read them differently
+type Races<'a> = (Vec<&'a str>, Vec<&'a str>);
+
+fn races(input: &str) -> IResult<&str, Races<'_>> {
+    separated_pair(
+        preceded(tag("Time:"), many1(preceded(space1, digit1))),
+        line_ending,
+        preceded(tag("Distance:"), many1(preceded(space1, digit1))),
+    )(input)
+}
+
+fn parse(input: &str) -> Result<Races<'_>, ParseError> {
+    let (times, distances) = from_nom(input, races(input))?;
+    if times.len() != distances.len() {
+        let line = input.lines().nth(1).unwrap_or_default();
+        let expected = format!("{} distances", times.len());
+        return Err(ParseError::new(input, line, expected));
+    }
+    Ok((times, distances))
+}
+
+fn number(digits: &str) -> f64 {
+    digits.parse().expect("digits are a number")
+}
+
+fn part1((times, distances): &Races) -> i64 {
+    times
+        .iter()
+        .zip(distances)
+        .map(|(t, d)| integer_between_bounds(number(t), number(d)))
+        .product()
+}
+
+// part 2 reads each line as a single number with the spaces removed
+fn part2((times, distances): &Races) -> i64 {
+    integer_between_bounds(number(&times.concat()), number(&distances.concat()))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = Races<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
 }
 
 fn main() {
+    let args = Cli::parse();
+    let input = read_to_string(args.path).unwrap();
+    let input = or_exit(parse(&input));
     let start = Instant::now();
-    let g = integer_between_bounds;
-    println!(
-        "Part 1: {}",
-        g(41.0, 214.0) * g(96.0, 1789.0) * g(88.0, 1127.0) * g(94.0, 1055.0)
-    );
-    println!("Part 2: {}", g(41968894.0, 214178911271055.0));
+    println!("Part 1: {}", part1(&input));
+    println!("Part 2: {}", part2(&input));
     println!("Time elapsed: {:?}", start.elapsed());
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let input = parse("Time:      7  15   30\nDistance:  9  40  200").unwrap();
+        assert_eq!(part1(&input), 288);
+        assert_eq!(part2(&input), 71503);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = parse("Time: 7 15\nDistance: 9").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "input line 2, column 1: expected 2 distances, got Distance: 9"
+        );
+    }
+}
@@ -1,12 +1,16 @@
-use std::{cmp::max, collections::BTreeMap, convert::TryInto, fs::read, time::Instant};
+use std::{cmp::max, collections::BTreeMap, convert::TryInto, fmt::Display, time::Instant};
 
+use advent_of_code::{
+    input,
+    parse::{from_nom, or_exit, parse_lines, IResult, ParseError},
+    solution::{Solution, Streaming},
+};
 use clap::Parser;
 use nom::{
     character::complete::{anychar, multispace1},
     combinator::{map_opt, map_res},
     multi::{many0, many1},
     sequence::{separated_pair, terminated, tuple},
-    IResult,
 };
 
 #[derive(Parser, Debug, Clone)]
@@ -35,15 +39,16 @@ fn hand(input: &str) -> IResult<&str, Hand> {
     map_res(tuple((card, card, card, card, card)), |f| f.try_into())(input)
 }
 
+fn hand_bid(input: &str) -> IResult<&str, (Hand, u32)> {
+    separated_pair(hand, multispace1, bid)(input)
+}
+
 fn camel_card(input: &str) -> IResult<&str, Vec<(Hand, u32)>> {
-    many1(terminated(
-        separated_pair(hand, multispace1, bid),
-        many0(multispace1),
-    ))(input)
+    many1(terminated(hand_bid, many0(multispace1)))(input)
 }
 
 #[derive(PartialEq, PartialOrd, Eq, Ord, Debug, Copy, Clone)]
-enum Card {
+pub enum Card {
     Number(u32),
     T,
     J,
@@ -151,14 +156,45 @@ fn joker_total_winnings(mut hands: Vec<(Hand, u32)>) -> u32 {
         .fold(0, |acc, (i, (_, bid))| acc + (i as u32 + 1) * bid)
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = Vec<(Hand, u32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, camel_card(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        total_winnings(input.clone())
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        joker_total_winnings(input.clone())
+    }
+}
+
+// the hands have to be ranked against each other, so only the text of the input is streamed
+impl Streaming for Day7 {
+    type Line = (Hand, u32);
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        from_nom(line, hand_bid(line))
+    }
+
+    fn answers(hands: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let hands: Vec<_> = hands.collect();
+        (total_winnings(hands.clone()), joker_total_winnings(hands))
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let start = Instant::now();
-    let input = read(args.path.as_path()).unwrap();
-    let input = std::str::from_utf8(&input).unwrap();
-    let (_, input) = camel_card(input).unwrap();
-    println!("Part 1: {}", total_winnings(input.clone()));
-    println!("Part 2: {}", joker_total_winnings(input.clone()));
+    let input = input::open(args.path.as_path()).unwrap();
+    let hands: Vec<_> = parse_lines(input, Day7::line).map(or_exit).collect();
+    println!("Part 1: {}", total_winnings(hands.clone()));
+    println!("Part 2: {}", joker_total_winnings(hands));
     println!("Time elapsed: {:?}", start.elapsed());
 }
 
@@ -1,13 +1,17 @@
+use advent_of_code::{
+    cycle,
+    numtheory::crt,
+    parse::{from_nom, or_exit, tag, IResult, ParseError},
+    solution::Solution,
+};
 use clap::Parser;
 use nom::branch::alt;
-use nom::bytes::complete::tag;
 use nom::character::complete::{alphanumeric1, multispace0};
 use nom::combinator::{map, value};
 use nom::multi::many1;
 use nom::sequence::{separated_pair, terminated, tuple};
-use nom::IResult;
-use std::collections::HashMap;
-use std::fs::read;
+use std::collections::{BTreeSet, HashMap};
+use std::fs::read_to_string;
 use std::time::Instant;
 
 #[derive(Parser, Debug, Clone)]
@@ -18,43 +22,18 @@ struct Cli {
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone, Hash)]
-enum Instruction {
+pub enum Instruction {
     Left,
     Right,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
-struct Node<'a> {
+pub struct Node<'a> {
     name: &'a str,
     left: &'a str,
     right: &'a str,
 }
 
-#[derive(Eq, PartialEq, Hash, Debug, Clone)]
-struct InstructionCycle<'a> {
-    instructions: &'a [Instruction],
-    pub step: usize,
-}
-
-impl<'a> InstructionCycle<'a> {
-    fn new(instructions: &'a [Instruction]) -> Self {
-        Self {
-            instructions,
-            step: 0,
-        }
-    }
-}
-
-impl<'a> Iterator for InstructionCycle<'a> {
-    type Item = Instruction;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        let instruction = self.instructions.get(self.step).unwrap();
-        self.step = (self.step + 1) % self.instructions.len();
-        Some(*instruction)
-    }
-}
-
 fn instructions(input: &str) -> IResult<&str, Vec<Instruction>> {
     many1(alt((
         value(Instruction::Left, nom::character::complete::char('L')),
@@ -106,72 +85,135 @@ fn traverse((instructions, nodes): &(Vec<Instruction>, Vec<Node>)) -> u64 {
     steps
 }
 
-/// Find cycle time of each starting position then find the lowest common multiple of the cycle time
-/// from each starting position.
-fn ghost_traverse((instructions, nodes): &(Vec<Instruction>, Vec<Node>)) -> u64 {
-    let map = nodes
-        .iter()
-        .map(|node| (node.name, (node.left, node.right)))
-        .collect::<std::collections::HashMap<_, _>>();
-    let ins_state = InstructionCycle::new(instructions);
-    let mut steps: u64 = 0;
-    let mut current_nodes = nodes
-        .iter()
-        .filter_map(|node| {
-            if node.name.ends_with('A') {
-                Some(node.name)
-            } else {
-                None
-            }
-        })
-        .collect::<Vec<&str>>();
-    let mut visited: HashMap<(u64, &str), u64> = HashMap::new();
-    let mut cycle_times = vec![None; current_nodes.len()];
-
-    for instruction in ins_state {
-        for i in 0..current_nodes.len() {
-            if cycle_times[i].is_some() {
-                continue;
-            }
-
-            let current = &mut current_nodes[i];
-            let cycle_time = &mut cycle_times[i];
-            let state_key = (steps % instructions.len() as u64, *current);
+/// Where one ghost stands on a Z node. After `offset` steps its walk repeats every `length` steps.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Walk {
+    offset: u64,
+    length: u64,
+    /// Steps on a Z node before the walk repeats; each happens once.
+    before_cycle: Vec<u64>,
+    /// Steps on a Z node in the first repeat, between `offset` and `offset + length`.
+    in_cycle: Vec<u64>,
+}
 
+impl Walk {
+    fn new<'a>(
+        start: &'a str,
+        instructions: &[Instruction],
+        map: &HashMap<&'a str, (&'a str, &'a str)>,
+    ) -> Self {
+        let (cycle, states) = cycle::find((0, start), |&(i, current)| {
             let (left, right) = map.get(current).expect("Node not found");
-            *current = match instruction {
+            let next = match instructions[i] {
                 Instruction::Left => left,
                 Instruction::Right => right,
             };
-            if let Some(last_step) = visited.get(&state_key) {
-                *cycle_time = Some(steps - last_step);
-            } else {
-                visited.insert(state_key, steps);
-            }
+            ((i + 1) % instructions.len(), *next)
+        });
+        let (before_cycle, in_cycle) = states[..cycle.start + cycle.length]
+            .iter()
+            .enumerate()
+            .filter(|(_, (_, node))| node.ends_with('Z'))
+            .map(|(step, _)| step as u64)
+            .partition(|&step| step < cycle.start as u64);
+        Walk {
+            offset: cycle.start as u64,
+            length: cycle.length as u64,
+            before_cycle,
+            in_cycle,
         }
-        steps += 1;
-        if cycle_times.iter().all(|x| x.is_some()) {
-            break;
+    }
+
+    fn on_z(&self, step: u64) -> bool {
+        if step < self.offset {
+            self.before_cycle.contains(&step)
+        } else {
+            let step = self.offset + (step - self.offset) % self.length;
+            self.in_cycle.contains(&step)
         }
     }
+}
+
+/// The first step at which every walk is on a Z node, if there is one.
+fn align(walks: &[Walk]) -> Option<u64> {
+    // before every walk repeats, just look
+    let repeating = walks.iter().map(|w| w.offset).max().unwrap_or(0);
+    if let Some(step) = (0..repeating).find(|&step| walks.iter().all(|w| w.on_z(step))) {
+        return Some(step);
+    }
+    // after that, each walk picks one of its Z steps and the picks have to agree. The
+    // systems so far share one modulus, so each residue is kept once.
+    let systems = walks
+        .iter()
+        .fold(BTreeSet::from([(0, 1)]), |systems, walk| {
+            systems
+                .iter()
+                .flat_map(|&system| {
+                    walk.in_cycle
+                        .iter()
+                        .filter_map(move |&z| crt(system, (z as i128, walk.length as i128)))
+                })
+                .collect()
+        });
+    systems
+        .into_iter()
+        .map(|(a, m)| {
+            let from = repeating as i128;
+            if a >= from {
+                a
+            } else {
+                a + (from - a + m - 1) / m * m
+            }
+        })
+        .min()
+        .map(|step| step as u64)
+}
+
+fn ghost_walks<'a>((instructions, nodes): &(Vec<Instruction>, Vec<Node<'a>>)) -> Vec<Walk> {
+    let map = nodes
+        .iter()
+        .map(|node| (node.name, (node.left, node.right)))
+        .collect::<HashMap<_, _>>();
+    nodes
+        .iter()
+        .filter(|node| node.name.ends_with('A'))
+        .map(|node| Walk::new(node.name, instructions, &map))
+        .collect()
+}
+
+fn ghost_traverse(input: &(Vec<Instruction>, Vec<Node>)) -> Option<u64> {
+    align(&ghost_walks(input))
+}
+
+fn show(steps: Option<u64>) -> String {
+    steps.map_or_else(|| "never".to_string(), |steps| steps.to_string())
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input<'a> = (Vec<Instruction>, Vec<Node<'a>>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, maps(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        traverse(input)
+    }
 
-    // find the lowest common multiple of the cycle times
-    let cycle_times = cycle_times.iter().map(|x| x.unwrap()).collect::<Vec<u64>>();
-    let mut lcm = cycle_times[0];
-    for cycle_time in cycle_times.iter().skip(1) {
-        lcm = lcm * cycle_time / gcd::binary_u64(lcm, *cycle_time);
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        show(ghost_traverse(input))
     }
-    lcm
 }
 
 fn main() {
     let args = Cli::parse();
     let start = Instant::now();
-    let input = read(args.path.as_path()).unwrap();
-    let input = std::str::from_utf8(&input).unwrap();
-    let (_, input) = maps(input).unwrap();
-    println!("Part 1: {}", traverse(&input));
-    println!("Part 2: {}", ghost_traverse(&input));
+    let input = read_to_string(args.path.as_path()).unwrap();
+    let maps = or_exit(Day8::parse(&input));
+    println!("Part 1: {}", traverse(&maps));
+    println!("Part 2: {}", show(ghost_traverse(&maps)));
 
     println!("Time elapsed: {:?}", start.elapsed());
 }
@@ -248,17 +290,85 @@ mod tests {
         assert_eq!(traverse(&input), 6);
     }
 
-    // #[test]
-    // fn test_equivalence() {
-    //     let input = include_str!("../../input/day8-example");
-    //     let (_, input) = maps(input).unwrap();
-    //     assert_eq!(traverse(&input), ghost_traverse(&input));
-    // }
-
     #[test]
     fn test_ghost_example() {
         let input = include_str!("../../input/day8-example-ghost");
         let (_, input) = maps(input).unwrap();
-        assert_eq!(ghost_traverse(&input), 6);
+        assert_eq!(ghost_traverse(&input), Some(6));
+        assert_eq!(
+            ghost_walks(&input),
+            vec![
+                Walk {
+                    offset: 1,
+                    length: 2,
+                    before_cycle: vec![],
+                    in_cycle: vec![2],
+                },
+                Walk {
+                    offset: 1,
+                    length: 6,
+                    before_cycle: vec![],
+                    in_cycle: vec![3, 6],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ghost_alignment() {
+        // on Z every other step, but one ghost at even steps and the other at odd ones
+        let input = "L
+
+11A = (11B, XXX)
+11B = (11Z, XXX)
+11Z = (11B, XXX)
+22A = (22Z, XXX)
+22Z = (22A, XXX)
+XXX = (XXX, XXX)";
+        let (_, input) = maps(input).unwrap();
+        assert_eq!(ghost_traverse(&input), None);
+
+        // a Z node before the walk repeats, never visited again
+        let input = "L
+
+11A = (11Z, XXX)
+11Z = (11B, XXX)
+11B = (11B, XXX)
+22A = (22Z, XXX)
+22Z = (22A, XXX)
+XXX = (XXX, XXX)";
+        let (_, input) = maps(input).unwrap();
+        assert_eq!(ghost_traverse(&input), Some(1));
+
+        // cycles of 4 and 6 steps that line up at 9 rather than at their lcm
+        let input = "L
+
+11A = (11P, XXX)
+11P = (11B, XXX)
+11B = (11C, XXX)
+11C = (11D, XXX)
+11D = (11Z, XXX)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, XXX)
+22C = (22Z, XXX)
+22Z = (22D, XXX)
+22D = (22E, XXX)
+22E = (22A, XXX)
+XXX = (XXX, XXX)";
+        let (_, input) = maps(input).unwrap();
+        assert_eq!(ghost_traverse(&input), Some(9));
+    }
+
+    #[test]
+    fn test_align_many() {
+        // 3^16 ways to pick the Z steps, but only 3 residues modulo 4
+        let walk = Walk {
+            offset: 0,
+            length: 4,
+            before_cycle: vec![],
+            in_cycle: vec![1, 2, 3],
+        };
+        assert_eq!(align(&vec![walk; 16]), Some(1));
     }
 }
@@ -1,11 +1,16 @@
+use advent_of_code::{
+    input,
+    parse::{from_nom, or_exit, parse_lines, IResult, ParseError},
+    solution::{Solution, Streaming},
+};
 use clap::Parser;
 use nom::{
     character::complete::{newline, space0},
     combinator::opt,
     multi::many1,
     sequence::terminated,
-    IResult,
 };
+use std::fmt::Display;
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
@@ -22,6 +27,10 @@ fn sequences(input: &str) -> IResult<&str, Vec<Vec<i32>>> {
     many1(terminated(sequence, opt(newline)))(input)
 }
 
+fn history(line: &str) -> Result<Vec<i32>, ParseError> {
+    from_nom(line, sequence(line))
+}
+
 fn lower_increment(input: &[i32]) -> i32 {
     assert_ne!(input.len(), 0);
     if input.iter().all(|d| *d == 0) {
@@ -49,12 +58,53 @@ fn part2(input: &[Vec<i32>]) -> i32 {
     input.iter().map(|s| front_lower_increment(s)).sum()
 }
 
+// both extrapolations in one pass, a history at a time
+fn extrapolate(histories: impl Iterator<Item = Vec<i32>>) -> (i32, i32) {
+    histories.fold((0, 0), |(next, previous), history| {
+        (
+            next + lower_increment(&history),
+            previous + front_lower_increment(&history),
+        )
+    })
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input<'a> = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, sequences(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day9 {
+    type Line = Vec<i32>;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        history(line)
+    }
+
+    fn answers(histories: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        extrapolate(histories)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
-    let input = std::fs::read_to_string(args.path).unwrap();
-    let (_, sequences) = sequences(&input).unwrap();
-    println!("Part 1: {}", part1(&sequences));
-    println!("Part 2: {}", part2(&sequences));
+    let input = input::open(args.path.as_path()).unwrap();
+    let histories = parse_lines(input, history).map(or_exit);
+    let (next, previous) = extrapolate(histories);
+    println!("Part 1: {}", next);
+    println!("Part 2: {}", previous);
 }
 
 #[cfg(test)]
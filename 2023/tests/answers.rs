@@ -0,0 +1,17 @@
+use advent_of_code::answers;
+
+fn check(args: &[&str]) {
+    answers::check(env!("CARGO_BIN_EXE_aoc"), env!("CARGO_MANIFEST_DIR"), args);
+}
+
+#[test]
+fn examples() {
+    check(&["--examples"]);
+}
+
+// the real inputs take minutes to solve, run with `cargo test --release -- --ignored`
+#[test]
+#[ignore]
+fn real_inputs() {
+    check(&[]);
+}
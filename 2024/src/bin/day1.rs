@@ -1,37 +1,98 @@
+use advent_of_code::{
+    input,
+    parse::{IResult, ParseError, from_nom, or_exit, parse_lines},
+    solution::{Solution, Streaming},
+};
+use nom::{character::complete::space1, sequence::separated_pair};
 use std::env;
-use std::fs;
+use std::fmt::Display;
+use std::path::Path;
 
-fn main() {
-    let args = env::args().collect::<Vec<String>>();
-    let filename = args
-        .get(1)
-        .expect("Please provide a filename as an argument");
-    let contents = fs::read_to_string(filename).expect("Could not read file");
-
-    let mut v1: Vec<i32> = Vec::new();
-    let mut v2: Vec<i32> = Vec::new();
-    for line in contents.lines() {
-        let parts = line.split_whitespace().collect::<Vec<_>>();
-        let p = parts.as_slice();
-        match p {
-            [p1, p2] => {
-                v1.push(p1.parse().expect("Failed"));
-                v2.push(p2.parse().expect("Failed"));
-            }
-            x => println!("err parsing: {x:?}"),
-        }
+fn location_ids(line: &str) -> IResult<&str, (i32, i32)> {
+    separated_pair(
+        nom::character::complete::i32,
+        space1,
+        nom::character::complete::i32,
+    )(line)
+}
+
+// blank lines, like the one the saved input starts with, are skipped
+fn location_line(line: &str) -> Result<Option<(i32, i32)>, ParseError> {
+    if line.is_empty() {
+        Ok(None)
+    } else {
+        from_nom(line, location_ids(line)).map(Some)
     }
+}
 
+fn sorted_lists(pairs: impl Iterator<Item = (i32, i32)>) -> (Vec<i32>, Vec<i32>) {
+    let (mut v1, mut v2): (Vec<i32>, Vec<i32>) = pairs.unzip();
     v1.sort();
     v2.sort();
+    (v1, v2)
+}
 
-    let score: u32 = v1.iter().zip(v2.iter()).map(|(i, j)| i.abs_diff(*j)).sum();
-    println!("part1: {score}");
+fn parse(contents: &str) -> Result<(Vec<i32>, Vec<i32>), ParseError> {
+    let pairs = parse_lines(contents.as_bytes(), location_line)
+        .filter_map(Result::transpose)
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(sorted_lists(pairs.into_iter()))
+}
+
+fn part1((v1, v2): &(Vec<i32>, Vec<i32>)) -> u32 {
+    v1.iter().zip(v2.iter()).map(|(i, j)| i.abs_diff(*j)).sum()
+}
 
-    let score: i32 = v1
-        .iter()
+fn part2((v1, v2): &(Vec<i32>, Vec<i32>)) -> i32 {
+    v1.iter()
         .map(|x| x * (v2.iter().filter(|i| *i == x).count() as i32))
-        .sum();
+        .sum()
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = (Vec<i32>, Vec<i32>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+// both lists have to be sorted, so only the text of the input is streamed
+impl Streaming for Day1 {
+    type Line = Option<(i32, i32)>;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        location_line(line)
+    }
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let lists = sorted_lists(lines.flatten());
+        (part1(&lists), part2(&lists))
+    }
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<String>>();
+    let filename = args
+        .get(1)
+        .expect("Please provide a filename as an argument");
+    let contents = input::open(Path::new(filename)).expect("Could not read file");
+    let lines = parse_lines(contents, location_line).map(or_exit);
+    let contents = sorted_lists(lines.flatten());
+
+    let score = part1(&contents);
+    println!("part1: {score}");
 
+    let score = part2(&contents);
     println!("part2: {score}");
 }
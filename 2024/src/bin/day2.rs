@@ -1,9 +1,10 @@
-use std::{
-    cmp::Ordering,
-    fs::{File, Permissions, read_to_string},
-    io::BufRead,
-};
+use std::{cmp::Ordering, fmt::Display};
 
+use advent_of_code::{
+    input,
+    parse::{ParseError, or_exit, parse_lines},
+    solution::{Solution, Streaming},
+};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -70,29 +71,79 @@ fn permute(report: &[i32]) -> Vec<Vec<i32>> {
     permutations
 }
 
+fn report(line: &str) -> Result<Vec<i32>, ParseError> {
+    line.split(" ")
+        .map(|level| {
+            level
+                .parse()
+                .map_err(|_| ParseError::new(line, level, "a level"))
+        })
+        .collect()
+}
+
+fn is_tolerable(report: &[i32]) -> bool {
+    if is_safe(report) {
+        true
+    } else {
+        permute(report).iter().any(|x| is_safe(x))
+    }
+}
+
+/// Counts the safe and the tolerable reports in one pass, a report at a time.
+fn count(reports: impl Iterator<Item = Vec<i32>>) -> (usize, usize) {
+    reports.fold((0, 0), |(safe, tolerable), report| {
+        (
+            safe + usize::from(is_safe(&report)),
+            tolerable + usize::from(is_tolerable(&report)),
+        )
+    })
+}
+
+fn part1(reports: &[Vec<i32>]) -> usize {
+    reports.iter().filter(|report| is_safe(report)).count()
+}
+
+fn part2(reports: &[Vec<i32>]) -> usize {
+    reports.iter().filter(|report| is_tolerable(report)).count()
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), report).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day2 {
+    type Line = Vec<i32>;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        report(line)
+    }
+
+    fn answers(reports: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        count(reports)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
-    let content = read_to_string(args.path).expect("could not read file");
-    let reports: Result<Vec<Vec<i32>>, _> = content
-        .lines()
-        .map(|line| line.split(" ").map(str::parse).collect())
-        .collect();
-    let reports = reports.expect("Error parsing");
-
-    let count = reports.iter().filter(|report| is_safe(report)).count();
-    println!("{:?}", count);
-
-    let count = reports
-        .iter()
-        .filter(|report| {
-            if is_safe(report) {
-                true
-            } else {
-                permute(report).iter().any(|x| is_safe(x))
-            }
-        })
-        .count();
-    println!("{:?}", count);
+    let reports = input::open(&args.path).expect("could not read file");
+    // the reports are streamed, stopping at the first bad one
+    let (safe, tolerable) = count(parse_lines(reports, report).map(or_exit));
+    println!("{:?}", safe);
+    println!("{:?}", tolerable);
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use advent_of_code::{parse::ParseError, solution::Solution};
 use clap::Parser;
 use regex::{Match, Regex};
 use std::fs::read_to_string;
@@ -10,48 +11,36 @@ struct Cli {
 }
 
 #[derive(Debug)]
-enum Instructions {
+pub enum Instructions {
     Mul(u32, u32),
     Do,
     Dont,
 }
 
-fn part1(content: &str) -> u32 {
-    let re = Regex::new(r"mul\(([0-9]+),([0-9]+)\)").expect("Could not parse regex");
-    let mut sum: u32 = 0;
-    for m in re.captures_iter(content) {
-        if let Some((x, y)) = m
-            .get(1)
-            .map(|x| x.as_str())
-            .and_then(|x| x.parse::<u32>().ok())
-            .zip(
-                m.get(2)
-                    .map(|x| x.as_str())
-                    .and_then(|x| x.parse::<u32>().ok()),
-            )
-        {
-            sum += x * y;
-        }
-    }
-    sum
+// Anything that isn't an instruction is corrupted memory and skipped, so this can't fail
+fn instructions(content: &str) -> Vec<Instructions> {
+    let re = Regex::new(r"do\(\)|don't\(\)|mul\((\d+),(\d+)\)").expect("Could not parse regex");
+    re.captures_iter(content)
+        .filter_map(|m| match &m[0] {
+            "do()" => Some(Instructions::Do),
+            "don't()" => Some(Instructions::Dont),
+            // a number too big for a u32 isn't a valid mul
+            _ => Some(Instructions::Mul(m[1].parse().ok()?, m[2].parse().ok()?)),
+        })
+        .collect()
 }
 
-fn part2(content: &str) -> u32 {
-    let re = Regex::new(r"(do|don't|mul)\((?:(\d+),(\d+))?\)").expect("Could not parse regex");
-    let mut instructions = Vec::new();
-    for m in re.captures_iter(content) {
-        let ins = m.get(1).map(|x| x.as_str()).unwrap();
-        instructions.push(match ins {
-            "don't" => Instructions::Dont,
-            "do" => Instructions::Do,
-            "mul" => {
-                let x = m.get(2).and_then(|x| x.as_str().parse().ok()).unwrap();
-                let y = m.get(3).and_then(|x| x.as_str().parse().ok()).unwrap();
-                Instructions::Mul(x, y)
-            }
-            x => panic!("{}", x),
+fn part1(instructions: &[Instructions]) -> u32 {
+    instructions
+        .iter()
+        .map(|ins| match ins {
+            Instructions::Mul(x, y) => x * y,
+            _ => 0,
         })
-    }
+        .sum()
+}
+
+fn part2(instructions: &[Instructions]) -> u32 {
     instructions
         .iter()
         .fold((true, 0), |(state, a), ins| match ins {
@@ -62,9 +51,28 @@ fn part2(content: &str) -> u32 {
         .1
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Vec<Instructions>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        Ok(instructions(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let content = read_to_string(args.path).expect("could not read file");
+    let content = instructions(&content);
     let part1 = part1(&content);
 
     let part2 = part2(&content);
@@ -1,16 +1,13 @@
 use std::{env::current_dir, fs::read_to_string, path::PathBuf};
 
+use advent_of_code::{
+    grid::Grid,
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 use ahash::{HashSet, HashSetExt};
 use clap::Parser;
 use itertools::Itertools;
-use nom::{
-    IResult,
-    branch::alt,
-    character::complete::{self, newline},
-    combinator::{map, opt},
-    multi::many1,
-    sequence::terminated,
-};
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
@@ -19,45 +16,8 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-#[derive(Debug)]
-struct Grid {
-    letter: Vec<Vec<char>>,
-    height: i32,
-    width: i32,
-}
-
-impl Grid {
-    fn new(letter: Vec<Vec<char>>) -> Self {
-        let height = letter.len() as i32;
-        let width = letter[0].len() as i32;
-        Self {
-            letter,
-            width,
-            height,
-        }
-    }
-    fn get(&self, x: i32, y: i32) -> Option<char> {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.letter[y as usize][x as usize])
-    }
-}
-
-// parse a grid of characters
-fn grid(input: &str) -> IResult<&str, Grid> {
-    map(
-        many1(terminated(
-            many1(alt((
-                complete::char('X'),
-                complete::char('M'),
-                complete::char('A'),
-                complete::char('S'),
-            ))),
-            opt(newline),
-        )),
-        Grid::new,
-    )(input)
+fn grid(input: &str) -> Result<Grid<char>, ParseError> {
+    Grid::try_parse(input, |c| "XMAS".contains(c).then_some(c), "one of XMAS")
 }
 
 fn directions(word: &[char]) -> Vec<Vec<(i32, i32, char)>> {
@@ -106,7 +66,7 @@ fn mas_directions() -> Vec<Vec<(i32, i32, char)>> {
     result
 }
 
-fn solve(grid: &Grid, pattern: &[Vec<(i32, i32, char)>]) -> i32 {
+fn solve(grid: &Grid<char>, pattern: &[Vec<(i32, i32, char)>]) -> i32 {
     let mut count = 0;
     for y in 0..grid.height {
         for x in 0..grid.width {
@@ -125,12 +85,39 @@ fn solve(grid: &Grid, pattern: &[Vec<(i32, i32, char)>]) -> i32 {
     }
     count
 }
+
+fn part1(grid: &Grid<char>) -> i32 {
+    solve(grid, &directions(&['X', 'M', 'A', 'S']))
+}
+
+fn part2(grid: &Grid<char>) -> i32 {
+    solve(grid, &mas_directions())
+}
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = Grid<char>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        grid(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let content = read_to_string(args.path).expect("could not read file");
-    let (_, grid) = grid(&content).expect("parse error");
-    let part1 = solve(&grid, &directions(&['X', 'M', 'A', 'S']));
+    let content = or_exit(grid(&content));
+    let part1 = part1(&content);
     println!("{part1:?}");
-    let part2 = solve(&grid, &mas_directions());
+    let part2 = part2(&content);
     println!("{part2:?}");
 }
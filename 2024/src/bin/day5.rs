@@ -5,7 +5,10 @@ use std::{
     fs::read_to_string,
 };
 
-use anyhow::{Context, Error};
+use advent_of_code::{
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 use clap::Parser;
 use nom::Or;
 
@@ -16,28 +19,37 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-struct Rules(Vec<(u32, u32)>);
-struct Updates(Vec<Vec<u32>>);
+pub struct Rules(Vec<(u32, u32)>);
+pub struct Updates(Vec<Vec<u32>>);
 
-fn parse(input: &str) -> Result<(Rules, Updates), Error> {
-    let (first, second) = input.split_once("\n\n").context("Can't split")?;
+fn parse(input: &str) -> Result<(Rules, Updates), ParseError> {
+    let (first, second) = input.split_once("\n\n").ok_or_else(|| {
+        ParseError::new(input, &input[input.len()..], "a blank line after the rules")
+    })?;
     let first = first
         .lines()
         .map(|x| {
             x.split_once('|')
                 .and_then(|(x, y)| (x.parse().ok().zip(y.parse().ok())))
-                .unwrap()
+                .ok_or_else(|| ParseError::new(input, x, "a rule like 47|53"))
         })
-        .collect();
+        .collect::<Result<_, _>>()?;
     let second = second
         .lines()
-        .map(|x| x.split(',').map(|x| x.parse().unwrap()).collect())
-        .collect();
+        .map(|x| {
+            x.split(',')
+                .map(|x| {
+                    x.parse()
+                        .map_err(|_| ParseError::new(input, x, "a page number"))
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
     Ok((Rules(first), Updates(second)))
 }
 
 #[derive(Debug, PartialEq, Eq)]
-struct OrderingRules {
+pub struct OrderingRules {
     after: BTreeMap<u32, BTreeSet<u32>>,
 }
 
@@ -81,8 +93,6 @@ fn part2(rules: &OrderingRules, Updates(updates): &Updates) -> u32 {
     let mut result = 0;
     for update in updates {
         let mut update = update.clone();
-        println!("-----");
-        println!("{update:?}");
         let mut incorrect = false;
         loop {
             let last = update.clone();
@@ -93,7 +103,6 @@ fn part2(rules: &OrderingRules, Updates(updates): &Updates) -> u32 {
                     update.swap(i, i + 1);
                 }
             }
-            println!("{update:?}");
             if update == last {
                 if incorrect {
                     let d = update[update.len() / 2];
@@ -107,10 +116,29 @@ fn part2(rules: &OrderingRules, Updates(updates): &Updates) -> u32 {
     result
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input<'a> = (OrderingRules, Updates);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        let (rules, updates) = parse(input)?;
+        Ok((compile_rules(rules), updates))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(&input.0, &input.1)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(&input.0, &input.1)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let content = read_to_string(args.path).expect("could not read file");
-    let (rules, updates) = parse(&content).unwrap();
+    let (rules, updates) = or_exit(parse(&content));
     let rules = compile_rules(rules);
     println!("{:?}", rules);
     println!("{}", part1(&rules, &updates));
@@ -1,18 +1,14 @@
 use std::{
-    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    collections::{BTreeSet, HashSet},
     fs::read_to_string,
 };
 
-use clap::Parser;
-use ndarray::RawViewRepr;
-use nom::{
-    IResult,
-    branch::alt,
-    character::complete::{self, newline},
-    combinator::{map, opt, value},
-    multi::many1,
-    sequence::terminated,
+use advent_of_code::{
+    grid::Grid,
+    parse::{ParseError, or_exit},
+    solution::Solution,
 };
+use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
@@ -32,7 +28,7 @@ enum RawTile {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Empty,
     Occupied,
 }
@@ -51,7 +47,7 @@ impl From<RawTile> for Tile {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-struct Guard {
+pub struct Guard {
     pos: (i32, i32),
     direction: Direction,
 }
@@ -98,204 +94,145 @@ impl Guard {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-enum Direction {
+pub enum Direction {
     Up,
     Down,
     Left,
     Right,
 }
 
-#[derive(Debug, Clone)]
-struct Grid<T> {
-    letter: Vec<Vec<T>>,
-    height: i32,
-    width: i32,
-}
-
-impl<T: Copy> Grid<T> {
-    fn new(letter: Vec<Vec<T>>) -> Self {
-        let height = letter.len() as i32;
-        let width = letter[0].len() as i32;
-        Self {
-            letter,
-            width,
-            height,
-        }
-    }
-    fn get(&self, x: i32, y: i32) -> Option<T> {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
-        }
-        Some(self.letter[y as usize][x as usize])
-    }
-    fn set(&mut self, x: i32, y: i32, s: T) -> Option<()> {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
-        }
-        self.letter[y as usize][x as usize] = s;
-        Some(())
-    }
-}
-
-fn grid(input: &str) -> IResult<&str, Grid<RawTile>> {
-    map(
-        many1(terminated(
-            many1(alt((
-                value(RawTile::Empty, complete::char('.')),
-                value(RawTile::Occupied, complete::char('#')),
-                value(RawTile::GuardUp, complete::char('^')),
-                value(RawTile::GuardDown, complete::char('v')),
-                value(RawTile::GuardLeft, complete::char('<')),
-                value(RawTile::GuardRight, complete::char('>')),
-            ))),
-            opt(newline),
-        )),
-        Grid::new,
-    )(input)
+fn grid(input: &str) -> Result<Grid<RawTile>, ParseError> {
+    Grid::try_parse(
+        input,
+        |c| match c {
+            '.' => Some(RawTile::Empty),
+            '#' => Some(RawTile::Occupied),
+            '^' => Some(RawTile::GuardUp),
+            'v' => Some(RawTile::GuardDown),
+            '<' => Some(RawTile::GuardLeft),
+            '>' => Some(RawTile::GuardRight),
+            _ => None,
+        },
+        "a tile",
+    )
 }
 
 fn parse_guard(grid: Grid<RawTile>) -> (Grid<Tile>, Option<Guard>) {
     let guard = grid
-        .letter
-        .iter()
-        .enumerate()
-        .find_map(|(j, row)| {
-            row.iter().enumerate().find_map(|(i, x)| match x {
-                RawTile::GuardUp => Some((i, j, Direction::Up)),
-                RawTile::GuardDown => Some((i, j, Direction::Down)),
-                RawTile::GuardLeft => Some((i, j, Direction::Left)),
-                RawTile::GuardRight => Some((i, j, Direction::Right)),
-                _ => None,
-            })
+        .all()
+        .into_iter()
+        .find_map(|(x, y, tile)| match tile {
+            RawTile::GuardUp => Some((x, y, Direction::Up)),
+            RawTile::GuardDown => Some((x, y, Direction::Down)),
+            RawTile::GuardLeft => Some((x, y, Direction::Left)),
+            RawTile::GuardRight => Some((x, y, Direction::Right)),
+            _ => None,
         })
         .map(|(x, y, d)| Guard {
-            pos: (x as i32, y as i32),
+            pos: (x, y),
             direction: d,
         });
-    let new_grid = Grid::new(
-        grid.letter
-            .into_iter()
-            .map(|row| {
-                row.into_iter()
-                    .map(|x| match x {
-                        RawTile::Empty => Tile::Empty,
-                        RawTile::Occupied => Tile::Occupied,
-                        RawTile::GuardDown => Tile::Empty,
-                        RawTile::GuardUp => Tile::Empty,
-                        RawTile::GuardLeft => Tile::Empty,
-                        RawTile::GuardRight => Tile::Empty,
-                    })
-                    .collect()
-            })
-            .collect(),
-    );
+    let new_grid = grid.map(|x| match x {
+        RawTile::Empty => Tile::Empty,
+        RawTile::Occupied => Tile::Occupied,
+        RawTile::GuardDown => Tile::Empty,
+        RawTile::GuardUp => Tile::Empty,
+        RawTile::GuardLeft => Tile::Empty,
+        RawTile::GuardRight => Tile::Empty,
+    });
     (new_grid, guard)
 }
 
-fn solve_part1(grid: &Grid<Tile>, mut guard: Guard) -> i32 {
-    let mut visited = Vec::new();
-    let (mut x, mut y) = guard.peek();
-    visited.push(guard.pos);
-    while let Some(tile_ahead) = grid.get(x, y) {
-        if let Some(g) = guard.rotate(tile_ahead) {
-            guard = g;
-            continue;
-        };
-        guard = guard.march();
-        visited.push(guard.pos);
-        (x, y) = guard.peek();
+/// Turns the guard until the way ahead is clear, then takes one step. `None` once the guard
+/// walks off the grid.
+fn step(grid: &Grid<Tile>, mut guard: Guard) -> Option<Guard> {
+    loop {
+        let (x, y) = guard.peek();
+        match guard.rotate(grid.get(x, y)?) {
+            Some(turned) => guard = turned,
+            None => return Some(guard.march()),
+        }
     }
-    visited.into_iter().collect::<BTreeSet<_>>().len() as i32
 }
 
-fn print_loop(grid: &Grid<Tile>, prevs: &[Guard]) {
-    let mut h = HashMap::new();
-    for Guard { pos, direction } in prevs {
-        h.insert(pos, direction);
-    }
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            print!(
-                "{}",
-                match grid.get(x, y) {
-                    Some(Tile::Occupied) => "#",
-                    Some(Tile::Empty) => {
-                        match h.get(&(x, y)) {
-                            Some(Direction::Down) => "v",
-                            Some(Direction::Up) => "^",
-                            Some(Direction::Left) => "<",
-                            Some(Direction::Right) => ">",
-                            None => ".",
-                        }
-                    }
-                    None => {
-                        panic!("moo")
-                    }
-                }
-            );
+/// The positions the guard walks through before leaving the grid or going round in a loop, in
+/// order and with repeats.
+fn path(grid: &Grid<Tile>, mut guard: Guard) -> Vec<(i32, i32)> {
+    let mut seen = HashSet::from([guard]);
+    let mut visited = vec![guard.pos];
+    while let Some(next) = step(grid, guard) {
+        if !seen.insert(next) {
+            break;
         }
-        print!("\n");
+        guard = next;
+        visited.push(guard.pos);
     }
+    visited
 }
 
-fn has_loop(
-    grid: &Grid<Tile>,
-    mut guard: Guard,
-    iterations: i32,
-    orig_x: i32,
-    orig_y: i32,
-) -> bool {
-    let mut visited = HashSet::new();
-    let mut prev = Vec::new();
-    let (mut x, mut y) = guard.peek();
-    while let Some(tile_ahead) = grid.get(x, y) {
-        if let Some(g) = guard.rotate(tile_ahead) {
-            guard = g;
-            continue;
-        };
-        guard = guard.march();
-        if visited.contains(&guard) {
-            print_loop(grid, &prev);
+fn solve_part1(grid: &Grid<Tile>, guard: Guard) -> i32 {
+    path(grid, guard).into_iter().collect::<BTreeSet<_>>().len() as i32
+}
+
+fn has_loop(grid: &Grid<Tile>, mut guard: Guard) -> bool {
+    let mut visited = HashSet::from([guard]);
+    while let Some(next) = step(grid, guard) {
+        guard = next;
+        // the guard has been here facing the same way, so the walk repeats from now on
+        if !visited.insert(guard) {
             return true;
         }
-        visited.insert(guard);
-        prev.push(guard);
-        (x, y) = guard.peek();
     }
     false
 }
 
+/// An obstruction only changes the walk if it's put somewhere the guard would walk through.
 fn solve_part2(grid: &Grid<Tile>, guard: Guard) -> i32 {
+    let candidates = path(grid, guard)
+        .into_iter()
+        .filter(|&pos| pos != guard.pos)
+        .collect::<BTreeSet<_>>();
+    let mut grid = grid.clone();
     let mut loop_count = 0;
-    let mut iterations = 0;
-    for y in 0..grid.height {
-        for x in 0..grid.width {
-            iterations += 1;
-            if iterations != 13808 {
-                continue;
-            }
-            if guard.pos == (x, y) {
-                continue;
-            }
-            if grid.get(x, y) == Some(Tile::Occupied) {
-                continue;
-            }
-            let mut new_grid = grid.clone();
-            new_grid.set(x, y, Tile::Occupied).unwrap();
-            if has_loop(&new_grid, guard, iterations, x, y) {
-                loop_count += 1;
-            }
+    for (x, y) in candidates {
+        grid.set(x, y, Tile::Occupied).unwrap();
+        if has_loop(&grid, guard) {
+            loop_count += 1;
         }
+        grid.set(x, y, Tile::Empty).unwrap();
     }
     loop_count
 }
 
+fn parse(content: &str) -> Result<(Grid<Tile>, Guard), ParseError> {
+    let (grid, guard) = parse_guard(grid(content)?);
+    let guard =
+        guard.ok_or_else(|| ParseError::new(content, &content[content.len()..], "a guard"))?;
+    Ok((grid, guard))
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input<'a> = (Grid<Tile>, Guard);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve_part1(&input.0, input.1)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve_part2(&input.0, input.1)
+    }
+}
+
 fn main() {
     let args = Cli::parse();
     let content = read_to_string(args.path).expect("could not read file");
-    let (remain, grid) = grid(&content).unwrap();
-    let (grid, guard) = parse_guard(grid);
-    let guard = guard.expect("No guard found");
+    let (grid, guard) = or_exit(parse(&content));
     println!("{}", solve_part1(&grid, guard));
     println!("{}", solve_part2(&grid, guard));
 }
@@ -305,5 +242,17 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_loop() {}
+    fn test_example() {
+        let (grid, guard) = parse(include_str!("../../input/day6-test")).unwrap();
+        assert_eq!(solve_part1(&grid, guard), 41);
+        assert_eq!(solve_part2(&grid, guard), 6);
+    }
+
+    #[test]
+    fn test_loop() {
+        // the guard is walled in from the start, so the walk has to stop when it comes round again
+        let (grid, guard) = parse(include_str!("../../input/day6-test-loop")).unwrap();
+        assert!(has_loop(&grid, guard));
+        assert_eq!(solve_part1(&grid, guard), 18);
+    }
 }
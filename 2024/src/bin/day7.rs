@@ -3,12 +3,14 @@ use std::{
     path::Path,
 };
 
+use advent_of_code::{
+    parse::{IResult, ParseError, from_nom, tag},
+    solution::Solution,
+};
 use clap::Parser;
 use itertools::Itertools;
 use ndarray::iter;
 use nom::{
-    IResult,
-    bytes::complete::tag,
     character::complete::newline,
     combinator::{map, map_res, opt},
     multi::{many1, separated_list1},
@@ -23,7 +25,7 @@ struct Cli {
 }
 
 #[derive(Debug, Eq, PartialEq)]
-struct Equation {
+pub struct Equation {
     result: i64,
     test_values: Vec<i64>,
 }
@@ -114,23 +116,43 @@ fn solve<'a>(equations: impl Iterator<Item = &'a Equation>, operators: &[Operato
         .sum()
 }
 
+fn part1(equations: &[Equation]) -> i64 {
+    solve(equations.iter(), &[Operator::Add, Operator::Mul])
+}
+
+fn part2(equations: &[Equation]) -> i64 {
+    solve(
+        equations.iter(),
+        &[Operator::Add, Operator::Mul, Operator::Cat],
+    )
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = Vec<Equation>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        from_nom(input, parse(input))
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() -> anyhow::Result<()> {
     let args = Cli::parse();
     let content = read_to_string(args.path)?;
     let (remaining, equations) = parse(&content).unwrap();
     let p = Path::new("testfile");
     assert!(remaining.is_empty());
-    println!(
-        "part 1:  {}",
-        solve(equations.iter(), &[Operator::Add, Operator::Mul]),
-    );
-    println!(
-        "part 2:  {}",
-        solve(
-            equations.iter(),
-            &[Operator::Add, Operator::Mul, Operator::Cat]
-        ),
-    );
+    println!("part 1:  {}", part1(&equations));
+    println!("part 2:  {}", part2(&equations));
 
     Ok(())
 }
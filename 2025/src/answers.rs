@@ -0,0 +1,115 @@
+use std::{ffi::OsStr, num::ParseIntError, path::Path, process::Command};
+
+use thiserror::Error;
+
+/// One line of a year's `answers.txt`: the answer a part should give for an input file.
+///
+/// Lines look like `<day> <part> <input> <answer>`, lines starting with `#` are comments. An answer
+/// written as `@file` is read from that file in the input directory, for answers that
+/// span several lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Answer {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    pub expected: Expected,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Inline(String),
+    File(String),
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ManifestError {
+    #[error("line {0}: expected `<day> <part> <input> <answer>`")]
+    Malformed(usize),
+    #[error("line {0}: {1}")]
+    BadNumber(usize, ParseIntError),
+}
+
+pub fn parse(manifest: &str) -> Result<Vec<Answer>, ManifestError> {
+    manifest
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(n, line)| {
+            let mut fields = line.split_whitespace();
+            let (Some(day), Some(part), Some(input)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                return Err(ManifestError::Malformed(n));
+            };
+            let answer = fields.collect::<Vec<_>>().join(" ");
+            if answer.is_empty() {
+                return Err(ManifestError::Malformed(n));
+            }
+            Ok(Answer {
+                day: number(day, n)?,
+                part: number(part, n)?,
+                input: input.to_string(),
+                expected: match answer.strip_prefix('@') {
+                    Some(file) => Expected::File(file.to_string()),
+                    None => Expected::Inline(answer.to_string()),
+                },
+            })
+        })
+        .collect()
+}
+
+fn number(field: &str, line: usize) -> Result<u32, ManifestError> {
+    field
+        .parse()
+        .map_err(|err| ManifestError::BadNumber(line, err))
+}
+
+/// Runs a year's `aoc check` from its crate directory and fails the calling test when an answer
+/// differs from the manifest. Each crate's `tests/answers.rs` passes its own
+/// `env!("CARGO_BIN_EXE_aoc")` and `env!("CARGO_MANIFEST_DIR")`.
+pub fn check(aoc: impl AsRef<OsStr>, dir: impl AsRef<Path>, args: &[&str]) {
+    let status = Command::new(aoc)
+        .arg("check")
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "answers differ from answers.txt");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let manifest = "# day part input answer\n\n1  1  day1_test.txt  3\n6 2 day6 @day6-output\n";
+        assert_eq!(
+            parse(manifest),
+            Ok(vec![
+                Answer {
+                    day: 1,
+                    part: 1,
+                    input: "day1_test.txt".to_string(),
+                    expected: Expected::Inline("3".to_string()),
+                },
+                Answer {
+                    day: 6,
+                    part: 2,
+                    input: "day6".to_string(),
+                    expected: Expected::File("day6-output".to_string()),
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_malformed() {
+        assert_eq!(parse("1 1 day1"), Err(ManifestError::Malformed(1)));
+        assert!(matches!(
+            parse("\none 1 day1 3"),
+            Err(ManifestError::BadNumber(2, _))
+        ));
+    }
+}
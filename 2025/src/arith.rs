@@ -0,0 +1,98 @@
+use std::{fmt::Display, str::FromStr};
+
+pub use num_bigint::BigInt;
+pub use num_rational::BigRational;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
+pub use num_traits::{One, Zero};
+use thiserror::Error;
+
+/// The integers an evaluator can run on: the machine ones report overflow, [`BigInt`] never does.
+pub trait Integer:
+    Clone + Ord + Display + Zero + One + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv
+{
+}
+
+impl<T> Integer for T where
+    T: Clone + Ord + Display + Zero + One + CheckedAdd + CheckedSub + CheckedMul + CheckedDiv
+{
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum ArithError {
+    #[error("arithmetic overflow, try the big arithmetic")]
+    Overflow,
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("wrong number of operands")]
+    Arity,
+}
+
+pub fn add<T: Integer>(a: &T, b: &T) -> Result<T, ArithError> {
+    a.checked_add(b).ok_or(ArithError::Overflow)
+}
+
+pub fn sub<T: Integer>(a: &T, b: &T) -> Result<T, ArithError> {
+    a.checked_sub(b).ok_or(ArithError::Overflow)
+}
+
+pub fn mul<T: Integer>(a: &T, b: &T) -> Result<T, ArithError> {
+    a.checked_mul(b).ok_or(ArithError::Overflow)
+}
+
+/// Truncates towards zero, like `/` on the machine integers.
+pub fn div<T: Integer>(a: &T, b: &T) -> Result<T, ArithError> {
+    if b.is_zero() {
+        return Err(ArithError::DivisionByZero);
+    }
+    a.checked_div(b).ok_or(ArithError::Overflow)
+}
+
+/// Which integers an evaluator uses, picked at run time with `--arithmetic checked|big`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Arithmetic {
+    #[default]
+    Checked,
+    Big,
+}
+
+impl FromStr for Arithmetic {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "checked" => Ok(Arithmetic::Checked),
+            "big" => Ok(Arithmetic::Big),
+            _ => Err(format!("unknown arithmetic {s:?}, expected checked or big")),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checked() {
+        assert_eq!(add(&u64::MAX, &1), Err(ArithError::Overflow));
+        assert_eq!(mul(&(1i64 << 32), &(1 << 31)), Err(ArithError::Overflow));
+        assert_eq!(div(&i64::MIN, &-1), Err(ArithError::Overflow));
+        assert_eq!(div(&7, &0), Err(ArithError::DivisionByZero));
+        assert_eq!(div(&-7, &2), Ok(-3));
+    }
+
+    #[test]
+    fn test_big() {
+        let max = BigInt::from(u64::MAX);
+        assert_eq!(
+            mul(&max, &max).unwrap().to_string(),
+            "340282366920938463426481119284349108225"
+        );
+        assert_eq!(
+            div(&BigInt::from(-7), &BigInt::from(2)),
+            Ok(BigInt::from(-3))
+        );
+        assert_eq!(div(&max, &BigInt::from(0)), Err(ArithError::DivisionByZero));
+        assert_eq!("big".parse(), Ok(Arithmetic::Big));
+        assert!("wrapping".parse::<Arithmetic>().is_err());
+    }
+}
@@ -0,0 +1,94 @@
+use std::{
+    fmt::Display,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+/// How many untimed warmup runs to do before timing `iterations` runs.
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub warmup: u32,
+    pub iterations: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Display for Stage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Stage::Parse => write!(f, "parse"),
+            Stage::Part1 => write!(f, "part1"),
+            Stage::Part2 => write!(f, "part2"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarise");
+        samples.sort();
+        let n = samples.len();
+        let median = if !n.is_multiple_of(2) {
+            samples[n / 2]
+        } else {
+            (samples[n / 2 - 1] + samples[n / 2]) / 2
+        };
+        Self {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / n as u32,
+        }
+    }
+}
+
+pub fn time<T>(settings: &Settings, mut f: impl FnMut() -> T) -> Stats {
+    for _ in 0..settings.warmup {
+        black_box(f());
+    }
+    let samples = (0..settings.iterations.max(1))
+        .map(|_| {
+            let start = Instant::now();
+            black_box(f());
+            start.elapsed()
+        })
+        .collect();
+    Stats::from_samples(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats() {
+        let ms = Duration::from_millis;
+        assert_eq!(
+            Stats::from_samples(vec![ms(5), ms(1), ms(3)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(3),
+            }
+        );
+        assert_eq!(
+            Stats::from_samples(vec![ms(4), ms(1), ms(2), ms(9)]),
+            Stats {
+                min: ms(1),
+                median: ms(3),
+                mean: ms(4),
+            }
+        );
+    }
+}
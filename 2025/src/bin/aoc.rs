@@ -0,0 +1,40 @@
+use std::process::ExitCode;
+
+use advent_of_code::{runner, solution::Day};
+
+#[path = "day1.rs"]
+#[allow(dead_code)]
+mod day1;
+#[path = "day2.rs"]
+#[allow(dead_code)]
+mod day2;
+#[path = "day3.rs"]
+#[allow(dead_code)]
+mod day3;
+#[path = "day4.rs"]
+#[allow(dead_code)]
+mod day4;
+#[path = "day5.rs"]
+#[allow(dead_code)]
+mod day5;
+#[path = "day6.rs"]
+#[allow(dead_code)]
+mod day6;
+#[path = "day7.rs"]
+#[allow(dead_code)]
+mod day7;
+
+fn main() -> ExitCode {
+    runner::main(
+        2025,
+        &[
+            Day::streaming::<day1::Day1>(1),
+            Day::new::<day2::Day2>(2),
+            Day::streaming::<day3::Day3>(3),
+            Day::new::<day4::Day4>(4),
+            Day::new::<day5::Day5>(5),
+            Day::new::<day6::Day6>(6),
+            Day::new::<day7::Day7>(7),
+        ],
+    )
+}
@@ -1,3 +1,11 @@
+use std::{fmt::Display, io::BufRead};
+
+use advent_of_code::{
+    input,
+    numtheory::multiples_between,
+    parse::{ParseError, or_exit, parse_lines},
+    solution::{Solution, Streaming},
+};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -8,67 +16,105 @@ struct Cli {
 }
 
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
-enum Direction {
+pub enum Direction {
     R,
     L,
 }
 
-fn parse(line: &str) -> (Direction, i32) {
-    let (dir, count) = line.split_at(1);
+fn parse(line: &str) -> Result<(Direction, i32), ParseError> {
+    let (dir, count) = line.split_at(line.chars().next().map_or(0, char::len_utf8));
     let direction = match dir {
         "R" => Direction::R,
         "L" => Direction::L,
-        _ => panic!("err"),
+        _ => return Err(ParseError::new(line, dir, "R/L")),
+    };
+    let count = count
+        .parse()
+        .map_err(|_| ParseError::new(line, count, "a number"))?;
+    Ok((direction, count))
+}
+
+/// Where the dial points, with how often it has stopped on 0 and clicked past it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Dial {
+    position: i32,
+    stops: usize,
+    clicks: usize,
+}
+
+const START: Dial = Dial {
+    position: 50,
+    stops: 0,
+    clicks: 0,
+};
+
+fn turn(dial: Dial, (d, i): (Direction, i32)) -> Dial {
+    let a = dial.position;
+    // the clicks past 0 are the multiples of 100 the dial sweeps over, not counting where it starts
+    let (b, q) = match d {
+        Direction::R => (a + i, multiples_between(100, a + 1, a + i)),
+        Direction::L => (a - i, multiples_between(100, a - i, a - 1)),
     };
-    (direction, count.parse().expect("not an int"))
+    let b = b.rem_euclid(100);
+    Dial {
+        position: b,
+        stops: dial.stops + usize::from(b == 0),
+        clicks: dial.clicks + q as usize,
+    }
 }
 
-fn part1(input: &str) -> usize {
-    input
-        .lines()
-        .map(parse)
-        .scan(50, |a, (d, i)| {
-            *a = match d {
-                Direction::R => (*a + i).rem_euclid(100),
-                Direction::L => (*a - i).rem_euclid(100),
-            };
-            Some(*a)
-        })
-        .filter(|x| *x == 0)
-        .count()
+/// Turns the dial a line at a time, so the rotations are never all in memory.
+fn turn_all(rotations: impl BufRead) -> Result<Dial, ParseError> {
+    parse_lines(rotations, parse).try_fold(START, |dial, rotation| Ok(turn(dial, rotation?)))
 }
 
-fn part2(input: &str) -> usize {
-    input
-        .lines()
-        .map(parse)
-        .fold((50, 0), |(a, c), (d, i)| {
-            let b = match d {
-                Direction::R => (a + i).rem_euclid(100),
-                Direction::L => (a - i).rem_euclid(100),
-            };
-            let q = match d {
-                Direction::R => (a + i).div_euclid(100),
-                Direction::L if a - i > 0 => 0,
-                Direction::L if a - i == 0 => 1,
-                Direction::L if a == 0 => (a - i) / (-100),
-                Direction::L => ((a - i) / (-100)) + 1,
-            };
-            (b, c + q)
-        })
-        .1 as usize
+fn part1(rotations: &[(Direction, i32)]) -> usize {
+    rotations.iter().copied().fold(START, turn).stops
+}
+
+fn part2(rotations: &[(Direction, i32)]) -> usize {
+    rotations.iter().copied().fold(START, turn).clicks
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input<'a> = Vec<(Direction, i32)>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse_lines(input.as_bytes(), parse).collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
+impl Streaming for Day1 {
+    type Line = (Direction, i32);
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        parse(line)
+    }
+
+    fn answers(rotations: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        let dial = rotations.fold(START, turn);
+        (dial.stops, dial.clicks)
+    }
 }
 
 fn main() {
     let cli = Cli::parse();
 
-    let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
-
-    let result_part1 = part1(&input);
-    println!("Part 1 Result: {result_part1}");
+    let input = input::open(&cli.path).expect("Failed to read input file");
+    let dial = or_exit(turn_all(input));
 
-    let result_part2 = part2(&input);
-    println!("Part 2 Result: {result_part2}");
+    println!("Part 1 Result: {}", dial.stops);
+    println!("Part 2 Result: {}", dial.clicks);
 }
 
 #[cfg(test)]
@@ -77,6 +123,16 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        assert_eq!(parse("R50"), (Direction::R, 50))
+        assert_eq!(parse("R50"), Ok((Direction::R, 50)));
+        assert_eq!(
+            Day1::parse("R50\nL5\nX7\n").unwrap_err().to_string(),
+            "input line 3, column 1: expected R/L, got X"
+        );
+    }
+
+    #[test]
+    fn test_turn_all() {
+        let dial = turn_all(include_str!("../../input/day1_test.txt").as_bytes()).unwrap();
+        assert_eq!((dial.stops, dial.clicks), (3, 6));
     }
 }
@@ -1,6 +1,11 @@
+use advent_of_code::{
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 use clap::Parser;
 use std::io::Cursor;
 use std::io::Write;
+use std::ops::RangeInclusive;
 
 #[derive(Parser, Debug, Clone)]
 #[command(author, version, about, long_about = None)]
@@ -30,7 +35,6 @@ fn is_invalid(n: i64) -> bool {
     let mut cursor = Cursor::new(&mut buffer[..]);
     write!(cursor, "{}", n).unwrap();
     let length = cursor.position() as usize;
-    println!("{:?}, {}", &buffer[0..length], length);
     'next: for j in 1..=length / 2 {
         if length % j != 0 {
             continue;
@@ -49,19 +53,54 @@ fn is_invalid(n: i64) -> bool {
     false
 }
 
-fn solve(input: &str, f: impl Fn(i64) -> bool) -> i64 {
+fn parse(input: &str) -> Result<Vec<RangeInclusive<i64>>, ParseError> {
+    let number = |x: &str| {
+        x.parse::<i64>()
+            .map_err(|_| ParseError::new(input, x, "a number"))
+    };
     input
+        .trim_end()
         .split(',')
-        .map(|range| range.split_once('-').unwrap())
-        .flat_map(|(start, end)| start.parse().unwrap()..=end.parse().unwrap())
+        .map(|range| {
+            let (start, end) = range
+                .split_once('-')
+                .ok_or_else(|| ParseError::new(input, range, "a range like 11-22"))?;
+            Ok(number(start)?..=number(end)?)
+        })
+        .collect()
+}
+
+fn solve(ranges: &[RangeInclusive<i64>], f: impl Fn(i64) -> bool) -> i64 {
+    ranges
+        .iter()
+        .flat_map(|range| range.clone())
         .filter(|x| f(*x))
         .sum()
 }
 
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input<'a> = Vec<RangeInclusive<i64>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve(input, is_mirror)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve(input, is_invalid)
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
 
     let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
+    let input = or_exit(parse(&input));
 
     println!("Part 1 Result: {}", solve(&input, is_mirror));
 
@@ -87,4 +126,10 @@ mod tests {
         assert_eq!(is_invalid(1212), true);
         assert_eq!(is_invalid(123123123), true);
     }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("11-22,95-115\n"), Ok(vec![11..=22, 95..=115]));
+        assert_eq!(parse("11-22,9515").unwrap_err().column, 7);
+    }
 }
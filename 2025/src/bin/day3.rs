@@ -1,5 +1,10 @@
+use advent_of_code::{
+    input,
+    parse::{ParseError, or_exit, parse_lines},
+    solution::{Solution, Streaming},
+};
 use ahash::{HashMap, HashMapExt};
-use std::cmp::max;
+use std::{cmp::max, fmt::Display};
 
 use clap::Parser;
 
@@ -10,6 +15,21 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
+/// A bank needs a battery for each of the 12 digits part 2 turns on.
+fn bank(line: &str) -> Result<&[u8], ParseError> {
+    if let Some(i) = line.find(|c: char| !c.is_ascii_digit()) {
+        return Err(ParseError::new(line, &line[i..], "a battery joltage"));
+    }
+    if line.len() < 12 {
+        return Err(ParseError::new(
+            line,
+            line,
+            "a bank of at least 12 batteries",
+        ));
+    }
+    Ok(line.as_bytes())
+}
+
 fn max_jolt<'a>(n: usize, mem: &mut HashMap<(usize, &'a [u8]), i64>, input: &'a [u8]) -> i64 {
     if n == 1 {
         let result = input.iter().fold(0, |a, c| max(a, (c - b'0') as i64));
@@ -31,18 +51,66 @@ fn max_jolt<'a>(n: usize, mem: &mut HashMap<(usize, &'a [u8]), i64>, input: &'a
     largest
 }
 
-fn solve(n: usize, input: &str) -> i64 {
-    input
-        .lines()
-        .map(|line| max_jolt(n, &mut HashMap::new(), line.as_bytes()))
+fn solve(n: usize, banks: &[&[u8]]) -> i64 {
+    banks
+        .iter()
+        .map(|bank| max_jolt(n, &mut HashMap::new(), bank))
         .sum()
 }
 
+/// Both parts in one pass, holding a single bank at a time.
+fn joltages(banks: impl Iterator<Item = impl AsRef<[u8]>>) -> (i64, i64) {
+    banks
+        .map(|bank| {
+            (
+                max_jolt(2, &mut HashMap::new(), bank.as_ref()),
+                max_jolt(12, &mut HashMap::new(), bank.as_ref()),
+            )
+        })
+        .fold((0, 0), |(a, b), (x, y)| (a + x, b + y))
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input<'a> = Vec<&'a [u8]>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        input
+            .lines()
+            .enumerate()
+            .map(|(i, line)| bank(line).map_err(|err| err.on_line(i + 1)))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve(2, input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        solve(12, input)
+    }
+}
+
+impl Streaming for Day3 {
+    type Line = Vec<u8>;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError> {
+        bank(line).map(<[u8]>::to_vec)
+    }
+
+    fn answers(banks: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display) {
+        joltages(banks)
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
-    let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
-    println!("Part 1: {}", solve(2, &input));
-    println!("Part 2: {}", solve(12, &input));
+    let input = input::open(&cli.path).expect("Failed to read input file");
+    // the banks are streamed, stopping at the first bad one
+    let (part1, part2) = joltages(parse_lines(input, Day3::line).map(or_exit));
+    println!("Part 1: {part1}");
+    println!("Part 2: {part2}");
 }
 
 #[cfg(test)]
@@ -84,6 +152,16 @@ mod tests {
         assert_eq!(max_jolt(2, &mut HashMap::new(), "2314".as_bytes()), 34);
     }
 
+    #[test]
+    fn test_stream() {
+        let example = include_str!("../../input/day3_test");
+        let banks = Day3::parse(example).unwrap();
+        assert_eq!(
+            joltages(banks.iter()),
+            (solve(2, &banks), solve(12, &banks))
+        );
+    }
+
     #[test]
     fn test_general_case() {
         assert_eq!(
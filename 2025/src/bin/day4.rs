@@ -1,4 +1,8 @@
 use advent_of_code::grid::Grid;
+use advent_of_code::{
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 use ahash::{HashMap, HashMapExt};
 use itertools::Itertools;
 use rayon::iter::Empty;
@@ -14,26 +18,25 @@ struct Cli {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
-enum Tile {
+pub enum Tile {
     Roll,
     Empty,
 }
 
-fn parse(input: &str) -> Grid<Tile> {
-    Grid::new(
-        input
-            .lines()
-            .map(|line| {
-                line.chars()
-                    .map(|x| match x {
-                        '@' => Tile::Roll,
-                        '.' => Tile::Empty,
-                        _ => panic!("invalid"),
-                    })
-                    .collect()
-            })
-            .collect(),
-    )
+fn parse(input: &str) -> Result<Grid<Tile>, ParseError> {
+    let rows = input
+        .lines()
+        .map(|line| {
+            line.char_indices()
+                .map(|(i, x)| match x {
+                    '@' => Ok(Tile::Roll),
+                    '.' => Ok(Tile::Empty),
+                    _ => Err(ParseError::new(input, &line[i..i + x.len_utf8()], "@ or .")),
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(Grid::new(rows))
 }
 
 fn get_accessible(grid: &Grid<Tile>) -> Vec<(i32, i32)> {
@@ -60,13 +63,12 @@ fn get_accessible(grid: &Grid<Tile>) -> Vec<(i32, i32)> {
     accessibles
 }
 
-fn part1(input: &str) -> usize {
-    let grid = parse(input);
-    get_accessible(&grid).len()
+fn part1(grid: &Grid<Tile>) -> usize {
+    get_accessible(grid).len()
 }
 
-fn part2(input: &str) -> usize {
-    let mut grid = parse(input);
+fn part2(grid: &Grid<Tile>) -> usize {
+    let mut grid = grid.clone();
     let mut removed = 0;
     loop {
         let accessible = get_accessible(&grid);
@@ -81,9 +83,28 @@ fn part2(input: &str) -> usize {
     removed
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input<'a> = Grid<Tile>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
     let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
+    let input = or_exit(parse(&input));
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
 }
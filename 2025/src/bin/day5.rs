@@ -1,3 +1,8 @@
+use advent_of_code::{
+    interval::IntervalSet,
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 use clap::Parser;
 
 #[derive(Parser, Debug, Clone)]
@@ -7,49 +12,75 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-fn parser(input: &str) -> (Vec<(i64, i64)>, Vec<i64>) {
-    let (fresh_ingredient_ranges, available_ingredient) = input.split_once("\n\n").unwrap();
+type Inventory = (Vec<(i64, i64)>, Vec<i64>);
+
+fn parser(input: &str) -> Result<Inventory, ParseError> {
+    let number = |x: &str| {
+        x.parse::<i64>()
+            .map_err(|_| ParseError::new(input, x, "a number"))
+    };
+    let (fresh_ingredient_ranges, available_ingredient) =
+        input.split_once("\n\n").ok_or_else(|| {
+            ParseError::new(
+                input,
+                &input[input.len()..],
+                "a blank line after the ranges",
+            )
+        })?;
     let fresh_ingredients_ranges = fresh_ingredient_ranges
         .lines()
-        .map(|line| line.split_once('-').unwrap())
-        .map(|(a, b)| (a.parse().unwrap(), b.parse().unwrap()))
-        .collect();
+        .map(|line| {
+            let (a, b) = line
+                .split_once('-')
+                .ok_or_else(|| ParseError::new(input, line, "a range like 3-5"))?;
+            Ok((number(a)?, number(b)?))
+        })
+        .collect::<Result<_, _>>()?;
     let available_ingredient = available_ingredient
         .lines()
-        .map(|x| x.parse().unwrap())
-        .collect();
-    (fresh_ingredients_ranges, available_ingredient)
+        .map(number)
+        .collect::<Result<_, _>>()?;
+    Ok((fresh_ingredients_ranges, available_ingredient))
+}
+
+fn fresh(ranges: &[(i64, i64)]) -> IntervalSet<i64> {
+    ranges.iter().map(|&(low, high)| low..=high).collect()
 }
 
 fn part1((ranges, ingredients): &(Vec<(i64, i64)>, Vec<i64>)) -> usize {
+    let fresh = fresh(ranges);
     ingredients
         .iter()
-        .filter(|&ingredient| {
-            ranges
-                .iter()
-                .any(|(lower, higher)| ingredient >= lower && ingredient <= higher)
-        })
+        .filter(|&&ingredient| fresh.contains(ingredient))
         .count()
 }
 
-fn part2(ingredients: &Vec<(i64, i64)>) -> i64 {
-    let mut ingredients = ingredients.clone();
-    ingredients.sort();
-    ingredients
-        .iter()
-        .fold((0, None), |(counter, max), &(low, high)| match max {
-            None => (counter + high - low + 1, Some(high)),
-            Some(m) if high <= m => (counter, Some(m)),
-            Some(m) if high > m && low <= m => (counter + high - m, Some(high)),
-            Some(_) => (counter + high - low + 1, Some(high)),
-        })
-        .0
+fn part2(ingredients: &[(i64, i64)]) -> i64 {
+    fresh(ingredients).len()
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input<'a> = (Vec<(i64, i64)>, Vec<i64>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parser(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(&input.0)
+    }
 }
 
 fn main() -> () {
     let cli = Cli::parse();
     let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
-    let input = parser(&input);
+    let input = or_exit(parser(&input));
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input.0));
 }
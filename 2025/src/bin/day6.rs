@@ -1,6 +1,7 @@
-use anyhow::Error;
-use core::num;
-use std::f32::consts::E;
+use advent_of_code::{
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 
 use clap::Parser;
 
@@ -31,94 +32,128 @@ impl Operation {
     }
 }
 
-impl TryFrom<char> for Operation {
-    type Error = anyhow::Error;
+/// One problem on the worksheet, with its rows cut to the problem's columns.
+pub struct Problem<'a> {
+    operation: Operation,
+    rows: Vec<&'a str>,
+}
 
-    fn try_from(value: char) -> Result<Self, Self::Error> {
-        match value {
-            '+' => Ok(Operation::Plus),
-            '*' => Ok(Operation::Multiply),
-            _ => Err(Error::msg("Invalid operation")),
-        }
-    }
+/// Cuts the columns `start..end` out of a line, which can be shorter than the others.
+fn cut(line: &str, start: usize, end: usize) -> &str {
+    &line[start.min(line.len())..end.min(line.len())]
 }
 
-fn parse(input: &str) -> (Vec<Vec<i64>>, Vec<Operation>) {
-    let mut lines = input.lines().peekable();
-    let mut operations = Vec::new();
-    let mut numbers = Vec::new();
-    while let Some(line) = lines.next() {
-        if lines.peek().is_none() {
-            operations = line
-                .split_whitespace()
-                .map(|x| match x {
-                    "+" => Operation::Plus,
-                    "*" => Operation::Multiply,
-                    _ => panic!(""),
-                })
-                .collect()
-        } else {
-            numbers.push(
-                line.split_whitespace()
-                    .map(|x| x.parse().unwrap())
-                    .collect(),
-            );
+fn parse(input: &str) -> Result<Vec<Problem<'_>>, ParseError> {
+    let lines: Vec<&str> = input.lines().collect();
+    let Some((&operators, rows)) = lines.split_last().filter(|(_, rows)| !rows.is_empty()) else {
+        return Err(ParseError::new(
+            input,
+            &input[input.len()..],
+            "a line of numbers",
+        ));
+    };
+    for line in rows {
+        if let Some(i) = line.find(|c: char| c != ' ' && !c.is_ascii_digit()) {
+            return Err(ParseError::new(input, &line[i..], "a digit"));
         }
     }
-    (numbers, operations)
-}
+    if let Some(i) = operators.find(|c| !" +*".contains(c)) {
+        return Err(ParseError::new(input, &operators[i..], "'+' or '*'"));
+    }
 
-fn part1(input: &str) -> i64 {
-    let (numbers, operations) = parse(&input);
-    let mut total = 0;
-    for i in 0..numbers[0].len() {
-        let op = operations[i];
-        let mut val = op.init();
-        for j in 0..numbers.len() {
-            val = op.apply(val, numbers[j][i]);
+    // the problems are split by columns that are blank on every line
+    let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+    let blank = |i: usize| {
+        lines
+            .iter()
+            .all(|line| line.as_bytes().get(i).is_none_or(|&c| c == b' '))
+    };
+    let mut problems = Vec::new();
+    let mut start = 0;
+    for end in (0..=width).filter(|&i| i == width || blank(i)) {
+        if end > start {
+            let operator = cut(operators, start, end);
+            let operation = match operator.trim() {
+                "+" => Operation::Plus,
+                "*" => Operation::Multiply,
+                _ => return Err(ParseError::new(input, operator, "one operator")),
+            };
+            let rows = rows.iter().map(|line| cut(line, start, end)).collect();
+            problems.push(Problem { operation, rows });
         }
-        total += val;
+        start = end + 1;
     }
-    total
+    Ok(problems)
 }
 
-fn part2(input: &str) -> i64 {
-    let height = input.lines().count();
-    let width = input.lines().next().unwrap().len();
-    let cells: Vec<Vec<char>> = input.lines().map(|line| line.chars().collect()).collect();
-    let mut op: Operation = cells[height - 1][0].try_into().unwrap();
-    let mut total = 0;
-    let mut op_value = op.init();
-    for i in 0..width {
-        //op = cells[height - 1][i].try_into().unwrap_or(op);
-        let m: Result<Operation, Error> = cells[height - 1][i].try_into();
-        op = match m {
-            Ok(x) => {
-                op_value = x.init();
-                x
-            }
-            Err(_) => op,
-        };
-        let mut holding = None;
-        for j in 0..height - 1 {
-            let c = cells[j][i];
-            holding = match c.to_digit(10).map(|x| x as i64) {
-                Some(d) => Some(holding.unwrap_or(0) * 10 + d),
-                None => holding,
-            }
-        }
-        match holding {
-            Some(h) => op_value = op.apply(op_value, h),
-            None => total += op_value,
-        }
+/// The number made of the digits in order, skipping the spaces.
+fn number(digits: impl Iterator<Item = u8>) -> Option<i64> {
+    digits
+        .filter(u8::is_ascii_digit)
+        .map(|d| i64::from(d - b'0'))
+        .reduce(|a, d| a * 10 + d)
+}
+
+fn solve(problems: &[Problem], numbers: impl Fn(&Problem) -> Vec<i64>) -> i64 {
+    problems
+        .iter()
+        .map(|problem| {
+            let op = problem.operation;
+            numbers(problem)
+                .into_iter()
+                .fold(op.init(), |a, n| op.apply(a, n))
+        })
+        .sum()
+}
+
+// each row is a number
+fn part1(problems: &[Problem]) -> i64 {
+    solve(problems, |problem| {
+        let rows = problem.rows.iter();
+        rows.filter_map(|row| number(row.bytes())).collect()
+    })
+}
+
+// each column is a number, read top to bottom
+fn part2(problems: &[Problem]) -> i64 {
+    solve(problems, |problem| {
+        let width = problem.rows.iter().map(|row| row.len()).max().unwrap_or(0);
+        (0..width)
+            .filter_map(|i| {
+                number(
+                    problem
+                        .rows
+                        .iter()
+                        .filter_map(|row| row.as_bytes().get(i).copied()),
+                )
+            })
+            .collect()
+    })
+}
+
+pub struct Day6;
+
+// the two parts read the numbers of a problem in different directions
+impl Solution for Day6 {
+    type Input<'a> = Vec<Problem<'a>>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        part2(input)
     }
-    total += op_value;
-    total
 }
 
 fn main() {
     let cli = Cli::parse();
     let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
+    let input = or_exit(parse(&input));
     println!("Part 1: {}", part1(&input));
     println!("Part 2: {}", part2(&input));
 }
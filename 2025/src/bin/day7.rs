@@ -1,3 +1,7 @@
+use advent_of_code::{
+    parse::{ParseError, or_exit},
+    solution::Solution,
+};
 use clap::Parser;
 use std::collections::HashSet;
 
@@ -8,33 +12,29 @@ struct Cli {
     path: std::path::PathBuf,
 }
 
-fn parse(input: &str) -> (Vec<HashSet<usize>>, HashSet<usize>) {
-    (
-        input
-            .lines()
-            .map(|line| {
-                let len = line.len();
-                line.chars()
-                    .enumerate()
-                    .filter_map(|(i, x)| match x {
-                        '^' => Some(i),
-                        _ => None,
-                    })
-                    .collect()
-            })
-            .collect(),
-        input
-            .lines()
-            .next()
-            .unwrap()
-            .chars()
-            .enumerate()
-            .filter_map(|(i, x)| match x {
-                'S' => Some(i),
-                _ => None,
-            })
-            .collect(),
-    )
+fn parse(input: &str) -> Result<(Vec<HashSet<usize>>, HashSet<usize>), ParseError> {
+    let first = input
+        .lines()
+        .next()
+        .ok_or_else(|| ParseError::new(input, input, "a row with the start"))?;
+    let splitters = input
+        .lines()
+        .map(|line| {
+            line.char_indices()
+                .filter_map(|(i, x)| match x {
+                    '^' => Some(Ok(i)),
+                    '.' | 'S' => None,
+                    _ => Some(Err(ParseError::new(input, &line[i..], "'.', '^' or 'S'"))),
+                })
+                .collect()
+        })
+        .collect::<Result<_, _>>()?;
+    let beam = first
+        .char_indices()
+        .filter(|&(_, x)| x == 'S')
+        .map(|(i, _)| i)
+        .collect();
+    Ok((splitters, beam))
 }
 
 fn part1(splitters: &[HashSet<usize>], mut beam: HashSet<usize>) -> (HashSet<usize>, usize) {
@@ -52,9 +52,24 @@ fn part1(splitters: &[HashSet<usize>], mut beam: HashSet<usize>) -> (HashSet<usi
     (beam, counter)
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input<'a> = (Vec<HashSet<usize>>, HashSet<usize>);
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input<'_>) -> impl std::fmt::Display {
+        let (splitters, beam) = input;
+        part1(splitters, beam.clone()).1
+    }
+}
+
 fn main() {
     let cli = Cli::parse();
     let input = std::fs::read_to_string(cli.path).expect("Failed to read input file");
-    let (splitters, beam) = parse(&input);
+    let (splitters, beam) = or_exit(parse(&input));
     println!("Part 1: {:?}", part1(&splitters, beam));
 }
@@ -0,0 +1,118 @@
+use std::{
+    hash::Hash,
+    ops::{Add, Mul, Sub},
+};
+
+use ahash::{HashMap, HashMapExt};
+
+/// A simulation that repeats itself: the state after `start + length` steps is the state after
+/// `start` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as `step`.
+    pub fn reduce(&self, step: usize) -> usize {
+        if step < self.start {
+            step
+        } else {
+            self.start + (step - self.start) % self.length
+        }
+    }
+
+    /// Extends a quantity that grows by the same amount every cycle, like a height or a count, to
+    /// `step`. `values[i]` is the quantity after `i` steps and must reach the end of the cycle.
+    pub fn extrapolate<T>(&self, values: &[T], step: usize) -> T
+    where
+        T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + TryFrom<usize>,
+    {
+        if step < self.start {
+            return values[step];
+        }
+        let delta = values[self.start + self.length] - values[self.start];
+        let Ok(cycles) = T::try_from((step - self.start) / self.length) else {
+            panic!("{step} steps overflow the extrapolated value");
+        };
+        values[self.reduce(step)] + delta * cycles
+    }
+}
+
+/// Remembers every state a simulation passes through, to spot the first one that comes back.
+pub struct CycleFinder<K> {
+    seen: HashMap<K, usize>,
+}
+
+impl<K: Eq + Hash> Default for CycleFinder<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K: Eq + Hash> CycleFinder<K> {
+    pub fn new() -> Self {
+        Self {
+            seen: HashMap::new(),
+        }
+    }
+
+    /// Records the state after `step` steps. Gives the cycle if it was seen at an earlier step.
+    pub fn check(&mut self, step: usize, state: K) -> Option<Cycle> {
+        self.seen.insert(state, step).map(|start| Cycle {
+            start,
+            length: step - start,
+        })
+    }
+}
+
+/// Steps a simulation from `initial` until a state repeats. Gives the cycle along with every state
+/// up to the end of it, so `states[cycle.reduce(n)]` is the state after `n` steps.
+pub fn find<S: Clone + Eq + Hash>(initial: S, mut step: impl FnMut(&S) -> S) -> (Cycle, Vec<S>) {
+    let mut finder = CycleFinder::new();
+    let mut states = vec![initial];
+    loop {
+        let n = states.len() - 1;
+        if let Some(cycle) = finder.check(n, states[n].clone()) {
+            return (cycle, states);
+        }
+        let next = step(&states[n]);
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find() {
+        // 1, 2, 4, 8, 16, 32, 64, 28, 56, 12, 24, 48, 96, 92, 84, 68, 36, 72, 44, 88, 76, 52, 4
+        let (cycle, states) = find(1u32, |n| n * 2 % 100);
+        assert_eq!(
+            cycle,
+            Cycle {
+                start: 2,
+                length: 20
+            }
+        );
+        assert_eq!(states.len(), 23);
+        assert_eq!(states[cycle.reduce(1_000_000)], 76);
+        assert_eq!(cycle.reduce(1), 1);
+    }
+
+    #[test]
+    fn test_extrapolate() {
+        // a counter that gains 3 every cycle of 2 steps, after a lead-in of 1 step
+        let values = [0u64, 10, 11, 13, 14];
+        let cycle = Cycle {
+            start: 1,
+            length: 2,
+        };
+        assert_eq!(cycle.extrapolate(&values, 0), 0);
+        assert_eq!(cycle.extrapolate(&values, 4), 14);
+        assert_eq!(cycle.extrapolate(&values, 5), 16);
+        assert_eq!(cycle.extrapolate(&values, 1_000_001), 1_500_010);
+    }
+}
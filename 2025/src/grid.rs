@@ -1,40 +1,281 @@
-#[derive(Debug, Clone)]
+use std::fmt::{self, Display};
+
+use crate::parse::ParseError;
+
+const NEIGHBOURS4: [(i32, i32); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+const NEIGHBOURS8: [(i32, i32); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+/// A rectangular grid stored row by row, indexed by `(x, y)` with `y` growing downwards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Grid<T> {
-    letter: Vec<Vec<T>>,
+    cells: Vec<T>,
     pub height: i32,
     pub width: i32,
 }
 
-impl<T: Copy> Grid<T> {
-    pub fn new(letter: Vec<Vec<T>>) -> Self {
-        let height = letter.len() as i32;
-        let width = letter[0].len() as i32;
+impl<T> Grid<T> {
+    pub fn new(rows: Vec<Vec<T>>) -> Self {
+        let height = rows.len() as i32;
+        let width = rows.first().map_or(0, Vec::len) as i32;
+        assert!(
+            rows.iter().all(|row| row.len() == width as usize),
+            "rows have different lengths"
+        );
         Self {
-            letter,
+            cells: rows.into_iter().flatten().collect(),
             width,
             height,
         }
     }
-    pub fn get(&self, x: i32, y: i32) -> Option<T> {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
+
+    pub fn from_fn(width: i32, height: i32, mut f: impl FnMut(i32, i32) -> T) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| f(x, y))
+            .collect();
+        Self {
+            cells,
+            width,
+            height,
         }
-        Some(self.letter[y as usize][x as usize])
     }
-    pub fn set(&mut self, x: i32, y: i32, s: T) -> Option<()> {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return None;
+
+    /// Reads one tile per character, one row per line.
+    pub fn parse(input: &str, mut tile: impl FnMut(char) -> T) -> Self {
+        Self::new(
+            input
+                .lines()
+                .map(|line| line.chars().map(&mut tile).collect())
+                .collect(),
+        )
+    }
+
+    /// Like [`Grid::parse`], but fails on a character `tile` has no tile for and on rows of
+    /// different lengths.
+    pub fn try_parse(
+        input: &str,
+        mut tile: impl FnMut(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut rows: Vec<Vec<T>> = Vec::new();
+        for line in input.lines() {
+            let row = line
+                .char_indices()
+                .map(|(i, c)| tile(c).ok_or_else(|| ParseError::new(input, &line[i..], expected)))
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(first) = rows.first().filter(|first| first.len() != row.len()) {
+                let expected = format!("a row of {} tiles", first.len());
+                return Err(ParseError::new(input, line, expected));
+            }
+            rows.push(row);
         }
-        self.letter[y as usize][x as usize] = s;
+        Ok(Self::new(rows))
+    }
+
+    fn index(&self, x: i32, y: i32) -> Option<usize> {
+        self.contains(x, y).then(|| (y * self.width + x) as usize)
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= 0 && y >= 0 && x < self.width && y < self.height
+    }
+
+    pub fn get_ref(&self, x: i32, y: i32) -> Option<&T> {
+        self.index(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut T> {
+        self.index(x, y).map(|i| &mut self.cells[i])
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, s: T) -> Option<()> {
+        *self.get_mut(x, y)? = s;
         Some(())
     }
+
+    /// Every tile, row by row.
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    pub fn row(&self, y: i32) -> &[T] {
+        let start = (y * self.width) as usize;
+        &self.cells[start..start + self.width as usize]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        (0..self.height).map(|y| self.row(y))
+    }
+
+    pub fn column(&self, x: i32) -> impl Iterator<Item = &T> {
+        assert!(0 <= x && x < self.width, "column {x} is outside the grid");
+        self.cells
+            .iter()
+            .skip(x as usize)
+            .step_by(self.width as usize)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|x| self.column(x))
+    }
+
+    /// The up to 4 orthogonal neighbours of a position that lie inside the grid.
+    pub fn neighbours4(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> + '_ {
+        NEIGHBOURS4
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|&(x, y)| self.contains(x, y))
+    }
+
+    /// The up to 8 neighbours of a position, diagonals included, that lie inside the grid.
+    pub fn neighbours8(&self, x: i32, y: i32) -> impl Iterator<Item = (i32, i32)> + '_ {
+        NEIGHBOURS8
+            .iter()
+            .map(move |(dx, dy)| (x + dx, y + dy))
+            .filter(|&(x, y)| self.contains(x, y))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            cells: self.cells.iter().map(f).collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn filled(width: i32, height: i32, item: T) -> Self {
+        Self {
+            cells: vec![item; (width * height) as usize],
+            width,
+            height,
+        }
+    }
+
+    /// Mirrors the grid along its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| self.at(y, x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self.at(y, self.height - 1 - x)
+        })
+    }
+
+    pub fn rotate_anticlockwise(&self) -> Self {
+        Self::from_fn(self.height, self.width, |x, y| {
+            self.at(self.width - 1 - y, x)
+        })
+    }
+
+    fn at(&self, x: i32, y: i32) -> T {
+        self.cells[(y * self.width + x) as usize].clone()
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    pub fn get(&self, x: i32, y: i32) -> Option<T> {
+        self.get_ref(x, y).copied()
+    }
+
     pub fn all(&self) -> Vec<(i32, i32, T)> {
-        let mut v = Vec::new();
-        for y in 0..self.height {
-            for x in 0..self.width {
-                v.push((x, y, self.get(x, y).unwrap()));
+        self.positions()
+            .zip(self.cells.iter())
+            .map(|((x, y), &tile)| (x, y, tile))
+            .collect()
+    }
+}
+
+impl<T: PartialEq> Grid<T> {
+    /// The first position holding `tile`, reading row by row.
+    pub fn find(&self, tile: &T) -> Option<(i32, i32)> {
+        let i = self.cells.iter().position(|t| t == tile)? as i32;
+        Some((i % self.width, i / self.width))
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for tile in row {
+                write!(f, "{tile}")?;
             }
+            writeln!(f)?;
         }
-        v
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "ab\ncd\nef\n";
+
+    #[test]
+    fn test_parse() {
+        let grid = Grid::parse(EXAMPLE, |c| c);
+        assert_eq!((grid.width, grid.height), (2, 3));
+        assert_eq!(grid.get(1, 2), Some('f'));
+        assert_eq!(grid.get(2, 0), None);
+        assert_eq!(grid.find(&'d'), Some((1, 1)));
+        assert_eq!(grid.to_string(), EXAMPLE);
+    }
+
+    #[test]
+    fn test_try_parse() {
+        let letter = |c: char| c.is_ascii_lowercase().then_some(c);
+        assert_eq!(
+            Grid::try_parse(EXAMPLE, letter, "a letter"),
+            Ok(Grid::parse(EXAMPLE, |c| c))
+        );
+        let empty = Grid::try_parse("", letter, "a letter").unwrap();
+        assert_eq!((empty.width, empty.height), (0, 0));
+        let err = Grid::try_parse("ab\ncD\n", letter, "a letter").unwrap_err();
+        assert_eq!((err.line, err.column, err.found.as_str()), (2, 2, "D"));
+        let err = Grid::try_parse("ab\nc\n", letter, "a letter").unwrap_err();
+        assert_eq!((err.line, err.expected.as_str()), (2, "a row of 2 tiles"));
+    }
+
+    #[test]
+    fn test_views() {
+        let grid = Grid::parse(EXAMPLE, |c| c);
+        assert_eq!(grid.row(1), &['c', 'd']);
+        assert_eq!(grid.column(1).collect::<String>(), "bdf");
+        assert_eq!(grid.columns().count(), 2);
+        assert_eq!(
+            grid.neighbours4(0, 0).collect::<Vec<_>>(),
+            vec![(1, 0), (0, 1)]
+        );
+        assert_eq!(grid.neighbours8(0, 1).count(), 5);
+    }
+
+    #[test]
+    fn test_rotate() {
+        let grid = Grid::parse(EXAMPLE, |c| c);
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "eca\nfdb\n");
+        assert_eq!(grid.rotate_anticlockwise().to_string(), "bdf\nace\n");
+        assert_eq!(grid.rotate_clockwise().rotate_anticlockwise(), grid);
     }
 }
@@ -0,0 +1,295 @@
+use std::{
+    convert::Infallible,
+    env,
+    fmt::Display,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    str::FromStr,
+};
+
+use thiserror::Error;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Which of a day's inputs to use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Variant {
+    /// The personal puzzle input, the only one that can be fetched.
+    Real,
+    /// The puzzle text's examples, counting from 1.
+    Example(u32),
+    /// Any other hand-made input, like `day6-mika`.
+    Named(String),
+}
+
+impl FromStr for Variant {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s {
+            "" | "real" => Variant::Real,
+            "example" => Variant::Example(1),
+            _ => match s.strip_prefix("example-").map(str::parse) {
+                Some(Ok(n)) => Variant::Example(n),
+                _ => Variant::Named(s.to_string()),
+            },
+        })
+    }
+}
+
+impl Display for Variant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Variant::Real => write!(f, "real"),
+            Variant::Example(n) => write!(f, "example-{n}"),
+            Variant::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl Variant {
+    /// File names this variant has been saved under, in order of preference.
+    pub fn file_names(&self, day: u32) -> Vec<String> {
+        match self {
+            Variant::Real => vec![format!("day{day}")],
+            Variant::Example(1) => vec![
+                format!("day{day}-example"),
+                format!("day{day}-example-1"),
+                format!("day{day}-test"),
+                format!("day{day}_test"),
+                format!("day{day}_test.txt"),
+            ],
+            Variant::Example(n) => vec![
+                format!("day{day}-example-{n}"),
+                format!("day{day}-test{n}"),
+                format!("day{day}_test{n}"),
+            ],
+            Variant::Named(name) => vec![format!("day{day}-{name}"), format!("day{day}_{name}")],
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("no {variant} input for day {day} in {}", dir.display())]
+    NotFound {
+        day: u32,
+        variant: Variant,
+        dir: PathBuf,
+    },
+    #[error(
+        "no {} input for day {day}, set AOC_SESSION to download it",
+        Variant::Real
+    )]
+    NoSession { day: u32 },
+    #[error("{}: {source}", path.display())]
+    Io { path: PathBuf, source: io::Error },
+    #[error("fetching {url}: {reason}")]
+    Fetch { url: String, reason: String },
+}
+
+/// A year's input directory, downloading real inputs that aren't there yet.
+#[derive(Debug, Clone)]
+pub struct Inputs {
+    pub dir: PathBuf,
+    pub base_url: String,
+    pub session: Option<String>,
+}
+
+impl Inputs {
+    /// Takes the session token from `AOC_SESSION` and the server from `AOC_BASE_URL`.
+    pub fn from_env(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            base_url: env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string()),
+            session: env::var("AOC_SESSION").ok().filter(|s| !s.is_empty()),
+        }
+    }
+
+    /// The existing file for a variant, if there is one.
+    pub fn find(&self, day: u32, variant: &Variant) -> Option<PathBuf> {
+        variant
+            .file_names(day)
+            .into_iter()
+            .map(|name| self.dir.join(name))
+            .find(|path| path.is_file())
+    }
+
+    pub fn read(&self, year: u32, day: u32, variant: &Variant) -> Result<String, InputError> {
+        if let Some(path) = self.find(day, variant) {
+            return fs::read_to_string(&path).map_err(|source| InputError::Io { path, source });
+        }
+        if *variant != Variant::Real {
+            return Err(InputError::NotFound {
+                day,
+                variant: variant.clone(),
+                dir: self.dir.clone(),
+            });
+        }
+        let session = self.session.as_ref().ok_or(InputError::NoSession { day })?;
+        let input = fetch(&self.base_url, session, year, day)?;
+        let path = self.dir.join(format!("day{day}"));
+        fs::create_dir_all(&self.dir)
+            .and_then(|()| fs::write(&path, &input))
+            .map_err(|source| InputError::Io { path, source })?;
+        Ok(input)
+    }
+}
+
+/// Opens an input to read line by line, `-` meaning stdin.
+pub fn open(path: &Path) -> io::Result<Box<dyn BufRead>> {
+    if path == Path::new("-") {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    Ok(Box::new(BufReader::new(File::open(path)?)))
+}
+
+/// Downloads with curl, so https works without a TLS stack here. The session cookie goes in on
+/// stdin to keep it out of the process list.
+fn fetch(base_url: &str, session: &str, year: u32, day: u32) -> Result<String, InputError> {
+    let url = format!("{}/{year}/day/{day}/input", base_url.trim_end_matches('/'));
+    let failed = |reason: String| InputError::Fetch {
+        url: url.clone(),
+        reason,
+    };
+    let mut curl = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--header", "@-"])
+        .args(["--user-agent", "github.com/Isawan/advent-of-code runner"])
+        .arg(&url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| failed(format!("running curl: {err}")))?;
+    let mut stdin = curl.stdin.take().unwrap();
+    writeln!(stdin, "Cookie: session={session}").map_err(|err| failed(err.to_string()))?;
+    drop(stdin);
+    let output = curl
+        .wait_with_output()
+        .map_err(|err| failed(err.to_string()))?;
+    if !output.status.success() {
+        return Err(failed(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(|_| failed("input is not UTF-8".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{net::TcpListener, thread};
+
+    use super::*;
+
+    /// A scratch input directory for tests, removed again on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let dir = env::temp_dir().join(format!("aoc-{name}-{}", std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+
+        fn path(&self) -> &Path {
+            &self.0
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn inputs(dir: &Path, base_url: &str) -> Inputs {
+        Inputs {
+            dir: dir.to_path_buf(),
+            base_url: base_url.to_string(),
+            session: Some("abc123".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_variant() {
+        assert_eq!("".parse(), Ok(Variant::Real));
+        assert_eq!("example".parse(), Ok(Variant::Example(1)));
+        assert_eq!("example-3".parse(), Ok(Variant::Example(3)));
+        assert_eq!("mika".parse(), Ok(Variant::Named("mika".to_string())));
+        assert_eq!(
+            "example-one-cycle".parse(),
+            Ok(Variant::Named("example-one-cycle".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_find() {
+        let dir = TempDir::new("find");
+        for name in [
+            "day1_test.txt",
+            "day3-test2",
+            "day6-mika",
+            "day10-example-1",
+        ] {
+            fs::write(dir.path().join(name), "").unwrap();
+        }
+        let inputs = inputs(dir.path(), "http://127.0.0.1:9");
+        let found = |day, variant: &str| {
+            inputs
+                .find(day, &variant.parse().unwrap())
+                .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+        };
+        assert_eq!(found(1, "example").as_deref(), Some("day1_test.txt"));
+        assert_eq!(found(3, "example-2").as_deref(), Some("day3-test2"));
+        assert_eq!(found(6, "mika").as_deref(), Some("day6-mika"));
+        assert_eq!(found(10, "example").as_deref(), Some("day10-example-1"));
+        assert_eq!(found(1, "real"), None);
+        assert!(matches!(
+            inputs.read(2025, 1, &Variant::Example(2)),
+            Err(InputError::NotFound { day: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_fetch_and_cache() {
+        let dir = TempDir::new("fetch");
+        let server = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", server.local_addr().unwrap());
+        // answers one request, so a second read has to come from the cache
+        let stand_in = thread::spawn(move || {
+            let (mut stream, _) = server.accept().unwrap();
+            let request: Vec<String> = BufReader::new(&stream)
+                .lines()
+                .map(Result::unwrap)
+                .take_while(|line| !line.is_empty())
+                .collect();
+            let body = "1\n2\n3\n";
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request
+        });
+
+        let inputs = inputs(dir.path(), &base_url);
+        assert_eq!(inputs.read(2025, 7, &Variant::Real).unwrap(), "1\n2\n3\n");
+        let request = stand_in.join().unwrap();
+        assert_eq!(request[0], "GET /2025/day/7/input HTTP/1.1");
+        assert!(request.contains(&"Cookie: session=abc123".to_string()));
+        assert_eq!(inputs.read(2025, 7, &Variant::Real).unwrap(), "1\n2\n3\n");
+
+        let offline = Inputs {
+            session: None,
+            ..inputs
+        };
+        assert!(matches!(
+            offline.read(2025, 8, &Variant::Real),
+            Err(InputError::NoSession { day: 8 })
+        ));
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    cmp::{max, min},
+    fmt::{self, Display},
+    ops::{Range, RangeInclusive},
+};
+
+use num_traits::PrimInt;
+
+/// A set of integers kept as sorted, disjoint half-open ranges. Ranges that overlap or touch are
+/// merged, so two sets with the same members compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct IntervalSet<T> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self { ranges: Vec::new() }
+    }
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sorts and merges `(start, end)` pairs, dropping the empty ones.
+    fn normalize(mut ranges: Vec<(T, T)>) -> Self {
+        ranges.retain(|&(start, end)| start < end);
+        ranges.sort_unstable();
+        let mut merged: Vec<(T, T)> = Vec::with_capacity(ranges.len());
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some((_, last_end)) if start <= *last_end => *last_end = max(*last_end, end),
+                _ => merged.push((start, end)),
+            }
+        }
+        Self { ranges: merged }
+    }
+
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.is_empty() {
+            return;
+        }
+        let first = self.ranges.partition_point(|&(_, end)| end < range.start);
+        let last = self
+            .ranges
+            .partition_point(|&(start, _)| start <= range.end);
+        let merged = if first < last {
+            (
+                min(range.start, self.ranges[first].0),
+                max(range.end, self.ranges[last - 1].1),
+            )
+        } else {
+            (range.start, range.end)
+        };
+        self.ranges.splice(first..last, [merged]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let i = self.ranges.partition_point(|&(_, end)| end <= value);
+        self.ranges.get(i).is_some_and(|&(start, _)| start <= value)
+    }
+
+    /// The number of members, summed over all the ranges.
+    pub fn len(&self) -> T {
+        self.ranges
+            .iter()
+            .fold(T::zero(), |total, &(start, end)| total + (end - start))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|&(start, _)| start)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Range<T>> + '_ {
+        self.ranges.iter().map(|&(start, end)| start..end)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::normalize(self.ranges.iter().chain(&other.ranges).copied().collect())
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while let (Some(&(a_start, a_end)), Some(&(b_start, b_end))) =
+            (self.ranges.get(i), other.ranges.get(j))
+        {
+            let (start, end) = (max(a_start, b_start), min(a_end, b_end));
+            if start < end {
+                ranges.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for &(start, end) in &self.ranges {
+            while other.ranges.get(j).is_some_and(|&(_, e)| e <= start) {
+                j += 1;
+            }
+            let mut lo = start;
+            for &(s, e) in other.ranges[j..].iter().take_while(|&&(s, _)| s < end) {
+                if s > lo {
+                    ranges.push((lo, s));
+                }
+                lo = max(lo, e);
+            }
+            if lo < end {
+                ranges.push((lo, end));
+            }
+        }
+        Self { ranges }
+    }
+
+    /// The parts of `bounds` outside the set.
+    pub fn gaps(&self, bounds: Range<T>) -> Self {
+        Self::from(bounds).difference(self)
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.difference(other).is_empty()
+    }
+
+    /// Moves every member by `offset`.
+    pub fn shift(&self, offset: T) -> Self {
+        Self {
+            ranges: self
+                .ranges
+                .iter()
+                .map(|&(start, end)| (start + offset, end + offset))
+                .collect(),
+        }
+    }
+}
+
+impl<T: PrimInt> From<Range<T>> for IntervalSet<T> {
+    fn from(range: Range<T>) -> Self {
+        Self::normalize(vec![(range.start, range.end)])
+    }
+}
+
+impl<T: PrimInt> From<RangeInclusive<T>> for IntervalSet<T> {
+    fn from(range: RangeInclusive<T>) -> Self {
+        std::iter::once(range).collect()
+    }
+}
+
+impl<T: PrimInt> FromIterator<Range<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        Self::normalize(iter.into_iter().map(|r| (r.start, r.end)).collect())
+    }
+}
+
+/// Panics if a range ends at `T::MAX`, which has no half-open end.
+impl<T: PrimInt> FromIterator<RangeInclusive<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = RangeInclusive<T>>>(iter: I) -> Self {
+        Self::normalize(
+            iter.into_iter()
+                .filter(|r| !r.is_empty())
+                .map(|r| (*r.start(), *r.end() + T::one()))
+                .collect(),
+        )
+    }
+}
+
+impl<T: Display> Display for IntervalSet<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (start, end)) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{start}..{end}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(ranges: &[Range<i64>]) -> IntervalSet<i64> {
+        ranges.iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_merge() {
+        let s = set(&[10..15, 0..3, 3..5, 12..22, 30..30]);
+        assert_eq!(s.iter().collect::<Vec<_>>(), vec![0..5, 10..22]);
+        assert_eq!(s.len(), 17);
+        assert_eq!(s.min(), Some(0));
+        assert_eq!(s.to_string(), "0..5, 10..22");
+        assert_eq!(s, [0..=4, 10..=21].into_iter().collect());
+
+        let mut inserted = IntervalSet::new();
+        for r in [10..15, 0..3, 30..30, 3..5, 12..22] {
+            inserted.insert(r);
+        }
+        assert_eq!(inserted, s);
+        inserted.insert(4..11);
+        assert_eq!(inserted, IntervalSet::from(0..22));
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[0..5, 10..22]);
+        assert!(s.contains(0) && s.contains(4) && s.contains(21));
+        assert!(!s.contains(-1) && !s.contains(5) && !s.contains(22));
+        assert!(!IntervalSet::new().contains(0));
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a = set(&[0..5, 10..22]);
+        let b = set(&[1..2, 4..11, 20..30]);
+        assert_eq!(a.union(&b), IntervalSet::from(0..30));
+        assert_eq!(a.intersection(&b), set(&[1..2, 4..5, 10..11, 20..22]));
+        assert_eq!(a.difference(&b), set(&[0..1, 2..4, 11..20]));
+        assert_eq!(b.difference(&a), set(&[5..10, 22..30]));
+        assert_eq!(a.gaps(-3..25), set(&[-3..0, 5..10, 22..25]));
+        assert_eq!(a.shift(-10), set(&[-10..-5, 0..12]));
+        assert!(set(&[1..3, 12..20]).is_subset(&a));
+        assert!(!b.is_subset(&a));
+    }
+
+    #[test]
+    fn test_against_members() {
+        let a = set(&[0..5, 8..9, 12..20, 25..40]);
+        let b = set(&[3..13, 15..16, 18..26, 39..45]);
+        let (union, both, only_a) = (a.union(&b), a.intersection(&b), a.difference(&b));
+        for x in -5..50 {
+            assert_eq!(union.contains(x), a.contains(x) || b.contains(x));
+            assert_eq!(both.contains(x), a.contains(x) && b.contains(x));
+            assert_eq!(only_a.contains(x), a.contains(x) && !b.contains(x));
+        }
+    }
+}
@@ -1 +1,13 @@
+pub mod answers;
+pub mod arith;
+pub mod bench;
+pub mod cycle;
 pub mod grid;
+pub mod input;
+pub mod interval;
+pub mod numtheory;
+pub mod parse;
+pub mod polygon;
+pub mod runner;
+pub mod search;
+pub mod solution;
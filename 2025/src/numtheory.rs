@@ -0,0 +1,169 @@
+use num_traits::{Euclid, PrimInt, Signed};
+
+use crate::arith::ArithError;
+
+pub fn gcd<T: PrimInt>(a: T, b: T) -> T {
+    if b.is_zero() { a } else { gcd(b, a % b) }
+}
+
+pub fn lcm<T: PrimInt>(a: T, b: T) -> Result<T, ArithError> {
+    if a.is_zero() || b.is_zero() {
+        return Ok(T::zero());
+    }
+    (a / gcd(a, b)).checked_mul(&b).ok_or(ArithError::Overflow)
+}
+
+/// The least common multiple of all the numbers, 1 if there are none.
+pub fn lcm_all<T: PrimInt>(numbers: impl IntoIterator<Item = T>) -> Result<T, ArithError> {
+    numbers
+        .into_iter()
+        .try_fold(T::one(), |total, n| lcm(total, n))
+}
+
+/// Gives `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+pub fn extended_gcd<T: PrimInt + Signed + Euclid>(a: T, b: T) -> (T, T, T) {
+    if b.is_zero() {
+        (a, T::one(), T::zero())
+    } else {
+        let (g, x, y) = extended_gcd(b, a % b);
+        (g, y, x - a / b * y)
+    }
+}
+
+/// The `x` in `0..m` with `a * x = 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: PrimInt + Signed + Euclid>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(&m), m);
+    g.is_one().then(|| x.rem_euclid(&m))
+}
+
+/// `a * b % m` for `a` and `b` in `0..m`. It doubles and adds rather than multiplies, so it never
+/// overflows however close `m` is to the largest `T`.
+pub fn mul_mod<T: PrimInt>(mut a: T, mut b: T, m: T) -> T {
+    let add = |x: T, y: T| if x >= m - y { x - (m - y) } else { x + y };
+    let mut product = T::zero();
+    while !b.is_zero() {
+        if (b & T::one()).is_one() {
+            product = add(product, a);
+        }
+        a = add(a, a);
+        b = b >> 1;
+    }
+    product
+}
+
+/// Solves `x = a1 (mod m1)` and `x = a2 (mod m2)` as `x = a (mod lcm(m1, m2))`. The moduli need
+/// not be coprime, in which case there may be no solution. Everything stays below the lcm, so
+/// there is also no answer when the lcm itself doesn't fit in `T`.
+pub fn crt<T: PrimInt + Signed + Euclid>((a1, m1): (T, T), (a2, m2): (T, T)) -> Option<(T, T)> {
+    let (a1, a2) = (a1.rem_euclid(&m1), a2.rem_euclid(&m2));
+    let (g, p, _) = extended_gcd(m1, m2);
+    let diff = a2 - a1;
+    if !(diff % g).is_zero() {
+        return None;
+    }
+    let step = m2 / g;
+    let k = mul_mod((diff / g).rem_euclid(&step), p.rem_euclid(&step), step);
+    let lcm = m1.checked_mul(&step)?;
+    // k < step, so this is below the lcm
+    Some((a1 + k * m1, lcm))
+}
+
+/// Solves a whole system of congruences `x = a (mod m)`, giving `(0, 1)` for an empty one.
+pub fn crt_all<T: PrimInt + Signed + Euclid>(
+    congruences: impl IntoIterator<Item = (T, T)>,
+) -> Option<(T, T)> {
+    congruences.into_iter().try_fold((T::zero(), T::one()), crt)
+}
+
+/// `base.pow(exp) % modulus` for a non-negative `base` and `exp`, without overflowing on the way.
+pub fn mod_pow<T: PrimInt>(base: T, mut exp: T, modulus: T) -> T {
+    let mut base = base % modulus;
+    let mut result = T::one() % modulus;
+    while !exp.is_zero() {
+        if (exp & T::one()).is_one() {
+            result = mul_mod(result, base, modulus);
+        }
+        base = mul_mod(base, base, modulus);
+        exp = exp >> 1;
+    }
+    result
+}
+
+/// How many multiples of a positive `m` lie between `low` and `high`, both included.
+pub fn multiples_between<T: PrimInt + Signed + Euclid>(m: T, low: T, high: T) -> T {
+    if low > high {
+        return T::zero();
+    }
+    high.div_euclid(&m) - (low - T::one()).div_euclid(&m)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12u64, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), Ok(12));
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), Ok(60));
+        assert_eq!(lcm_all(Vec::<u32>::new()), Ok(1));
+        assert_eq!(lcm_all([1u64 << 40, 3 << 30, 5 << 20]), Ok(15 << 40));
+        assert_eq!(lcm_all([u64::MAX, 2]), Err(ArithError::Overflow));
+    }
+
+    #[test]
+    fn test_inverse() {
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(4i64, 6), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt((2, 3), (3, 5)), Some((8, 15)));
+        assert_eq!(crt((3, 4), (1, 6)), Some((7, 12)));
+        assert_eq!(crt((0, 4), (1, 6)), None);
+        assert_eq!(crt((0, 1), (5, 7)), Some((5, 7)));
+        assert_eq!(crt_all([(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt_all(Vec::<(i64, i64)>::new()), Some((0, 1)));
+        let big = (1i128 << 61) - 1;
+        assert_eq!(
+            crt((5, big), (6, big + 2)),
+            Some((
+                2658455991569831744654692615953842181,
+                5316911983139663491615228241121378303
+            ))
+        );
+        // the lcm fits in an i64 though the products along the way wouldn't
+        let prime = (1i64 << 61) - 1;
+        assert_eq!(
+            crt((0, 3), (prime - 1, prime)),
+            Some((2305843009213693950, 6917529027641081853))
+        );
+        assert_eq!(crt((-1, 3), (1, 5)), Some((11, 15)));
+        assert_eq!(crt((5, prime), (6, prime + 2)), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, u64::MAX, (1 << 61) - 1), 4747561509943);
+        assert_eq!(mod_pow(i64::MAX - 1, 3, i64::MAX), i64::MAX - 1);
+        assert_eq!(mod_pow(7u8, 255, 251), mod_pow(7u64, 255, 251) as u8);
+        assert_eq!(mul_mod(u64::MAX - 1, u64::MAX - 2, u64::MAX), 2);
+    }
+
+    #[test]
+    fn test_multiples_between() {
+        assert_eq!(multiples_between(100, 51, 300), 3);
+        assert_eq!(multiples_between(100, -250, -1), 2);
+        assert_eq!(multiples_between(100, -200, 49), 3);
+        assert_eq!(multiples_between(100, 1, 99), 0);
+        assert_eq!(multiples_between(100, 5, 4), 0);
+    }
+}
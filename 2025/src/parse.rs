@@ -0,0 +1,247 @@
+use std::{
+    cmp::Ordering,
+    io::{self, BufRead},
+    process,
+};
+
+use nom::{
+    Compare, InputLength, InputTake,
+    error::{ErrorKind, FromExternalError},
+};
+use thiserror::Error;
+
+/// Input that doesn't match what a day's parser expects, pointing at where it went wrong.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("input line {line}, column {column}: expected {expected}, got {found}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// `found` has to be a slice of `text`, the lines and columns are counted from its start.
+    pub fn new(text: &str, found: &str, expected: impl Into<String>) -> Self {
+        let offset = (found.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        assert!(
+            offset <= text.len(),
+            "{found:?} is not part of the parsed text"
+        );
+        let before = &text[..offset];
+        let found = match found.lines().next() {
+            None => "end of input",
+            Some("") => "end of line",
+            Some(line) => line,
+        };
+        Self {
+            line: before.matches('\n').count() + 1,
+            column: before.rsplit('\n').next().unwrap().chars().count() + 1,
+            expected: expected.into(),
+            found: found.to_string(),
+        }
+    }
+
+    /// Moves an error from a parser that only saw one line onto that line of the input.
+    pub fn on_line(self, line: usize) -> Self {
+        Self { line, ..self }
+    }
+}
+
+/// A nom error that keeps what the parser expected, so the [`ParseError`] can name the tag or
+/// character that was missing rather than just the kind of parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expected<I> {
+    pub input: I,
+    pub expected: String,
+}
+
+/// nom's `IResult`, with an error that knows what was expected.
+pub type IResult<I, O> = nom::IResult<I, O, Expected<I>>;
+
+impl<I: InputLength> nom::error::ParseError<I> for Expected<I> {
+    fn from_error_kind(input: I, kind: ErrorKind) -> Self {
+        let expected = kind.description().to_lowercase();
+        Self { input, expected }
+    }
+
+    fn append(_: I, _: ErrorKind, other: Self) -> Self {
+        other
+    }
+
+    fn from_char(input: I, c: char) -> Self {
+        let expected = format!("{c:?}");
+        Self { input, expected }
+    }
+
+    /// Keeps whichever alternative got further, or lists both if they failed at the same spot.
+    fn or(self, other: Self) -> Self {
+        match self.input.input_len().cmp(&other.input.input_len()) {
+            Ordering::Less => self,
+            Ordering::Greater => other,
+            Ordering::Equal => Self {
+                expected: format!("{} or {}", self.expected, other.expected),
+                ..other
+            },
+        }
+    }
+}
+
+impl<I: InputLength, E> FromExternalError<I, E> for Expected<I> {
+    fn from_external_error(input: I, kind: ErrorKind, _: E) -> Self {
+        nom::error::ParseError::from_error_kind(input, kind)
+    }
+}
+
+impl<I> From<nom::error::Error<I>> for Expected<I> {
+    fn from(err: nom::error::Error<I>) -> Self {
+        Self {
+            input: err.input,
+            expected: err.code.description().to_lowercase(),
+        }
+    }
+}
+
+/// nom's `tag`, failing with the text it was looking for.
+pub fn tag<'t, I>(tag: &'t str) -> impl Fn(I) -> IResult<I, I> + 't
+where
+    I: InputTake + InputLength + Compare<&'t str> + Clone,
+{
+    move |input: I| {
+        nom::bytes::complete::tag(tag)(input.clone()).map_err(|_: nom::Err<Expected<I>>| {
+            let expected = format!("{tag:?}");
+            nom::Err::Error(Expected { input, expected })
+        })
+    }
+}
+
+/// Turns the result of a nom parser run over `input` into a [`ParseError`]. Whatever the parser
+/// leaves over has to be whitespace.
+pub fn from_nom<'a, O, E: Into<Expected<&'a str>>>(
+    input: &'a str,
+    result: nom::IResult<&'a str, O, E>,
+) -> Result<O, ParseError> {
+    match result {
+        Ok((rest, output)) if rest.trim().is_empty() => Ok(output),
+        Ok((rest, _)) => Err(ParseError::new(input, rest.trim_start(), "end of input")),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            let err = err.into();
+            Err(ParseError::new(input, err.input, err.expected))
+        }
+        Err(nom::Err::Incomplete(_)) => {
+            Err(ParseError::new(input, &input[input.len()..], "more input"))
+        }
+    }
+}
+
+/// Parses a stream one line at a time, so inputs never have to be held in memory whole.
+pub fn parse_lines<T>(
+    reader: impl BufRead,
+    mut parse: impl FnMut(&str) -> Result<T, ParseError>,
+) -> impl Iterator<Item = Result<T, ParseError>> {
+    reader.lines().enumerate().map(move |(i, line)| match line {
+        Ok(line) => parse(&line).map_err(|err| err.on_line(i + 1)),
+        Err(err) => Err(ParseError {
+            line: i + 1,
+            column: 1,
+            expected: match err.kind() {
+                io::ErrorKind::InvalidData => "UTF-8 text",
+                _ => "readable input",
+            }
+            .to_string(),
+            found: err.to_string(),
+        }),
+    })
+}
+
+/// For the days' own `main`: reports the error and exits rather than panicking.
+pub fn or_exit<T>(result: Result<T, ParseError>) -> T {
+    result.unwrap_or_else(|err| {
+        eprintln!("error: {err}");
+        process::exit(1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use nom::{
+        branch::alt,
+        character::complete::{char, u32},
+        combinator::map,
+        sequence::{preceded, terminated},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = "R10\nL5\nX7\n";
+        let err = ParseError::new(input, &input[7..8], "R or L");
+        assert_eq!(
+            err.to_string(),
+            "input line 3, column 1: expected R or L, got X"
+        );
+        assert_eq!((err.line, err.column), (3, 1));
+        assert_eq!(
+            ParseError::new(input, &input[10..], "R or L").found,
+            "end of input"
+        );
+        assert_eq!(ParseError::new("R", "R", "a rotation").on_line(42).line, 42);
+    }
+
+    #[test]
+    fn test_from_nom() {
+        let value = |input| preceded(tag("value: "), u32)(input);
+        let input = "value: 12\n";
+        assert_eq!(from_nom(input, value(input)), Ok(12));
+        let input = "value 12";
+        let err = from_nom(input, value(input)).unwrap_err();
+        assert_eq!((err.line, err.column), (1, 1));
+        assert_eq!(err.expected, "\"value: \"");
+        assert_eq!(err.found, "value 12");
+
+        let input = "value: 12x";
+        let err = from_nom(input, value(input)).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (10, "end of input"));
+        let input = "value: 12";
+        let err = from_nom(input, terminated(value, char(';'))(input)).unwrap_err();
+        assert_eq!(
+            (err.expected.as_str(), err.found.as_str()),
+            ("';'", "end of input")
+        );
+        let input = "value: x";
+        let err = from_nom(input, alt((value, map(tag("none"), |_| 0)))(input)).unwrap_err();
+        assert_eq!((err.column, err.expected.as_str()), (8, "digit"));
+        let input = "x";
+        let err = from_nom(input, alt((tag("some"), tag("none")))(input)).unwrap_err();
+        assert_eq!(err.expected, "\"some\" or \"none\"");
+
+        let plain: nom::IResult<&str, &str> = nom::bytes::complete::tag("a")(input);
+        assert_eq!(from_nom(input, plain).unwrap_err().expected, "tag");
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        let number = |line: &str| {
+            line.parse::<u32>()
+                .map_err(|_| ParseError::new(line, line, "a number"))
+        };
+        let parsed: Result<Vec<_>, _> = parse_lines("1\n2\n3\n".as_bytes(), number).collect();
+        assert_eq!(parsed, Ok(vec![1, 2, 3]));
+        let err = parse_lines("1\n2\nthree\n".as_bytes(), number)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "input line 3, column 1: expected a number, got three"
+        );
+    }
+
+    #[test]
+    fn test_unreadable_lines() {
+        let number = |line: &str| Ok(line.to_string());
+        let bytes: &[u8] = b"1\n\xff\n";
+        let err = parse_lines(bytes, number).find_map(Result::err).unwrap();
+        assert_eq!((err.line, err.expected.as_str()), (2, "UTF-8 text"));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::numtheory::gcd;
+
+/// A polygon is its lattice vertices in order around the boundary. Extra vertices along a straight
+/// edge are fine, so a grid loop can be passed tile by tile.
+fn edges(vertices: &[(i64, i64)]) -> impl Iterator<Item = ((i64, i64), (i64, i64))> + '_ {
+    vertices
+        .iter()
+        .copied()
+        .zip(vertices.iter().copied().cycle().skip(1))
+}
+
+/// Twice the area, by the shoelace formula, which keeps it a whole number.
+pub fn double_area(vertices: &[(i64, i64)]) -> i64 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| x1 * y2 - x2 * y1)
+        .sum::<i64>()
+        .abs()
+}
+
+pub fn perimeter(vertices: &[(i64, i64)]) -> f64 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| ((x2 - x1) as f64).hypot((y2 - y1) as f64))
+        .sum()
+}
+
+/// The lattice points on the edges.
+pub fn boundary_points(vertices: &[(i64, i64)]) -> i64 {
+    edges(vertices)
+        .map(|((x1, y1), (x2, y2))| gcd((x2 - x1).abs(), (y2 - y1).abs()))
+        .sum()
+}
+
+/// The lattice points strictly inside, by Pick's theorem: `A = I + B / 2 - 1`.
+pub fn interior_points(vertices: &[(i64, i64)]) -> i64 {
+    (double_area(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+/// Whether `point` is strictly inside; points on an edge are not.
+pub fn contains(vertices: &[(i64, i64)], (px, py): (i64, i64)) -> bool {
+    let mut inside = false;
+    for ((x1, y1), (x2, y2)) in edges(vertices) {
+        let cross = (x2 - x1) * (py - y1) - (y2 - y1) * (px - x1);
+        let on_edge = cross == 0
+            && (x1.min(x2)..=x1.max(x2)).contains(&px)
+            && (y1.min(y2)..=y1.max(y2)).contains(&py);
+        if on_edge {
+            return false;
+        }
+        // count the edges crossing a ray going right from the point; the cross product has the
+        // sign of the edge's direction when the crossing is to the right
+        if (y1 > py) != (y2 > py) && (cross > 0) == (y2 > y1) {
+            inside = !inside;
+        }
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_square() {
+        let square = [(0, 0), (4, 0), (4, 4), (0, 4)];
+        assert_eq!(double_area(&square), 32);
+        assert_eq!(perimeter(&square), 16.0);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert!(contains(&square, (1, 1)));
+        assert!(!contains(&square, (0, 2)));
+        assert!(!contains(&square, (4, 4)));
+        assert!(!contains(&square, (5, 2)));
+    }
+
+    #[test]
+    fn test_against_points() {
+        // an L shape, clockwise, with a vertex in the middle of an edge
+        let shape = [(0, 0), (0, 6), (3, 6), (3, 3), (6, 3), (6, 0), (3, 0)];
+        assert_eq!(double_area(&shape), 54);
+        assert_eq!(boundary_points(&shape), 24);
+        let inside = (-1..8)
+            .flat_map(|x| (-1..8).map(move |y| (x, y)))
+            .filter(|&p| contains(&shape, p))
+            .count();
+        assert_eq!(inside as i64, interior_points(&shape));
+        assert_eq!(inside, 16);
+
+        let triangle = [(0, 0), (5, 2), (1, 4)];
+        assert_eq!(double_area(&triangle), 18);
+        assert_eq!(boundary_points(&triangle), 4);
+        assert_eq!(interior_points(&triangle), 8);
+        assert!(contains(&triangle, (1, 1)) && contains(&triangle, (2, 3)));
+        assert!(!contains(&triangle, (3, 1)) && !contains(&triangle, (3, 3)));
+    }
+}
@@ -0,0 +1,528 @@
+use std::{
+    env,
+    ffi::OsString,
+    fs::read_to_string,
+    io,
+    panic::{AssertUnwindSafe, catch_unwind},
+    path::{self, Path, PathBuf},
+    process::{self, ExitCode},
+    thread,
+};
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::{
+    answers::{self, Expected},
+    bench::{Settings, Stage, Stats},
+    input::{self, Inputs, Variant},
+    parse::ParseError,
+    solution::Day,
+};
+
+const INPUT_DIR: &str = "input";
+const ANSWERS: &str = "answers.txt";
+
+#[derive(Parser, Debug, Clone)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Years to use, like `2022` or `2021,2022`. Defaults to the runner's own year, any other is
+    /// handed to that year's runner
+    #[arg(long, global = true, value_delimiter = ',')]
+    year: Vec<u32>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug, Clone)]
+enum Command {
+    /// Run one day, or every registered day of each year
+    Run(RunArgs),
+    /// Compare answers against each year's answers manifest
+    Check(CheckArgs),
+    /// Time parsing and each part of one day, or every registered day of each year
+    Bench(BenchArgs),
+}
+
+#[derive(Args, Debug, Clone)]
+struct RunArgs {
+    #[arg(long, short)]
+    day: Option<u32>,
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+    /// Input file for a single day, instead of looking it up in the input directory. `-` reads
+    /// stdin
+    #[arg(long, short, requires = "day", conflicts_with = "variant")]
+    path: Option<PathBuf>,
+    /// Which input to use: `real`, `example`, `example-<N>` or a name like `mika`
+    #[arg(long, default_value = "real")]
+    variant: Variant,
+    /// Missing real inputs are downloaded here when `AOC_SESSION` is set
+    #[arg(long, default_value = INPUT_DIR)]
+    input_dir: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+struct CheckArgs {
+    #[arg(long, short)]
+    day: Option<u32>,
+    /// Skip answers for the real `day<N>` inputs, which can take minutes to solve
+    #[arg(long)]
+    examples: bool,
+    #[arg(long, default_value = ANSWERS)]
+    answers: PathBuf,
+    #[arg(long, default_value = INPUT_DIR)]
+    input_dir: PathBuf,
+}
+
+#[derive(Args, Debug, Clone)]
+struct BenchArgs {
+    #[arg(long, short)]
+    day: Option<u32>,
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..=2))]
+    part: Option<u32>,
+    /// Untimed runs of each stage before measuring
+    #[arg(long, default_value_t = 1)]
+    warmup: u32,
+    #[arg(long, short, default_value_t = 10)]
+    iterations: u32,
+    /// Print one JSON object per timed stage instead of a table
+    #[arg(long)]
+    json: bool,
+    /// Input file for a single day, instead of looking it up in the input directory. `-` reads
+    /// stdin
+    #[arg(long, short, requires = "day", conflicts_with = "variant")]
+    path: Option<PathBuf>,
+    /// Which input to use: `real`, `example`, `example-<N>` or a name like `mika`
+    #[arg(long, default_value = "real")]
+    variant: Variant,
+    /// Missing real inputs are downloaded here when `AOC_SESSION` is set
+    #[arg(long, default_value = INPUT_DIR)]
+    input_dir: PathBuf,
+}
+
+enum Outcome {
+    Solved,
+    Skipped,
+    Failed,
+}
+
+/// Entry point for a year's `aoc` binary. The year crates use different editions and dependencies,
+/// so they can't be linked into one binary; a `--year` other than the runner's own is run by that
+/// year's `aoc` through cargo instead.
+pub fn main(year: u32, days: &[Day]) -> ExitCode {
+    let cli = Cli::parse();
+    let years = match cli.year.as_slice() {
+        [] => vec![year],
+        years => years.to_vec(),
+    };
+    let codes: Vec<ExitCode> = years
+        .into_iter()
+        .map(|requested| match &cli.command {
+            _ if requested != year => dispatch(requested, &cli.command),
+            Command::Run(args) => run(year, days, args),
+            Command::Check(args) => check(year, days, args),
+            Command::Bench(args) => bench(year, days, args),
+        })
+        .collect();
+    if codes.contains(&ExitCode::from(2)) {
+        ExitCode::from(2)
+    } else if codes.iter().all(|&code| code == ExitCode::SUCCESS) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Year crates sit next to each other, named after their year.
+fn year_dir(year: u32) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .unwrap_or(Path::new(".."))
+        .join(year.to_string())
+}
+
+fn dispatch(year: u32, command: &Command) -> ExitCode {
+    let dir = year_dir(year);
+    let manifest = dir.join("Cargo.toml");
+    if !manifest.is_file() {
+        eprintln!("error: no crate for {year} at {}", dir.display());
+        return ExitCode::from(2);
+    }
+    let args = match forward(command, &dir) {
+        Ok(args) => args,
+        Err(err) => {
+            eprintln!("error: {year}: {err}");
+            return ExitCode::from(2);
+        }
+    };
+    let mut cargo = process::Command::new(env::var_os("CARGO").unwrap_or("cargo".into()));
+    cargo.args(["run", "--quiet", "--bin", "aoc", "--manifest-path"]);
+    cargo.arg(&manifest);
+    if !cfg!(debug_assertions) {
+        cargo.arg("--release");
+    }
+    match cargo.arg("--").args(args).status() {
+        Ok(status) => match status.code() {
+            Some(code) => ExitCode::from(u8::try_from(code).unwrap_or(1)),
+            None => ExitCode::FAILURE,
+        },
+        Err(err) => {
+            eprintln!("error: {year}: running cargo: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// The command line for another year's runner. Paths given here are made absolute, and the
+/// default input directory and manifest become the ones in that year's crate.
+fn forward(command: &Command, dir: &Path) -> io::Result<Vec<OsString>> {
+    fn given(path: &Path, default: &str, dir: &Path) -> io::Result<PathBuf> {
+        if path == Path::new(default) {
+            Ok(dir.join(default))
+        } else {
+            path::absolute(path)
+        }
+    }
+
+    let (name, day, input_dir) = match command {
+        Command::Run(args) => ("run", args.day, &args.input_dir),
+        Command::Check(args) => ("check", args.day, &args.input_dir),
+        Command::Bench(args) => ("bench", args.day, &args.input_dir),
+    };
+    let mut forwarded: Vec<OsString> = vec![name.into()];
+    let mut flag = |flag: &str, value: OsString| forwarded.extend([flag.into(), value]);
+    if let Some(day) = day {
+        flag("--day", day.to_string().into());
+    }
+    flag("--input-dir", given(input_dir, INPUT_DIR, dir)?.into());
+    let (part, path, variant) = match command {
+        Command::Run(args) => (args.part, &args.path, &args.variant),
+        Command::Bench(args) => {
+            flag("--warmup", args.warmup.to_string().into());
+            flag("--iterations", args.iterations.to_string().into());
+            (args.part, &args.path, &args.variant)
+        }
+        Command::Check(args) => {
+            flag("--answers", given(&args.answers, ANSWERS, dir)?.into());
+            if args.examples {
+                forwarded.push("--examples".into());
+            }
+            return Ok(forwarded);
+        }
+    };
+    if let Some(part) = part {
+        flag("--part", part.to_string().into());
+    }
+    match path.as_deref() {
+        Some(path) if path == Path::new("-") => flag("--path", "-".into()),
+        Some(path) => flag("--path", path::absolute(path)?.into()),
+        None => flag("--variant", variant.to_string().into()),
+    }
+    if matches!(command, Command::Bench(args) if args.json) {
+        forwarded.push("--json".into());
+    }
+    Ok(forwarded)
+}
+
+fn run(year: u32, days: &[Day], args: &RunArgs) -> ExitCode {
+    let Some(selected) = select(year, days, args.day) else {
+        return ExitCode::from(2);
+    };
+    if let (Some(path), [day]) = (args.path.as_deref(), selected.as_slice()) {
+        return match run_path(year, day, args.part, path) {
+            Outcome::Failed => ExitCode::FAILURE,
+            _ => ExitCode::SUCCESS,
+        };
+    }
+
+    let inputs = Inputs::from_env(&args.input_dir);
+    let given = args.path.as_deref().map(read_path);
+    let mut failed = false;
+    for day in selected {
+        let outcome = match read_input(
+            year,
+            day,
+            given.as_ref(),
+            &inputs,
+            &args.variant,
+            args.day.is_some(),
+        ) {
+            Ok(input) => run_day(year, day, args.part, |p| {
+                catch_unwind(|| day.solver.solve(&input, p))
+            }),
+            Err(outcome) => outcome,
+        };
+        failed |= matches!(outcome, Outcome::Failed);
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn select(year: u32, days: &[Day], day: Option<u32>) -> Option<Vec<&Day>> {
+    let selected: Vec<&Day> = days
+        .iter()
+        .filter(|d| day.is_none_or(|day| d.day == day))
+        .collect();
+    if let (Some(day), true) = (day, selected.is_empty()) {
+        eprintln!("error: no solution registered for {year} day {day}");
+        return None;
+    }
+    Some(selected)
+}
+
+/// A day with a single solution reads `--path` as a stream when it can, so stdin or a huge input
+/// is never held whole. Otherwise the stream is read to the end and solved as usual.
+fn run_path(year: u32, day: &Day, part: Option<u32>, path: &Path) -> Outcome {
+    let mut reader = match input::open(path) {
+        Ok(reader) => reader,
+        Err(err) => {
+            eprintln!("error: {}: {}: {err}", day.label(year), path.display());
+            return Outcome::Failed;
+        }
+    };
+    match catch_unwind(AssertUnwindSafe(|| day.solver.stream(&mut reader))) {
+        Ok(Some(answers)) => run_day(year, day, part, |p| {
+            Ok(answers
+                .clone()
+                .map(|answers| answers[p as usize - 1].clone()))
+        }),
+        Ok(None) => match io::read_to_string(reader) {
+            Ok(input) => run_day(year, day, part, |p| {
+                catch_unwind(|| day.solver.solve(&input, p))
+            }),
+            Err(err) => {
+                eprintln!("error: {}: {}: {err}", day.label(year), path.display());
+                Outcome::Failed
+            }
+        },
+        Err(_) => {
+            eprintln!("{}: failed", day.label(year));
+            Outcome::Failed
+        }
+    }
+}
+
+/// Reads `--path` once up front, as stdin can't be read again for each solution of the day.
+fn read_path(path: &Path) -> Result<String, String> {
+    input::open(path)
+        .and_then(io::read_to_string)
+        .map_err(|err| format!("{}: {err}", path.display()))
+}
+
+/// Missing inputs are only an error when the day was asked for explicitly.
+fn read_input(
+    year: u32,
+    day: &Day,
+    given: Option<&Result<String, String>>,
+    inputs: &Inputs,
+    variant: &Variant,
+    explicit: bool,
+) -> Result<String, Outcome> {
+    let input = match given {
+        Some(given) => given.clone(),
+        None => inputs
+            .read(year, day.day, variant)
+            .map_err(|err| err.to_string()),
+    };
+    input.map_err(|err| {
+        if explicit {
+            eprintln!("error: {}: {err}", day.label(year));
+            Outcome::Failed
+        } else {
+            eprintln!("{}: skipped, {err}", day.label(year));
+            Outcome::Skipped
+        }
+    })
+}
+
+fn run_day(
+    year: u32,
+    day: &Day,
+    part: Option<u32>,
+    answer: impl Fn(u32) -> thread::Result<Result<String, ParseError>>,
+) -> Outcome {
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![1, 2],
+    };
+    let mut outcome = Outcome::Solved;
+    for p in parts {
+        let label = format!("{} part {p}", day.label(year));
+        match answer(p) {
+            Ok(Ok(answer)) if answer.contains('\n') => println!("{label}:\n{answer}"),
+            Ok(Ok(answer)) => println!("{label}: {answer}"),
+            Ok(Err(err)) => {
+                eprintln!("error: {label}: {err}");
+                outcome = Outcome::Failed;
+            }
+            Err(_) => {
+                eprintln!("{label}: failed");
+                outcome = Outcome::Failed;
+            }
+        }
+    }
+    outcome
+}
+
+fn check(year: u32, days: &[Day], args: &CheckArgs) -> ExitCode {
+    let answers = match read_to_string(&args.answers)
+        .map_err(|err| err.to_string())
+        .and_then(|manifest| answers::parse(&manifest).map_err(|err| err.to_string()))
+    {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("error: {}: {err}", args.answers.display());
+            return ExitCode::from(2);
+        }
+    };
+
+    let (mut matched, mut checked) = (0, 0);
+    for answer in answers
+        .iter()
+        .filter(|a| args.day.is_none_or(|day| a.day == day))
+        .filter(|a| !args.examples || a.input != format!("day{}", a.day))
+    {
+        let solvers: Vec<&Day> = days.iter().filter(|d| d.day == answer.day).collect();
+        if solvers.is_empty() {
+            eprintln!("{year} day {}: no solution registered", answer.day);
+            checked += 1;
+            continue;
+        }
+        let expected = match &answer.expected {
+            Expected::Inline(expected) => Ok(expected.clone()),
+            Expected::File(file) => read_to_string(args.input_dir.join(file)),
+        };
+        let input = read_to_string(args.input_dir.join(&answer.input));
+        let (expected, input) = match (expected, input) {
+            (Ok(expected), Ok(input)) => (expected, input),
+            (Err(err), _) | (_, Err(err)) => {
+                eprintln!("{year} day {}: {err}", answer.day);
+                checked += 1;
+                continue;
+            }
+        };
+
+        for day in solvers {
+            checked += 1;
+            let label = format!(
+                "{} part {} on {}",
+                day.label(year),
+                answer.part,
+                answer.input
+            );
+            match catch_unwind(|| day.solver.solve(&input, answer.part)) {
+                Ok(Ok(got)) if got.trim_end() == expected.trim_end() => matched += 1,
+                Ok(Ok(got)) => {
+                    println!(
+                        "{label}: expected {}, got {}",
+                        block(&expected),
+                        block(&got)
+                    );
+                }
+                Ok(Err(err)) => eprintln!("{label}: {err}"),
+                Err(_) => eprintln!("{label}: failed"),
+            }
+        }
+    }
+
+    println!("{year}: {matched} of {checked} answers match");
+    if matched == checked {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn bench(year: u32, days: &[Day], args: &BenchArgs) -> ExitCode {
+    let Some(selected) = select(year, days, args.day) else {
+        return ExitCode::from(2);
+    };
+    let settings = Settings {
+        warmup: args.warmup,
+        iterations: args.iterations,
+    };
+    let stages = match args.part {
+        Some(1) => vec![Stage::Parse, Stage::Part1],
+        Some(_) => vec![Stage::Parse, Stage::Part2],
+        None => vec![Stage::Parse, Stage::Part1, Stage::Part2],
+    };
+
+    let inputs = Inputs::from_env(&args.input_dir);
+    let given = args.path.as_deref().map(read_path);
+    let mut failed = false;
+    for day in selected {
+        let input = match read_input(
+            year,
+            day,
+            given.as_ref(),
+            &inputs,
+            &args.variant,
+            args.day.is_some(),
+        ) {
+            Ok(input) => input,
+            Err(outcome) => {
+                failed |= matches!(outcome, Outcome::Failed);
+                continue;
+            }
+        };
+        for &stage in &stages {
+            match catch_unwind(|| day.solver.bench(&input, stage, &settings)) {
+                Ok(Ok(Some(stats))) if args.json => {
+                    println!("{}", json(year, day, stage, &settings, &stats))
+                }
+                Ok(Ok(Some(stats))) => println!(
+                    "{} {stage}: min {:.2?}, median {:.2?}, mean {:.2?}",
+                    day.label(year),
+                    stats.min,
+                    stats.median,
+                    stats.mean
+                ),
+                Ok(Ok(None)) => {}
+                Ok(Err(err)) => {
+                    eprintln!("error: {}: {err}", day.label(year));
+                    failed = true;
+                    break;
+                }
+                Err(_) => {
+                    eprintln!("{} {stage}: failed", day.label(year));
+                    failed = true;
+                    break;
+                }
+            }
+        }
+    }
+    if failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
+
+fn json(year: u32, day: &Day, stage: Stage, settings: &Settings, stats: &Stats) -> String {
+    let variant = match day.variant {
+        Some(name) => format!("{name:?}"),
+        None => "null".to_string(),
+    };
+    format!(
+        "{{\"year\":{year},\"day\":{},\"variant\":{variant},\"stage\":\"{stage}\",\
+         \"warmup\":{},\"iterations\":{},\"min_ns\":{},\"median_ns\":{},\"mean_ns\":{}}}",
+        day.day,
+        settings.warmup,
+        settings.iterations,
+        stats.min.as_nanos(),
+        stats.median.as_nanos(),
+        stats.mean.as_nanos()
+    )
+}
+
+/// Puts multi-line answers on their own lines so they stay readable in a report.
+fn block(answer: &str) -> String {
+    if answer.trim_end().contains('\n') {
+        format!("\n{}\n", answer.trim_end())
+    } else {
+        answer.trim_end().to_string()
+    }
+}
@@ -0,0 +1,249 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque, hash_map::Entry},
+    hash::Hash,
+    ops::Add,
+};
+
+use ahash::{HashMap, HashMapExt};
+
+/// The cheapest way found to a goal, from the start node to the goal inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    pub cost: C,
+    pub nodes: Vec<N>,
+}
+
+impl<N, C> Path<N, C> {
+    pub fn goal(&self) -> &N {
+        self.nodes.last().unwrap()
+    }
+}
+
+struct Seen<N, C> {
+    node: N,
+    parent: Option<usize>,
+    cost: C,
+    closed: bool,
+}
+
+/// Nodes seen so far, numbered in the order they were found so queues only hold indices.
+struct Visited<N, C> {
+    index: HashMap<N, usize>,
+    seen: Vec<Seen<N, C>>,
+}
+
+impl<N: Clone + Eq + Hash, C: Copy> Visited<N, C> {
+    fn new(start: N, cost: C) -> Self {
+        let mut index = HashMap::new();
+        index.insert(start.clone(), 0);
+        Self {
+            index,
+            seen: vec![Seen {
+                node: start,
+                parent: None,
+                cost,
+                closed: false,
+            }],
+        }
+    }
+
+    fn path(&self, goal: usize) -> Path<N, C> {
+        let mut nodes = Vec::new();
+        let mut current = Some(goal);
+        while let Some(i) = current {
+            nodes.push(self.seen[i].node.clone());
+            current = self.seen[i].parent;
+        }
+        nodes.reverse();
+        Path {
+            cost: self.seen[goal].cost,
+            nodes,
+        }
+    }
+}
+
+/// Breadth first search where every step costs one. Stops at the first node `goal` accepts, so
+/// several targets can be searched for at once.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Clone + Eq + Hash,
+    I: IntoIterator<Item = N>,
+{
+    let mut visited = Visited::new(start, 0);
+    let mut queue = VecDeque::from([0]);
+    while let Some(i) = queue.pop_front() {
+        let Seen { node, cost, .. } = &visited.seen[i];
+        if goal(node) {
+            return Some(visited.path(i));
+        }
+        let (node, cost) = (node.clone(), *cost);
+        for next in neighbours(&node) {
+            if let Entry::Vacant(entry) = visited.index.entry(next) {
+                let j = visited.seen.len();
+                visited.seen.push(Seen {
+                    node: entry.key().clone(),
+                    parent: Some(i),
+                    cost: cost + 1,
+                    closed: false,
+                });
+                entry.insert(j);
+                queue.push_back(j);
+            }
+        }
+    }
+    None
+}
+
+/// Cheapest path search, `neighbours` gives each next node with the cost of stepping to it.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), goal)
+}
+
+/// Dijkstra guided by `heuristic`, which must never overestimate the remaining cost to a goal.
+/// Nodes are expanded again when a cheaper way to them turns up, so the heuristic doesn't also
+/// have to be consistent, though a consistent one never needs that.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut heap = BinaryHeap::new();
+    heap.push(Reverse((heuristic(&start), C::default(), 0)));
+    let mut visited = Visited::new(start, C::default());
+    while let Some(Reverse((_, cost, i))) = heap.pop() {
+        let seen = &mut visited.seen[i];
+        if seen.closed || cost > seen.cost {
+            continue;
+        }
+        seen.closed = true;
+        if goal(&seen.node) {
+            return Some(visited.path(i));
+        }
+        let node = seen.node.clone();
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            let j = match visited.index.entry(next) {
+                Entry::Occupied(entry) => {
+                    let j = *entry.get();
+                    let seen = &mut visited.seen[j];
+                    if next_cost >= seen.cost {
+                        continue;
+                    }
+                    seen.cost = next_cost;
+                    seen.parent = Some(i);
+                    seen.closed = false;
+                    j
+                }
+                Entry::Vacant(entry) => {
+                    let j = visited.seen.len();
+                    visited.seen.push(Seen {
+                        node: entry.key().clone(),
+                        parent: Some(i),
+                        cost: next_cost,
+                        closed: false,
+                    });
+                    entry.insert(j);
+                    j
+                }
+            };
+            let estimate = next_cost + heuristic(&visited.seen[j].node);
+            heap.push(Reverse((estimate, next_cost, j)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    const MAZE: &str = "\
+        S.#.....\n\
+        .##.###.\n\
+        ....#E..\n";
+
+    fn steps(maze: &Grid<char>) -> impl FnMut(&(i32, i32)) -> Vec<(i32, i32)> + '_ {
+        move |&(x, y)| {
+            maze.neighbours4(x, y)
+                .filter(|&(x, y)| maze.get(x, y) != Some('#'))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = Grid::parse(MAZE, |c| c);
+        let end = maze.find(&'E').unwrap();
+        let path = bfs((0, 0), steps(&maze), |&pos| pos == end).unwrap();
+        assert_eq!(path.cost, 15);
+        assert_eq!(path.nodes.len(), 16);
+        assert_eq!(path.nodes[..3], [(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(path.goal(), &end);
+        assert_eq!(bfs((0, 0), steps(&maze), |_| false), None);
+    }
+
+    #[test]
+    fn test_weighted() {
+        // stepping onto a tile costs its digit
+        let costs = Grid::parse("1911\n1919\n1111\n", |c| c.to_digit(10).unwrap());
+        let neighbours = |&(x, y): &(i32, i32)| {
+            costs
+                .neighbours4(x, y)
+                .map(|(x, y)| ((x, y), costs.get(x, y).unwrap()))
+                .collect::<Vec<_>>()
+        };
+        let end = (3, 0);
+        let manhattan =
+            |&(x, y): &(i32, i32)| (end.0 - x).unsigned_abs() + (end.1 - y).unsigned_abs();
+        let path = dijkstra((0, 0), neighbours, |&pos| pos == end).unwrap();
+        assert_eq!(path.cost, 7);
+        assert_eq!(
+            astar((0, 0), neighbours, manhattan, |&pos| pos == end),
+            Some(path)
+        );
+    }
+
+    #[test]
+    fn test_inconsistent_heuristic() {
+        // the heuristic is exact from a but too low from c, so c is first closed by way of b
+        let edges = [
+            ("s", "a", 1),
+            ("s", "b", 1),
+            ("a", "c", 1),
+            ("b", "c", 2),
+            ("c", "g", 3),
+        ];
+        let neighbours = |&node: &&str| {
+            edges
+                .iter()
+                .filter(|&&(from, _, _)| from == node)
+                .map(|&(_, to, cost)| (to, cost))
+                .collect::<Vec<_>>()
+        };
+        let heuristic = |&node: &&str| if node == "a" { 4 } else { 0 };
+        let path = astar("s", neighbours, heuristic, |&node| node == "g").unwrap();
+        assert_eq!(path.cost, 5);
+        assert_eq!(path.nodes, ["s", "a", "c", "g"]);
+    }
+}
@@ -0,0 +1,163 @@
+use std::{any::type_name, fmt::Display, io::BufRead, marker::PhantomData, panic::RefUnwindSafe};
+
+use crate::{
+    bench::{self, Settings, Stage, Stats},
+    parse::{ParseError, parse_lines},
+};
+
+/// A day's puzzle. The input is parsed once and both parts answer from it.
+pub trait Solution {
+    type Input<'a>;
+
+    fn parse(input: &str) -> Result<Self::Input<'_>, ParseError>;
+
+    fn part1(_input: &Self::Input<'_>) -> impl Display {
+        Unsolved
+    }
+
+    fn part2(_input: &Self::Input<'_>) -> impl Display {
+        Unsolved
+    }
+}
+
+/// A line-oriented day that can also answer from a stream, so stdin or a huge generated input is
+/// never read whole. Both parts come from one pass, as a stream can only be read once.
+pub trait Streaming: Solution {
+    type Line;
+
+    fn line(line: &str) -> Result<Self::Line, ParseError>;
+
+    fn answers(lines: impl Iterator<Item = Self::Line>) -> (impl Display, impl Display);
+}
+
+/// Answer for a part that hasn't been solved yet.
+pub struct Unsolved;
+
+impl Display for Unsolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsolved")
+    }
+}
+
+/// Object safe view of a [`Solution`], so days with different input types fit in one registry.
+pub trait Solver: RefUnwindSafe {
+    fn solve(&self, input: &str, part: u32) -> Result<String, ParseError>;
+
+    /// Times one stage, parsing the input beforehand for the parts. Unsolved parts give `None`.
+    fn bench(
+        &self,
+        input: &str,
+        stage: Stage,
+        settings: &Settings,
+    ) -> Result<Option<Stats>, ParseError>;
+
+    /// Answers both parts from one pass over a stream, for days that can. `None` leaves the
+    /// stream unread.
+    fn stream(&self, _input: &mut dyn BufRead) -> Option<Result<[String; 2], ParseError>> {
+        None
+    }
+}
+
+struct Erased<S>(PhantomData<fn() -> S>);
+
+impl<S: Solution> Solver for Erased<S> {
+    fn solve(&self, input: &str, part: u32) -> Result<String, ParseError> {
+        let input = S::parse(input)?;
+        Ok(match part {
+            1 => S::part1(&input).to_string(),
+            2 => S::part2(&input).to_string(),
+            _ => panic!("there is no part {part}"),
+        })
+    }
+
+    fn bench(
+        &self,
+        input: &str,
+        stage: Stage,
+        settings: &Settings,
+    ) -> Result<Option<Stats>, ParseError> {
+        let parsed = S::parse(input)?;
+        Ok(match stage {
+            Stage::Parse => Some(bench::time(settings, || S::parse(input))),
+            Stage::Part1 => solved(|| S::part1(&parsed)).map(|f| bench::time(settings, f)),
+            Stage::Part2 => solved(|| S::part2(&parsed)).map(|f| bench::time(settings, f)),
+        })
+    }
+}
+
+struct Streamed<S>(PhantomData<fn() -> S>);
+
+impl<S: Streaming> Solver for Streamed<S> {
+    fn solve(&self, input: &str, part: u32) -> Result<String, ParseError> {
+        Erased::<S>(PhantomData).solve(input, part)
+    }
+
+    fn bench(
+        &self,
+        input: &str,
+        stage: Stage,
+        settings: &Settings,
+    ) -> Result<Option<Stats>, ParseError> {
+        Erased::<S>(PhantomData).bench(input, stage, settings)
+    }
+
+    /// Stops at the first bad line, which is the error even though the parts saw the lines
+    /// before it.
+    fn stream(&self, input: &mut dyn BufRead) -> Option<Result<[String; 2], ParseError>> {
+        let mut error = None;
+        let answers = {
+            let lines = parse_lines(input, S::line)
+                .map_while(|line| line.map_err(|err| error = Some(err)).ok());
+            let (part1, part2) = S::answers(lines);
+            [part1.to_string(), part2.to_string()]
+        };
+        Some(error.map_or(Ok(answers), Err))
+    }
+}
+
+/// Tells unsolved parts apart by their return type, without running them.
+fn solved<T, F: FnMut() -> T>(part: F) -> Option<F> {
+    (type_name::<T>() != type_name::<Unsolved>()).then_some(part)
+}
+
+/// One registered day of a year.
+pub struct Day {
+    pub day: u32,
+    /// Names an alternative solution registered for the same day.
+    pub variant: Option<&'static str>,
+    pub solver: Box<dyn Solver>,
+}
+
+impl Day {
+    pub fn new<S: Solution + 'static>(day: u32) -> Self {
+        Self {
+            day,
+            variant: None,
+            solver: Box::new(Erased::<S>(PhantomData)),
+        }
+    }
+
+    /// Registers a [`Streaming`] day, which `aoc run --path` can answer without reading the
+    /// whole input.
+    pub fn streaming<S: Streaming + 'static>(day: u32) -> Self {
+        Self {
+            day,
+            variant: None,
+            solver: Box::new(Streamed::<S>(PhantomData)),
+        }
+    }
+
+    pub fn variant(self, name: &'static str) -> Self {
+        Self {
+            variant: Some(name),
+            ..self
+        }
+    }
+
+    pub fn label(&self, year: u32) -> String {
+        match self.variant {
+            Some(name) => format!("{year} day {} ({name})", self.day),
+            None => format!("{year} day {}", self.day),
+        }
+    }
+}
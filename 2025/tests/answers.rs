@@ -0,0 +1,61 @@
+use std::{
+    io::Write,
+    process::{Command, Stdio},
+};
+
+use advent_of_code::answers;
+
+fn check(args: &[&str]) {
+    answers::check(env!("CARGO_BIN_EXE_aoc"), env!("CARGO_MANIFEST_DIR"), args);
+}
+
+#[test]
+fn examples() {
+    check(&["--examples"]);
+}
+
+#[test]
+fn real_inputs() {
+    check(&[]);
+}
+
+#[test]
+fn streams_stdin() {
+    let mut aoc = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "-d", "1", "--path", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let example = include_str!("../input/day1_test.txt");
+    aoc.stdin
+        .take()
+        .unwrap()
+        .write_all(example.as_bytes())
+        .unwrap();
+    let output = aoc.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2025 day 1 part 1: 3\n2025 day 1 part 2: 6\n"
+    );
+}
+
+#[test]
+fn runs_other_years() {
+    let output = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--year", "2024", "-d", "2", "--variant", "example"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "2024 day 2 part 1: 2\n2024 day 2 part 2: 4\n"
+    );
+
+    let status = Command::new(env!("CARGO_BIN_EXE_aoc"))
+        .args(["run", "--year", "1999"])
+        .status()
+        .unwrap();
+    assert_eq!(status.code(), Some(2));
+}